@@ -0,0 +1,139 @@
+//! EIP-712 hashing of LooksRare maker orders, without `std`.
+//!
+//! This is the hashing the `looksrare` crate signs with, split out so that
+//! environments without `std`, like signing enclaves, produce exactly the same
+//! digests. Values are plain byte arrays: addresses are 20 bytes and `uint256`s
+//! are 32 big-endian bytes.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use tiny_keccak::{Hasher, Keccak};
+
+pub type Address = [u8; 20];
+pub type Word = [u8; 32];
+
+pub const DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+pub const MAKER_ORDER_TYPE: &str = "MakerOrder(bool isOrderAsk,address signer,address collection,uint256 price,uint256 tokenId,uint256 amount,address strategy,address currency,uint256 nonce,uint256 startTime,uint256 endTime,uint256 minPercentageToAsk,bytes params)";
+
+pub fn keccak256(bytes: &[u8]) -> Word {
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    finish(hasher)
+}
+
+/// The EIP-712 domain orders are signed under.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Domain<'a> {
+    pub name: &'a str,
+    pub version: &'a str,
+    pub chain_id: u64,
+    pub verifying_contract: Address,
+}
+
+impl Domain<'_> {
+    pub fn separator(&self) -> Word {
+        let mut hasher = Keccak::v256();
+        hasher.update(&keccak256(DOMAIN_TYPE.as_bytes()));
+        hasher.update(&keccak256(self.name.as_bytes()));
+        hasher.update(&keccak256(self.version.as_bytes()));
+        hasher.update(&uint(self.chain_id));
+        hasher.update(&address(self.verifying_contract));
+        finish(hasher)
+    }
+}
+
+/// A maker order as the exchange hashes it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MakerOrder {
+    pub is_order_ask: bool,
+    pub signer: Address,
+    pub collection: Address,
+    pub price: Word,
+    pub token_id: Word,
+    pub amount: Word,
+    pub strategy: Address,
+    pub currency: Address,
+    pub nonce: Word,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub min_percentage_to_ask: u64,
+    pub params: Vec<u8>,
+}
+
+impl MakerOrder {
+    /// `hashStruct` of the order, as computed by the exchange's `OrderTypes.hash`.
+    pub fn struct_hash(&self) -> Word {
+        let mut hasher = Keccak::v256();
+        hasher.update(&keccak256(MAKER_ORDER_TYPE.as_bytes()));
+        hasher.update(&uint(self.is_order_ask as u64));
+        hasher.update(&address(self.signer));
+        hasher.update(&address(self.collection));
+        hasher.update(&self.price);
+        hasher.update(&self.token_id);
+        hasher.update(&self.amount);
+        hasher.update(&address(self.strategy));
+        hasher.update(&address(self.currency));
+        hasher.update(&self.nonce);
+        hasher.update(&uint(self.start_time));
+        hasher.update(&uint(self.end_time));
+        hasher.update(&uint(self.min_percentage_to_ask));
+        hasher.update(&keccak256(&self.params));
+        finish(hasher)
+    }
+
+    /// The digest the signer signs: `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(order))`.
+    pub fn digest(&self, domain_separator: &Word) -> Word {
+        digest(domain_separator, &self.struct_hash())
+    }
+}
+
+pub fn digest(domain_separator: &Word, struct_hash: &Word) -> Word {
+    let mut hasher = Keccak::v256();
+    hasher.update(&[0x19, 0x01]);
+    hasher.update(domain_separator);
+    hasher.update(struct_hash);
+    finish(hasher)
+}
+
+fn uint(value: u64) -> Word {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn address(value: Address) -> Word {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&value);
+    word
+}
+
+fn finish(hasher: Keccak) -> Word {
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_empty_input() {
+        assert_eq!(
+            keccak256(&[]),
+            [
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0, 0xe5, 0x00,
+                0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+            ]
+        );
+    }
+
+    #[test]
+    fn pads_words_on_the_left() {
+        assert_eq!(uint(1)[31], 1);
+        assert_eq!(address([0xff; 20])[..12], [0u8; 12]);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::amount::Amount;
+use crate::api::{LooksRareApi, OrdersRequest, Status};
+use crate::composite::{partial, subtask, SubtaskError};
+use crate::contracts::{ERC20, ERC721};
+use crate::types::Account;
+use crate::ClientError;
+use ethers::prelude::{Address, Middleware, U256};
+use futures::future::try_join_all;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Profile data combined with trading activity and balances for one address.
+///
+/// Each part is fetched independently; parts that failed or timed out are
+/// `None` and their errors are listed in `errors`.
+#[derive(Debug)]
+pub struct AccountOverview {
+    pub address: Address,
+    pub account: Option<Account>,
+    pub active_asks: Option<u64>,
+    pub active_bids: Option<u64>,
+    /// Tokens held across the collections passed to `get_account_overview`.
+    pub owned_tokens: Option<U256>,
+    pub looks_balance: Option<Amount>,
+    pub weth_balance: Option<Amount>,
+    pub errors: Vec<SubtaskError>,
+}
+
+impl AccountOverview {
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Assembles an `AccountOverview`, running the API and provider calls concurrently,
+/// each bounded by `timeout`.
+pub async fn get_account_overview<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    address: Address,
+    collections: &[Address],
+    timeout: Duration,
+) -> AccountOverview {
+    let network = api.network();
+
+    let (account, active_asks, active_bids, owned_tokens, looks_balance, weth_balance) = futures::join!(
+        subtask("account", timeout, api.get_account(address)),
+        subtask("active asks", timeout, count_valid_orders(api, address, true)),
+        subtask("active bids", timeout, count_valid_orders(api, address, false)),
+        subtask("owned tokens", timeout, owned_token_count(provider.clone(), address, collections)),
+        subtask("looks balance", timeout, erc20_balance(provider.clone(), network.looks(), address)),
+        subtask("weth balance", timeout, erc20_balance(provider.clone(), network.weth(), address)),
+    );
+
+    let mut errors = vec![];
+
+    AccountOverview {
+        address,
+        account: partial(account, &mut errors),
+        active_asks: partial(active_asks, &mut errors),
+        active_bids: partial(active_bids, &mut errors),
+        owned_tokens: partial(owned_tokens, &mut errors),
+        looks_balance: partial(looks_balance, &mut errors),
+        weth_balance: partial(weth_balance, &mut errors),
+        errors,
+    }
+}
+
+async fn count_valid_orders(
+    api: &LooksRareApi,
+    signer: Address,
+    is_order_ask: bool,
+) -> Result<u64, ClientError> {
+    let req = OrdersRequest {
+        is_order_ask: Some(is_order_ask),
+        signer: Some(signer),
+        status: Some(vec![Status::Valid]),
+        ..Default::default()
+    };
+
+    let orders = api.get_all_orders(req).await?;
+
+    Ok(orders.len() as u64)
+}
+
+async fn owned_token_count<M: Middleware>(
+    provider: Arc<M>,
+    owner: Address,
+    collections: &[Address],
+) -> Result<U256, ClientError> {
+    let balances = try_join_all(collections.iter().map(|collection| {
+        let contract = ERC721::new(*collection, provider.clone());
+        async move { contract.balance_of(owner).call().await }
+    }))
+    .await?;
+
+    Ok(balances.into_iter().fold(U256::zero(), |total, balance| total + balance))
+}
+
+async fn erc20_balance<M: Middleware>(
+    provider: Arc<M>,
+    token: Address,
+    owner: Address,
+) -> Result<Amount, ClientError> {
+    let contract = ERC20::new(token, provider);
+    let balance_of = contract.balance_of(owner);
+    let decimals = contract.decimals();
+    let (balance, decimals) = futures::try_join!(balance_of.call(), decimals.call())?;
+
+    Ok(Amount::new(balance, decimals))
+}
@@ -0,0 +1,114 @@
+use ethers::prelude::U256;
+use std::fmt;
+
+/// Decimals of ETH, WETH and LOOKS.
+pub const ETHER_DECIMALS: u8 = 18;
+
+/// A token quantity or currency amount together with the decimals it is expressed in.
+///
+/// Keeping the decimals next to the raw value means a 6-decimal currency can't
+/// silently be read as if it had 18.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Amount {
+    pub value: U256,
+    pub decimals: u8,
+}
+
+impl Amount {
+    pub fn new(value: U256, decimals: u8) -> Self {
+        Self { value, decimals }
+    }
+
+    /// An amount in wei of an 18-decimal currency.
+    pub fn wei(value: U256) -> Self {
+        Self::new(value, ETHER_DECIMALS)
+    }
+
+    /// A whole-unit quantity, e.g. of ERC-1155 tokens.
+    pub fn units(value: U256) -> Self {
+        Self::new(value, 0)
+    }
+
+    /// Parses a decimal string such as `"1.25"`. Fails on more fractional digits than `decimals`.
+    pub fn parse(s: &str, decimals: u8) -> Option<Self> {
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if fraction.len() > usize::from(decimals) || (whole.is_empty() && fraction.is_empty()) {
+            return None;
+        }
+
+        let digits = format!("{}{:0<width$}", whole, fraction, width = usize::from(decimals));
+        let value = U256::from_dec_str(&digits).ok()?;
+
+        Some(Self::new(value, decimals))
+    }
+
+    /// The same amount in `decimals`, or `None` if that would drop precision or overflow.
+    pub fn rescale(&self, decimals: u8) -> Option<Self> {
+        let value = if decimals >= self.decimals {
+            self.value
+                .checked_mul(U256::exp10(usize::from(decimals - self.decimals)))?
+        } else {
+            let factor = U256::exp10(usize::from(self.decimals - decimals));
+            if !(self.value % factor).is_zero() {
+                return None;
+            }
+            self.value / factor
+        };
+
+        Some(Self::new(value, decimals))
+    }
+
+    /// Adds two amounts with the same decimals.
+    pub fn checked_add(&self, other: &Amount) -> Option<Self> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+
+        Some(Self::new(self.value.checked_add(other.value)?, self.decimals))
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.value.to_string();
+        let decimals = usize::from(self.decimals);
+        if decimals == 0 {
+            return write!(f, "{}", digits);
+        }
+
+        let padded = format!("{:0>width$}", digits, width = decimals + 1);
+        let (whole, fraction) = padded.split_at(padded.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            write!(f, "{}", whole)
+        } else {
+            write!(f, "{}.{}", whole, fraction)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_displays() {
+        let amount = Amount::parse("1.25", 6).unwrap();
+        assert_eq!(amount.value, U256::from(1_250_000));
+        assert_eq!(amount.to_string(), "1.25");
+
+        assert_eq!(Amount::wei(U256::from(5)).to_string(), "0.000000000000000005");
+        assert_eq!(Amount::units(U256::from(3)).to_string(), "3");
+        assert!(Amount::parse("1.0000001", 6).is_none());
+    }
+
+    #[test]
+    fn rescales_without_losing_precision() {
+        let usdc = Amount::parse("2.5", 6).unwrap();
+        assert_eq!(usdc.rescale(18).unwrap(), Amount::parse("2.5", 18).unwrap());
+        assert_eq!(usdc.rescale(1).unwrap().value, U256::from(25));
+        assert!(usdc.rescale(0).is_none());
+        assert!(usdc.checked_add(&Amount::wei(U256::one())).is_none());
+    }
+}
@@ -0,0 +1,507 @@
+use crate::api::{EventType, EventsRequest, LooksRareApi, OrdersRequest, Pagination, Status};
+use crate::constants;
+use crate::contracts::ERC721;
+use crate::ownership::ownership_snapshot;
+use crate::tape::{events_in_range, sales_in_range};
+use crate::types::{Event, Order};
+use crate::ClientError;
+use chrono::{DateTime, Utc};
+use ethers::prelude::{Address, Middleware, I256, U256, U64};
+use futures::{stream, StreamExt, TryStreamExt};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CONCURRENT_CALLS: usize = 32;
+
+const DAY: u64 = 86_400;
+const WEEK: u64 = 7 * DAY;
+const MONTH: u64 = 30 * DAY;
+const YEAR: u64 = 365 * DAY;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HolderDistribution {
+    pub unique_holders: usize,
+    pub total_tokens: usize,
+    /// Percentage of tokens held by the ten largest holders.
+    pub top_10_share: f64,
+    pub holding_durations: HoldingDurations,
+}
+
+/// Token counts bucketed by how long the current owner has held them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HoldingDurations {
+    pub under_1_day: usize,
+    pub under_1_week: usize,
+    pub under_1_month: usize,
+    pub under_1_year: usize,
+    pub over_1_year: usize,
+}
+
+impl HoldingDurations {
+    fn record(&mut self, held_for: u64) {
+        match held_for {
+            d if d < DAY => self.under_1_day += 1,
+            d if d < WEEK => self.under_1_week += 1,
+            d if d < MONTH => self.under_1_month += 1,
+            d if d < YEAR => self.under_1_year += 1,
+            _ => self.over_1_year += 1,
+        }
+    }
+}
+
+/// Computes holder statistics for a collection from its current ownership and `Transfer` history.
+pub async fn holder_distribution<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+) -> Result<HolderDistribution, ClientError> {
+    let snapshot = ownership_snapshot(provider.clone(), collection).await?;
+
+    let contract = ERC721::new(collection, provider.clone());
+    let transfers = contract
+        .transfer_filter()
+        .from_block(0u64)
+        .query_with_meta()
+        .await?;
+
+    // logs are returned in chain order, so the last write wins
+    let mut acquired_in: HashMap<U256, U64> = HashMap::new();
+    for (transfer, meta) in transfers {
+        acquired_in.insert(transfer.token_id, meta.block_number);
+    }
+
+    let mut blocks: Vec<U64> = acquired_in.values().copied().collect();
+    blocks.sort();
+    blocks.dedup();
+
+    let timestamps: HashMap<U64, u64> = stream::iter(blocks)
+        .map(|number| {
+            let provider = provider.clone();
+            async move {
+                let block = provider
+                    .get_block(number)
+                    .await
+                    .map_err(|e| ClientError::ProviderError(e.to_string()))?;
+                let timestamp = block.map(|block| block.timestamp.low_u64()).unwrap_or_default();
+                Ok::<_, ClientError>((number, timestamp))
+            }
+        })
+        .buffered(CONCURRENT_CALLS)
+        .try_collect()
+        .await?;
+
+    let acquired_at: HashMap<U256, u64> = acquired_in
+        .into_iter()
+        .filter_map(|(token_id, number)| timestamps.get(&number).map(|timestamp| (token_id, *timestamp)))
+        .collect();
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    Ok(distribution(&snapshot, &acquired_at, now))
+}
+
+fn distribution(
+    snapshot: &HashMap<Address, Vec<U256>>,
+    acquired_at: &HashMap<U256, u64>,
+    now: u64,
+) -> HolderDistribution {
+    let mut holdings: Vec<usize> = snapshot.values().map(|tokens| tokens.len()).collect();
+    holdings.sort_unstable_by(|a, b| b.cmp(a));
+
+    let total_tokens: usize = holdings.iter().sum();
+    let top_10: usize = holdings.iter().take(10).sum();
+    let top_10_share = if total_tokens == 0 {
+        0.0
+    } else {
+        top_10 as f64 * 100.0 / total_tokens as f64
+    };
+
+    let mut holding_durations = HoldingDurations::default();
+    for token_id in snapshot.values().flatten() {
+        if let Some(acquired) = acquired_at.get(token_id) {
+            holding_durations.record(now.saturating_sub(*acquired));
+        }
+    }
+
+    HolderDistribution {
+        unique_holders: holdings.len(),
+        total_tokens,
+        top_10_share,
+        holding_durations,
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CollectionLiquidity {
+    pub total_supply: u64,
+    /// Distinct tokens with at least one valid ask.
+    pub listed_tokens: u64,
+    /// Percentage of the supply that is listed.
+    pub listed_supply_percent: f64,
+    pub ask_depth: u64,
+    pub bid_depth: u64,
+    /// Valid bids per valid ask.
+    pub bid_ask_ratio: f64,
+    /// Mean seconds between a listing going live and its sale, over the most recent sales.
+    pub average_sale_time: Option<u64>,
+}
+
+/// Computes listing and depth metrics for a collection from its valid orders and recent sales.
+pub async fn collection_liquidity(
+    api: &LooksRareApi,
+    collection: Address,
+) -> Result<CollectionLiquidity, ClientError> {
+    let sales = EventsRequest {
+        collection: Some(collection),
+        event_type: Some(EventType::Sale),
+        pagination: Some(Pagination {
+            first: Some(constants::MAX_ORDERS_PER_PAGE),
+            cursor: None,
+        }),
+        ..Default::default()
+    };
+
+    let (stats, asks, bids, sales) = futures::try_join!(
+        api.get_collection_stats(collection),
+        api.get_all_orders(valid_orders(collection, true)),
+        api.get_all_orders(valid_orders(collection, false)),
+        api.get_events(sales),
+    )?;
+
+    let total_supply = stats.total_supply;
+
+    Ok(liquidity(total_supply, &asks, &bids, &sales))
+}
+
+fn valid_orders(collection: Address, is_order_ask: bool) -> OrdersRequest {
+    OrdersRequest {
+        is_order_ask: Some(is_order_ask),
+        collection: Some(collection),
+        status: Some(vec![Status::Valid]),
+        ..Default::default()
+    }
+}
+
+fn liquidity(total_supply: u64, asks: &[Order], bids: &[Order], sales: &[Event]) -> CollectionLiquidity {
+    let listed_tokens = asks
+        .iter()
+        .map(|order| order.token_id.as_str())
+        .collect::<HashSet<&str>>()
+        .len() as u64;
+
+    let listed_supply_percent = if total_supply == 0 {
+        0.0
+    } else {
+        listed_tokens as f64 * 100.0 / total_supply as f64
+    };
+
+    let ask_depth = asks.len() as u64;
+    let bid_depth = bids.len() as u64;
+    let bid_ask_ratio = if ask_depth == 0 {
+        0.0
+    } else {
+        bid_depth as f64 / ask_depth as f64
+    };
+
+    let sale_times: Vec<u64> = sales
+        .iter()
+        .filter_map(|sale| {
+            let order = sale.order.as_ref()?;
+            let sold_at = u64::try_from(sale.created_at.timestamp()).ok()?;
+            sold_at.checked_sub(order.start_time)
+        })
+        .collect();
+
+    let average_sale_time = if sale_times.is_empty() {
+        None
+    } else {
+        Some(sale_times.iter().sum::<u64>() / sale_times.len() as u64)
+    };
+
+    CollectionLiquidity {
+        total_supply,
+        listed_tokens,
+        listed_supply_percent,
+        ask_depth,
+        bid_depth,
+        bid_ask_ratio,
+        average_sale_time,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraderStats {
+    pub address: Address,
+    pub buys: u64,
+    pub sells: u64,
+    pub buy_volume: U256,
+    pub sell_volume: U256,
+    /// Sale price less purchase price, summed over sales of tokens the trader
+    /// bought within the same range. Fees and gas are not deducted.
+    pub realized_profit: I256,
+}
+
+impl TraderStats {
+    fn new(address: Address) -> Self {
+        Self {
+            address,
+            buys: 0,
+            sells: 0,
+            buy_volume: U256::zero(),
+            sell_volume: U256::zero(),
+            realized_profit: I256::zero(),
+        }
+    }
+}
+
+/// The same traders ranked three ways.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraderLeaderboard {
+    /// By buy volume, highest first.
+    pub buyers: Vec<TraderStats>,
+    /// By sell volume, highest first.
+    pub sellers: Vec<TraderStats>,
+    /// By realized profit, highest first.
+    pub earners: Vec<TraderStats>,
+}
+
+/// Ranks the buyers and sellers of a collection by their sales over a time range.
+pub async fn top_traders(
+    api: &LooksRareApi,
+    collection: Address,
+    range: Range<DateTime<Utc>>,
+) -> Result<TraderLeaderboard, ClientError> {
+    let sales = sales_in_range(api, collection, &range).await?;
+
+    Ok(leaderboard(&sales))
+}
+
+/// Expects sales oldest first, so each resale is matched with the purchase before it.
+fn leaderboard(sales: &[Event]) -> TraderLeaderboard {
+    let mut traders: HashMap<Address, TraderStats> = HashMap::new();
+    let mut cost_basis: HashMap<(Address, String), U256> = HashMap::new();
+
+    for sale in sales {
+        let (order, token, buyer) = match (&sale.order, &sale.token, sale.to) {
+            (Some(order), Some(token), Some(buyer)) => (order, token, buyer),
+            _ => continue,
+        };
+        let price = match U256::from_dec_str(&order.price) {
+            Ok(price) => price,
+            Err(_) => continue,
+        };
+
+        let seller = traders.entry(sale.from).or_insert_with(|| TraderStats::new(sale.from));
+        seller.sells += 1;
+        seller.sell_volume += price;
+        if let Some(paid) = cost_basis.remove(&(sale.from, token.token_id.clone())) {
+            seller.realized_profit += I256::from_raw(price) - I256::from_raw(paid);
+        }
+
+        let buyer_stats = traders.entry(buyer).or_insert_with(|| TraderStats::new(buyer));
+        buyer_stats.buys += 1;
+        buyer_stats.buy_volume += price;
+        cost_basis.insert((buyer, token.token_id.clone()), price);
+    }
+
+    let ranked = |key: fn(&TraderStats) -> I256| {
+        let mut ranked: Vec<TraderStats> = traders.values().cloned().collect();
+        ranked.sort_by(|a, b| key(b).cmp(&key(a)).then(a.address.cmp(&b.address)));
+        ranked
+    };
+
+    TraderLeaderboard {
+        buyers: ranked(|trader| I256::from_raw(trader.buy_volume)).into_iter().filter(|t| t.buys > 0).collect(),
+        sellers: ranked(|trader| I256::from_raw(trader.sell_volume)).into_iter().filter(|t| t.sells > 0).collect(),
+        earners: ranked(|trader| trader.realized_profit),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PostMintMetrics {
+    pub minted: u64,
+    /// Minted tokens listed within the window after their mint.
+    pub listed: u64,
+    pub listed_percent: f64,
+    /// Listed tokens per hour of the window.
+    pub listings_per_hour: f64,
+    /// Minted tokens sold within the window after their mint.
+    pub first_sales: u64,
+    /// Mean percentage of the first sale price over the mint price.
+    pub average_first_sale_premium: Option<f64>,
+}
+
+/// Measures how quickly tokens minted during `minted` were listed and sold in
+/// the `window` after each mint, with premiums relative to `mint_price`.
+pub async fn post_mint_metrics(
+    api: &LooksRareApi,
+    collection: Address,
+    minted: Range<DateTime<Utc>>,
+    mint_price: U256,
+    window: std::time::Duration,
+) -> Result<PostMintMetrics, ClientError> {
+    let window = chrono::Duration::seconds(window.as_secs() as i64);
+    let after = minted.start..minted.end + window;
+
+    let (mints, lists, sales) = futures::try_join!(
+        events_in_range(api, collection, EventType::Mint, &minted),
+        events_in_range(api, collection, EventType::List, &after),
+        events_in_range(api, collection, EventType::Sale, &after),
+    )?;
+
+    Ok(post_mint(&mints, &lists, &sales, mint_price, window))
+}
+
+fn post_mint(
+    mints: &[Event],
+    lists: &[Event],
+    sales: &[Event],
+    mint_price: U256,
+    window: chrono::Duration,
+) -> PostMintMetrics {
+    let token_of = |event: &Event| event.token.as_ref().map(|token| token.token_id.clone());
+
+    let minted_at: HashMap<String, DateTime<Utc>> = mints
+        .iter()
+        .filter_map(|mint| Some((token_of(mint)?, mint.created_at)))
+        .collect();
+    let within_window = |event: &Event| {
+        let token = token_of(event)?;
+        let minted = minted_at.get(&token)?;
+        (event.created_at >= *minted && event.created_at - *minted <= window).then_some(token)
+    };
+
+    let listed: HashSet<String> = lists.iter().filter_map(within_window).collect();
+
+    // sales are oldest first, so the first one seen for a token is its first sale
+    let mut first_sales: HashMap<String, U256> = HashMap::new();
+    for sale in sales {
+        let price = sale.order.as_ref().and_then(|order| U256::from_dec_str(&order.price).ok());
+        if let (Some(token), Some(price)) = (within_window(sale), price) {
+            first_sales.entry(token).or_insert(price);
+        }
+    }
+
+    let minted = minted_at.len() as u64;
+    let hours = window.num_seconds() as f64 / 3600.0;
+    let premiums: Vec<f64> = if mint_price.is_zero() {
+        vec![]
+    } else {
+        let mint = mint_price.to_string().parse::<f64>().unwrap_or_default();
+        first_sales
+            .values()
+            .map(|price| (price.to_string().parse::<f64>().unwrap_or_default() - mint) * 100.0 / mint)
+            .collect()
+    };
+
+    PostMintMetrics {
+        minted,
+        listed: listed.len() as u64,
+        listed_percent: if minted == 0 { 0.0 } else { listed.len() as f64 * 100.0 / minted as f64 },
+        listings_per_hour: if hours == 0.0 { 0.0 } else { listed.len() as f64 / hours },
+        first_sales: first_sales.len() as u64,
+        average_first_sale_premium: if premiums.is_empty() {
+            None
+        } else {
+            Some(premiums.iter().sum::<f64>() / premiums.len() as f64)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn computes_holder_distribution() {
+        let whale = Address::from_low_u64_be(1);
+        let mut snapshot: HashMap<Address, Vec<U256>> = HashMap::new();
+        snapshot.insert(whale, (0..10u64).map(U256::from).collect());
+        for i in 10..20u64 {
+            snapshot.insert(Address::from_low_u64_be(i), vec![U256::from(i)]);
+        }
+
+        let now = 10 * YEAR;
+        let mut acquired_at: HashMap<U256, u64> = HashMap::new();
+        acquired_at.insert(U256::from(0), now - 60);
+        acquired_at.insert(U256::from(1), now - 2 * DAY);
+        acquired_at.insert(U256::from(2), now - 2 * WEEK);
+        acquired_at.insert(U256::from(3), now - 2 * MONTH);
+        acquired_at.insert(U256::from(4), now - 2 * YEAR);
+
+        let stats = distribution(&snapshot, &acquired_at, now);
+
+        assert_eq!(stats.unique_holders, 11);
+        assert_eq!(stats.total_tokens, 20);
+        // the whale's 10 tokens plus nine single-token holders
+        assert_eq!(stats.top_10_share, 95.0);
+        assert_eq!(stats.holding_durations, HoldingDurations {
+            under_1_day: 1,
+            under_1_week: 1,
+            under_1_month: 1,
+            under_1_year: 1,
+            over_1_year: 1,
+        });
+    }
+
+    #[test]
+    fn empty_collection_has_no_share() {
+        let stats = distribution(&HashMap::new(), &HashMap::new(), 0);
+        assert_eq!(stats, HolderDistribution::default());
+    }
+
+    #[test]
+    fn computes_collection_liquidity() {
+        let mut asks = vec![fixtures::order(), fixtures::order(), fixtures::order()];
+        asks[2].token_id = String::from("2");
+        let mut bids = vec![fixtures::order()];
+        bids[0].is_order_ask = false;
+
+        // the fixture order starts at 1667747434, so the sale lands an hour later
+        let sales = vec![fixtures::event("SALE", "2022-11-06T16:10:34Z")];
+
+        let stats = liquidity(10, &asks, &bids, &sales);
+
+        assert_eq!(stats.listed_tokens, 2);
+        assert_eq!(stats.listed_supply_percent, 20.0);
+        assert_eq!(stats.ask_depth, 3);
+        assert_eq!(stats.bid_depth, 1);
+        assert_eq!(stats.average_sale_time, Some(3600));
+    }
+
+    #[test]
+    fn ranks_traders_with_realized_profit() {
+        let alice = Address::from_low_u64_be(1);
+        let bob = Address::from_low_u64_be(2);
+        let carol = Address::from_low_u64_be(3);
+        let board = leaderboard(&[fixtures::sale(alice, bob, "100"), fixtures::sale(bob, carol, "150")]);
+
+        assert_eq!(board.buyers[0].address, carol);
+        assert_eq!(board.buyers.len(), 2);
+        assert_eq!(board.sellers[0].address, bob);
+        assert_eq!(board.earners[0].address, bob);
+        assert_eq!(board.earners[0].realized_profit, I256::from_raw(U256::from(50)));
+        assert_eq!(board.earners[0].buy_volume, U256::from(100));
+    }
+
+    #[test]
+    fn measures_post_mint_activity() {
+        let at = fixtures::event_at;
+        let mints = vec![at("MINT", "2022-11-06T12:00:00Z", "1", "0"), at("MINT", "2022-11-06T12:00:00Z", "2", "0")];
+        let lists = vec![at("LIST", "2022-11-06T13:00:00Z", "1", "0"), at("LIST", "2022-11-07T13:00:00Z", "2", "0")];
+        let sales = vec![at("SALE", "2022-11-06T14:00:00Z", "1", "150"), at("SALE", "2022-11-06T15:00:00Z", "1", "300")];
+
+        let metrics = post_mint(&mints, &lists, &sales, U256::from(100), chrono::Duration::hours(6));
+
+        assert_eq!(metrics.minted, 2);
+        assert_eq!(metrics.listed, 1);
+        assert_eq!(metrics.listed_percent, 50.0);
+        assert_eq!(metrics.first_sales, 1);
+        assert_eq!(metrics.average_first_sale_premium, Some(50.0));
+    }
+}
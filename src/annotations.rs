@@ -0,0 +1,167 @@
+//! Local tags and notes on orders, e.g. the strategy or batch that placed them.
+
+use ethers::prelude::H256;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// What is known locally about one order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// E.g. `strategy:auto-rebid` or `batch:42`.
+    pub tags: BTreeSet<String>,
+    pub note: Option<String>,
+}
+
+impl Annotation {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+}
+
+/// One line of the store file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    order_hash: H256,
+    annotation: Annotation,
+}
+
+/// Annotations keyed by order hash, kept in memory and, if opened from a file,
+/// saved there as JSON lines on every change.
+///
+/// Attach it with `LooksRareApi::annotations` for trackers and reports to
+/// pick the annotations up, e.g. `watch::Outbid` and `tape::Trade`.
+#[derive(Debug, Default)]
+pub struct AnnotationStore {
+    path: Option<PathBuf>,
+    annotations: Mutex<HashMap<H256, Annotation>>,
+}
+
+impl AnnotationStore {
+    /// A store that is never saved.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the store at `path`, creating it on the first change. Later lines
+    /// for an order replace earlier ones.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let contents = match path.exists() {
+            true => fs::read_to_string(&path)?,
+            false => String::new(),
+        };
+
+        Ok(Self {
+            annotations: Mutex::new(parse(&contents)?),
+            path: Some(path),
+        })
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn get(&self, order_hash: H256) -> Option<Annotation> {
+        self.annotations.lock().unwrap().get(&order_hash).cloned()
+    }
+
+    /// Adds `tag` to the order's tags.
+    pub fn tag(&self, order_hash: H256, tag: impl Into<String>) -> io::Result<Annotation> {
+        let tag = tag.into();
+        self.update(order_hash, |annotation| {
+            annotation.tags.insert(tag);
+        })
+    }
+
+    /// Replaces the order's note.
+    pub fn note(&self, order_hash: H256, note: impl Into<String>) -> io::Result<Annotation> {
+        let note = note.into();
+        self.update(order_hash, |annotation| annotation.note = Some(note))
+    }
+
+    /// The orders tagged `tag`, in no particular order.
+    pub fn tagged(&self, tag: &str) -> Vec<H256> {
+        self.annotations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, annotation)| annotation.has_tag(tag))
+            .map(|(order_hash, _)| *order_hash)
+            .collect()
+    }
+
+    fn update(&self, order_hash: H256, change: impl FnOnce(&mut Annotation)) -> io::Result<Annotation> {
+        let mut annotations = self.annotations.lock().unwrap();
+        let mut annotation = annotations.get(&order_hash).cloned().unwrap_or_default();
+        change(&mut annotation);
+
+        if let Some(path) = &self.path {
+            let entry = Entry {
+                order_hash,
+                annotation: annotation.clone(),
+            };
+            let mut line = serde_json::to_string(&entry)?;
+            line.push('\n');
+
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(line.as_bytes())?;
+            file.flush()?;
+        }
+
+        annotations.insert(order_hash, annotation.clone());
+        Ok(annotation)
+    }
+}
+
+fn parse(contents: &str) -> io::Result<HashMap<H256, Annotation>> {
+    let mut annotations = HashMap::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let entry: Entry = serde_json::from_str(line).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid annotation on line {}: {}", number + 1, e))
+        })?;
+        annotations.insert(entry.order_hash, entry.annotation);
+    }
+
+    Ok(annotations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_annotations_survive_reopening() {
+        let path = std::env::temp_dir().join(format!("looksrare-annotations-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let (bid, ask) = (H256::repeat_byte(1), H256::repeat_byte(2));
+
+        let store = AnnotationStore::open(&path).unwrap();
+        store.tag(bid, "strategy:auto-rebid").unwrap();
+        store.tag(bid, "batch:42").unwrap();
+        store.note(bid, "first try").unwrap();
+        store.note(bid, "second try").unwrap();
+        store.tag(ask, "batch:42").unwrap();
+
+        let reopened = AnnotationStore::open(&path).unwrap();
+        let annotation = reopened.get(bid).unwrap();
+        assert!(annotation.has_tag("strategy:auto-rebid"));
+        assert_eq!(annotation.note.as_deref(), Some("second try"));
+
+        let mut batch = reopened.tagged("batch:42");
+        batch.sort();
+        assert_eq!(batch, vec![bid, ask]);
+        assert_eq!(reopened.get(H256::zero()), None);
+
+        let _ = fs::remove_file(&path);
+    }
+}
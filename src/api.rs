@@ -1,145 +1,794 @@
-use crate::types::{Account, CollectionInformation, CollectionRewards, CollectionStats, Network, Order};
+use crate::annotations::{Annotation, AnnotationStore};
+use crate::audit::{AuditAction, AuditError, AuditLog, AuditRecord, OrderRef};
+use crate::constants;
+use crate::limiter::{Priority, RateLimitStatus, RateLimiter, ServerRateLimit, ShedPolicy};
+use crate::order::SignedOrder;
+use crate::recording::{Recorder, Recording};
+use crate::retry::{self, RetryPolicy};
+use crate::secret::{Redacted, Secret};
+use crate::signer::DomainCache;
+use crate::ClientError;
+use crate::v1;
+use crate::types::{Account, Collection, CollectionInformation, CollectionRewards, CollectionStats, Currency, Event, Network, Order, Rewards, Strategy, Token};
+pub use crate::types::EventType;
 use thiserror::Error;
 use ethers::{
-    prelude::Address, 
+    prelude::{Address, Middleware, H256, U256},
 };
-use reqwest::{Client, ClientBuilder};
+use reqwest::{
+    header::{HeaderValue, ETAG, IF_NONE_MATCH},
+    Client, ClientBuilder, Method, Request, RequestBuilder, Response, StatusCode,
+};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// A LooksRare API client.
+///
+/// Clones share the HTTP client, caches and rate limiter; see `with_priority`.
+#[derive(Clone, Debug)]
 pub struct LooksRareApi {
     client: Client,
     network: Network,
+    base_url: Option<String>,
+    api_key: ApiKey,
+    retry: RetryPolicy,
+    etags: Arc<Mutex<HashMap<String, String>>>,
+    recorder: Option<Arc<Recorder>>,
+    audit: Option<Arc<AuditLog>>,
+    annotations: Option<Arc<AnnotationStore>>,
+    parse_mode: ParseMode,
+    limiter: Option<Arc<RateLimiter>>,
+    server_limit: Arc<Mutex<ServerRateLimit>>,
+    priority: Priority,
+    domains: Arc<DomainCache>,
 }
 
-impl LooksRareApi {
+/// Configures the network and HTTP client of a `LooksRareApi`.
+#[derive(Clone, Debug)]
+pub struct LooksRareApiBuilder {
+    network: Network,
+    base_url: Option<String>,
+    timeout: Option<Duration>,
+    api_key: ApiKey,
+    retry: RetryPolicy,
+    rate_limit: Option<(u32, Duration)>,
+}
+
+/// The API key shared by a client and its clones, so rotating it reaches all of them.
+#[derive(Clone, Default)]
+struct ApiKey(Arc<RwLock<Option<Secret>>>);
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.read().unwrap().fmt(f)
+    }
+}
+
+impl Default for LooksRareApiBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LooksRareApiBuilder {
     pub fn new() -> Self {
-        let builder = ClientBuilder::new();
+        Self {
+            network: Network::Mainnet,
+            base_url: None,
+            timeout: None,
+            api_key: ApiKey::default(),
+            retry: RetryPolicy::default(),
+            rate_limit: Some((constants::DEFAULT_REQUESTS_PER_MINUTE, Duration::from_secs(60))),
+        }
+    }
+
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Sends requests to `url`, e.g. a caching proxy, instead of the network's own
+    /// API host. Orders are still signed for the network, so `build` refuses
+    /// another network's LooksRare host, e.g. `https://api-goerli.looksrare.org`.
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = Some(url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Sends `key` in the `X-Looks-Api-Key` header of every request, for the
+    /// higher rate limits of authenticated clients. The key is kept as a
+    /// `Secret`, e.g. one read with `Secret::from_env`.
+    pub fn api_key(mut self, key: impl Into<Secret>) -> Self {
+        self.api_key = ApiKey(Arc::new(RwLock::new(Some(key.into()))));
+        self
+    }
+
+    /// Fails requests that take longer than `timeout`. By default requests never time out.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// How transient failures are retried. Defaults to `RetryPolicy::default()`.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Sends at most `requests` requests per `period` across the client and its clones,
+    /// queueing the rest. Defaults to LooksRare's 120 requests per minute.
+    pub fn rate_limit(mut self, requests: u32, period: Duration) -> Self {
+        self.rate_limit = Some((requests, period));
+        self
+    }
+
+    /// Sends requests as soon as they are made, e.g. for an API key with a higher limit
+    /// enforced elsewhere.
+    pub fn unlimited(mut self) -> Self {
+        self.rate_limit = None;
+        self
+    }
+
+    /// Panics if the base URL is invalid or another network's API host; see `try_build`.
+    pub fn build(self) -> LooksRareApi {
+        match self.try_build() {
+            Ok(api) => api,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn try_build(self) -> Result<LooksRareApi, LooksRareApiError> {
+        if let Some(url) = &self.base_url {
+            check_base_url(url, &self.network)?;
+        }
+
+        let mut builder = ClientBuilder::new();
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
 
         let client = builder.build().unwrap();
 
-        Self {
+        Ok(LooksRareApi {
             client,
-            network: Network::Mainnet,
+            network: self.network,
+            base_url: self.base_url,
+            api_key: self.api_key,
+            retry: self.retry,
+            etags: Arc::new(Mutex::new(HashMap::new())),
+            recorder: None,
+            audit: None,
+            annotations: None,
+            parse_mode: ParseMode::Strict,
+            limiter: self
+                .rate_limit
+                .map(|(requests, period)| Arc::new(RateLimiter::new(requests, period))),
+            server_limit: Arc::new(Mutex::new(ServerRateLimit::default())),
+            priority: Priority::Normal,
+            domains: Arc::new(DomainCache::new()),
+        })
+    }
+}
+
+/// Refuses LooksRare API hosts other than `network`'s, where orders signed for
+/// `network` would be invalid. Any other host, e.g. a proxy or a mock server,
+/// is taken to serve `network`.
+fn check_base_url(url: &str, network: &Network) -> Result<(), LooksRareApiError> {
+    let host = |url: &str| reqwest::Url::parse(url).ok()?.host_str().map(str::to_lowercase);
+    let base = host(url).ok_or_else(|| LooksRareApiError::InvalidBaseUrl(url.to_string()))?;
+
+    let looksrare = base == "looksrare.org" || base.ends_with(".looksrare.org");
+    if looksrare && Some(&base) != host(network.url()).as_ref() {
+        return Err(LooksRareApiError::WrongNetwork {
+            url: url.to_string(),
+            network: *network,
+        });
+    }
+
+    Ok(())
+}
+
+impl LooksRareApi {
+    /// A mainnet client. See `new_with_network` and `builder` for other networks.
+    pub fn new() -> Self {
+        LooksRareApiBuilder::new().build()
+    }
+
+    pub fn new_with_network(network: Network) -> Self {
+        LooksRareApiBuilder::new().network(network).build()
+    }
+
+    pub fn builder() -> LooksRareApiBuilder {
+        LooksRareApiBuilder::new()
+    }
+
+    /// Replaces the API key, or stops sending one, for this client and every clone of it.
+    ///
+    /// Requests already sent keep the key they were sent with. The old key is wiped from memory.
+    pub fn set_api_key(&self, key: Option<String>) {
+        *self.api_key.0.write().unwrap() = key.map(Secret::new);
+    }
+
+    pub fn has_api_key(&self) -> bool {
+        self.api_key.0.read().unwrap().is_some()
+    }
+
+    /// The versioned API root requests are sent under.
+    fn api(&self) -> String {
+        match &self.base_url {
+            Some(url) => format!("{}{}{}", url, constants::API_PATH, constants::VERSION),
+            None => self.network.api(),
+        }
+    }
+
+    /// Replaces the rate limit: at most `requests` requests per `period`, queueing the rest.
+    pub fn rate_limit(mut self, requests: u32, period: Duration) -> Self {
+        self.limiter = Some(Arc::new(RateLimiter::new(requests, period)));
+        self
+    }
+
+    /// Sheds requests covered by `policy` while the rate limiter is saturated,
+    /// failing them with `LooksRareApiError::Shed` instead of queueing.
+    ///
+    /// Has no effect unless `rate_limit` was called first. POSTs are always
+    /// sent as `Priority::High`.
+    pub fn shed(mut self, policy: ShedPolicy) -> Self {
+        if let Some(limiter) = self.limiter.as_mut().and_then(Arc::get_mut) {
+            limiter.shed(policy);
         }
+        self
+    }
+
+    /// A clone of this client whose GET requests are sent at `priority`.
+    pub fn with_priority(&self, priority: Priority) -> Self {
+        let mut api = self.clone();
+        api.priority = priority;
+        api
+    }
+
+    /// The remaining request budget, queue depth and the rate limit the API last reported.
+    pub fn rate_limit_status(&self) -> RateLimitStatus {
+        let mut status = match &self.limiter {
+            Some(limiter) => limiter.status(),
+            None => RateLimitStatus {
+                remaining: None,
+                queued: 0,
+                next_available: Instant::now(),
+                server: ServerRateLimit::default(),
+            },
+        };
+        status.server = self.server_limit.lock().unwrap().clone();
+
+        status
+    }
+
+    pub fn parse_mode(mut self, mode: ParseMode) -> Self {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Writes every raw response body to a file in `dir`, with its endpoint,
+    /// URL and status, so parsing problems can be replayed against the exact payload.
+    ///
+    /// See `recording::Recording::load` and `Recording::parse`. Files that fail
+    /// to write are skipped with a warning.
+    pub fn record_responses(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.recorder = Some(Arc::new(Recorder::new(dir.into())));
+        self
+    }
+
+    /// Records every order created, cancelled and executed through this client
+    /// and its clones in `log`.
+    pub fn audit_log(mut self, log: Arc<AuditLog>) -> Self {
+        self.audit = Some(log);
+        self
+    }
+
+    /// Appends `action` on `orders` to the audit log, if there is one. Failing
+    /// to write is reported but never fails the action.
+    pub(crate) fn audit<P: Serialize>(
+        &self,
+        action: AuditAction,
+        orders: Vec<OrderRef>,
+        payload: &P,
+        outcome: Result<String, String>,
+    ) {
+        let log = match &self.audit {
+            Some(log) => log,
+            None => return,
+        };
+
+        let (ok, detail) = match outcome {
+            Ok(detail) => (true, detail),
+            Err(err) => (false, err),
+        };
+        if let Err(e) = log.append(action, orders, payload, ok, Some(detail)) {
+            log::error!("failed to audit {}: {}", action.to_str(), e);
+        }
+    }
+
+    /// This client's EIP-712 domain separator, checked against the exchange
+    /// through `provider` on first use and cached for this client and its
+    /// clones. Fails with `ClientError::DomainMismatch` if they disagree.
+    ///
+    /// Every signing and fill path given a provider calls this first.
+    pub async fn domain_separator<M: Middleware>(&self, provider: Arc<M>) -> Result<H256, ClientError> {
+        self.domains.domain_separator(self.network, provider).await
+    }
+
+    /// Tags and notes on orders for this client and its clones to report with them.
+    pub fn annotations(mut self, store: Arc<AnnotationStore>) -> Self {
+        self.annotations = Some(store);
+        self
+    }
+
+    /// The annotation on the order with hash `order_hash`, as the API formats it.
+    pub fn annotation(&self, order_hash: &str) -> Option<Annotation> {
+        let store = self.annotations.as_ref()?;
+        store.get(order_hash.parse().ok()?)
+    }
+
+    /// The audit records so far, verified, or none without an audit log.
+    pub(crate) fn audit_records(&self) -> Result<Vec<AuditRecord>, AuditError> {
+        match &self.audit {
+            Some(log) => log.records(),
+            None => Ok(vec![]),
+        }
+    }
+
+    pub fn network(&self) -> &Network {
+        &self.network
     }
 
     pub async fn get_account(&self, address: Address) -> Result<Account, LooksRareApiError> {
-        let api = self.network.api();
+        let api = self.api();
         let url = format!("{}/accounts", api);
         let mut map = std::collections::HashMap::new();
         map.insert("address", serde_json::to_value(address)?);
 
-        let res = self.client.get(url).query(&map).send().await?;
-        let text = res.text().await?;
-        let resp: AccountResponse = serde_json::from_str(&text)?;
-        let data: Account = resp.data.ok_or(LooksRareApiError::AccountNotFound {
-            address: address
-        })?;
+        let request = self.client.get(url).query(&map);
+
+        self.get_parsed(request, |text| parse_account_response(text, address, self.parse_mode))
+            .await
+            .context_endpoint("GET /accounts")
+    }
 
-        Ok(data)
+    /// Looks up many accounts, at most `ACCOUNT_LOOKUP_CONCURRENCY` requests at a time.
+    ///
+    /// Addresses without a LooksRare profile are left out of the map; any other error fails the lookup.
+    pub async fn get_accounts(&self, addresses: Vec<Address>) -> Result<HashMap<Address, Account>, LooksRareApiError> {
+        stream::iter(addresses)
+            .map(|address| async move {
+                match self.get_account(address).await {
+                    Ok(account) => Ok(Some((address, account))),
+                    Err(e) if matches!(e.root(), LooksRareApiError::AccountNotFound { .. }) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+            .buffer_unordered(constants::ACCOUNT_LOOKUP_CONCURRENCY)
+            .try_filter_map(|found| async move { Ok(found) })
+            .try_collect()
+            .await
     }
 
     pub async fn get_orders(&self, req: OrdersRequest) -> Result<Vec<Order>, LooksRareApiError> {
-        let api = self.network.api();
+        let api = self.api();
         let url = format!("{}/orders", api);
 
+        let query = req.query(&self.network)?;
 
-        let mut query = vec![];
+        let request = self.client.get(url).query(&query).query(&req.extra_params);
 
-        if let Some(_a) = &req.is_order_ask { query.push(("isOrderAsk", serde_json::to_value(req.is_order_ask)?)); };
-        if let Some(_b) = &req.collection { query.push(("collection", serde_json::to_value(req.collection)?)); };
-        if let Some(_c) = &req.token_id { query.push(("tokenId", serde_json::to_value(req.token_id.unwrap().to_string())?)); };
-        if let Some(_d) = &req.signer { query.push(("signer", serde_json::to_value(req.signer)?)); };
-        if let Some(_e) = &req.nonce { query.push(("nonce", serde_json::to_value(req.nonce.unwrap().to_string())?)); };
-        if let Some(_f) = &req.strategy { query.push(("strategy", serde_json::to_value(req.strategy)?)); };
-        if let Some(_g) = &req.currency { query.push(("currency", serde_json::to_value(req.currency)?)); };
-        if let Some(_h) = &req.price {
-            if let Some(_min) = &req.price.clone().unwrap().min { query.push(("price[min]", serde_json::to_value(req.price.clone().unwrap().min.unwrap().to_string())?)); };
-            if let Some(_max) = &req.price.clone().unwrap().max { query.push(("price[max]", serde_json::to_value(req.price.clone().unwrap().max.unwrap().to_string())?)); };
-        };
-        if let Some(_i) = &req.start_time { query.push(("startTime", serde_json::to_value(req.start_time.unwrap().to_string())?)); };
-        if let Some(_j) = &req.end_time { query.push(("endTime", serde_json::to_value(req.end_time.unwrap().to_string())?)); };
-        if let Some(_k) = &req.status { req.status.unwrap().iter_mut().for_each(|x| { query.push(("status[]", serde_json::to_value(x.to_str()).unwrap())) } ); };
-        if let Some(_l) = &req.pagination {
-            if let Some(_first) = &req.pagination.clone().unwrap().first { query.push(("pagination[first]", serde_json::to_value(req.pagination.clone().unwrap().first.unwrap().to_string())?)); };    
-            if let Some(_cursor) = &req.pagination.clone().unwrap().cursor { query.push(("pagination[cursor]", serde_json::to_value(req.pagination.clone().unwrap().cursor)?)); }; 
-        };
-        if let Some(_m) = &req.sort { query.push(("sort", serde_json::to_value(req.sort.unwrap().to_str())?)); };
+        self.get_parsed(request, |text| parse_orders_response(text, self.parse_mode))
+            .await
+            .context_endpoint("GET /orders")
+    }
 
-        let res = self.client.get(url).query(&query).send().await?;
-        let text = res.text().await?;
+    /// Every valid collection offer on `collection`, highest first.
+    pub async fn get_collection_bids(&self, collection: Address) -> Result<Vec<Order>, LooksRareApiError> {
+        self.get_all_orders(OrdersRequest::collection_bids(collection)).await
+    }
+
+    /// Every valid offer on one token of `collection`, highest first, not counting collection offers.
+    pub async fn get_token_bids(&self, collection: Address, token_id: u64) -> Result<Vec<Order>, LooksRareApiError> {
+        self.get_all_orders(OrdersRequest::token_bids(collection, token_id)).await
+    }
+
+    /// Like `get_orders`, with the cursor to pass in `req.pagination` for the next page.
+    pub async fn get_orders_page(&self, req: OrdersRequest) -> Result<OrdersPage, LooksRareApiError> {
+        let first = req
+            .pagination
+            .as_ref()
+            .and_then(|pagination| pagination.first)
+            .unwrap_or(constants::DEFAULT_ORDERS_PER_PAGE);
+        let orders = self.get_orders(req).await?;
+
+        Ok(OrdersPage::new(orders, first))
+    }
+
+    /// Like `get_orders`, but returns `None` when the API answers 304 Not Modified
+    /// for a query this client has already fetched, so polling loops can skip the unchanged page.
+    pub async fn get_orders_if_modified(&self, req: OrdersRequest) -> Result<Option<Vec<Order>>, LooksRareApiError> {
+        let api = self.api();
+        let url = format!("{}/orders", api);
+
+        let query = req.query(&self.network)?;
+        let request = self.client.get(url).query(&query).query(&req.extra_params);
+
+        self.get_if_modified(request, |text| parse_orders_response(text, self.parse_mode))
+            .await
+            .context_endpoint("GET /orders")
+    }
 
-        let resp: OrdersResponse = serde_json::from_str(&text)?;
-        let data: Vec<Order> = resp.data.ok_or(LooksRareApiError::OrdersNotFound)?;
+    /// Follows the cursor until every order matching `req` has been fetched.
+    pub async fn get_all_orders(&self, req: OrdersRequest) -> Result<Vec<Order>, LooksRareApiError> {
+        self.get_orders_stream(req).try_collect().await
+    }
+
+    /// Every order matching `req`, fetched a page at a time as the stream is read
+    /// by following the cursor. Starts at `req.pagination.cursor` if it is set.
+    pub fn get_orders_stream(&self, mut req: OrdersRequest) -> impl Stream<Item = Result<Order, LooksRareApiError>> {
+        let api = self.clone();
+        let pagination = req.pagination.take().unwrap_or_default();
+        let first = pagination.first.unwrap_or(constants::MAX_ORDERS_PER_PAGE);
+
+        let fetch = move |cursor: Option<String>| {
+            let api = api.clone();
+            let mut req = req.clone();
+            req.pagination = Some(Pagination {
+                first: Some(first),
+                cursor,
+            });
+            async move { api.get_orders_page(req).await }
+        };
 
-        Ok(data)
+        paginate(fetch, pagination.cursor)
     }
 
+    /// Like `get_order_nonce`, for nonces that fit in a `u64`.
     pub async fn get_nonce(&self, address: Address) -> Result<u64, LooksRareApiError> {
-        let api = self.network.api();
+        let nonce = self.get_order_nonce(address).await?;
+
+        u64::try_from(nonce).map_err(|_| LooksRareApiError::NonceNotFound { address })
+    }
+
+    /// The next maker nonce `address` can sign orders with.
+    pub async fn get_order_nonce(&self, address: Address) -> Result<U256, LooksRareApiError> {
+        let api = self.api();
         let url = format!("{}/orders/nonce", api);
 
         let mut query = vec![];
         query.push(("address", serde_json::to_value(address)?));
 
-        let res = self.client.get(url).query(&query).send().await?;
-        let text = res.text().await?;
-
-        let resp: NonceResponse = serde_json::from_str(&text)?;
-        let nonce_string: String = resp.data.ok_or(LooksRareApiError::NonceNotFound {
-            address: address
-        })?;
-        let nonce: u64 = nonce_string.parse().unwrap();
+        let request = self.client.get(url).query(&query);
 
-        Ok(nonce)
+        self.get_parsed(request, |text| parse_nonce_response(text, address, self.parse_mode))
+            .await
+            .context_endpoint("GET /orders/nonce")
     }
 
     pub async fn get_collection_information(&self, address:Address) -> Result<CollectionInformation, LooksRareApiError> {
-        let api = self.network.api();
+        let api = self.api();
         let url = format!("{}/collections", api);
 
         let mut query = vec![];
         query.push(("address", serde_json::to_value(address)?));
 
-        let res = self.client.get(url).query(&query).send().await?;
-        let text = res.text().await?;
+        let request = self.client.get(url).query(&query);
+
+        let parse = |text: &str| {
+            let resp: CollectionInformationResponse = decode::<_, CollectionInformation>(text, self.parse_mode)?;
+            resp.data.ok_or(LooksRareApiError::CollectionNotFound { address })
+        };
+        self.get_parsed(request, parse).await.context_endpoint("GET /collections")
+    }
 
-        let resp: CollectionInformationResponse = serde_json::from_str(&text)?;
-        let collection_information: CollectionInformation = resp.data.ok_or(LooksRareApiError::CollectionNotFound{
-            address: address
-        })?;
+    pub async fn get_collections(&self, req: CollectionsRequest) -> Result<Vec<Collection>, LooksRareApiError> {
+        let api = self.api();
+        let url = format!("{}/collections", api);
+
+        let query = req.query()?;
 
-        Ok(collection_information)
+        let request = self.client.get(url).query(&query);
+
+        self.get_parsed(request, |text| parse_collections_response(text, self.parse_mode))
+            .await
+            .context_endpoint("GET /collections")
     }
 
     pub async fn get_collection_stats(&self, address:Address) -> Result<CollectionStats, LooksRareApiError> {
-        let api = self.network.api();
+        let api = self.api();
         let url = format!("{}/collections/stats", api);
 
         let mut query = vec![];
         query.push(("address", serde_json::to_value(address)?));
 
-        let res = self.client.get(url).query(&query).send().await?;
-        let text = res.text().await?;
-        let resp: CollectionStatsResponse = serde_json::from_str(&text)?;
-        let collection_stats: CollectionStats = resp.data.ok_or(LooksRareApiError::CollectionNotFound {
-            address: address
-        })?;
+        let request = self.client.get(url).query(&query);
 
-        Ok(collection_stats)
+        self.get_parsed(request, |text| parse_collection_stats_response(text, address, self.parse_mode))
+            .await
+            .context_endpoint("GET /collections/stats")
+    }
+
+    /// Like `get_collection_stats`, but returns `None` when the stats have not changed
+    /// since this client last fetched them.
+    pub async fn get_collection_stats_if_modified(&self, address: Address) -> Result<Option<CollectionStats>, LooksRareApiError> {
+        let api = self.api();
+        let url = format!("{}/collections/stats", api);
+
+        let query = vec![("address", serde_json::to_value(address)?)];
+        let request = self.client.get(url).query(&query);
+
+        self.get_if_modified(request, |text| parse_collection_stats_response(text, address, self.parse_mode))
+            .await
+            .context_endpoint("GET /collections/stats")
     }
 
     pub async fn get_top_5_listing_rewards_collections(&self) -> Result<Vec<CollectionRewards>, LooksRareApiError> {
-        let api = self.network.api();
+        let api = self.api();
         let url = format!("{}/collections/listing-rewards", api);
 
-        let res = self.client.get(url).send().await?;
+        let parse = |text: &str| {
+            let resp: Top5ListingRewardsCollectionsResponse = decode::<_, CollectionRewards>(text, self.parse_mode)?;
+            Ok::<_, LooksRareApiError>(resp.data)
+        };
+        self.get_parsed(self.client.get(url), parse)
+            .await
+            .context_endpoint("GET /collections/listing-rewards")
+    }
+
+    pub async fn get_token(&self, collection: Address, token_id: U256) -> Result<Token, LooksRareApiError> {
+        let api = self.api();
+        let url = format!("{}/tokens", api);
+
+        let query = vec![
+            ("collection", serde_json::to_value(collection)?),
+            ("tokenId", Value::String(token_id.to_string())),
+        ];
+
+        let request = self.client.get(url).query(&query);
+
+        self.get_parsed(request, |text| parse_token_response(text, collection, token_id, self.parse_mode))
+            .await
+            .context_endpoint("GET /tokens")
+    }
+
+    /// Trading and listing rewards earned by `address`, with their claim proofs.
+    pub async fn get_rewards(&self, address: Address) -> Result<Rewards, LooksRareApiError> {
+        let api = self.api();
+        let url = format!("{}/rewards", api);
+
+        let query = vec![("address", serde_json::to_value(address)?)];
+
+        let request = self.client.get(url).query(&query);
+
+        self.get_parsed(request, |text| parse_rewards_response(text, address, self.parse_mode))
+            .await
+            .context_endpoint("GET /rewards")
+    }
+
+    pub async fn get_events(&self, req: EventsRequest) -> Result<Vec<Event>, LooksRareApiError> {
+        let api = self.api();
+        let url = format!("{}/events", api);
+
+        let query = req.query()?;
+
+        let request = self.client.get(url).query(&query).query(&req.extra_params);
+
+        self.get_parsed(request, |text| parse_events_response(text, self.parse_mode))
+            .await
+            .context_endpoint("GET /events")
+    }
+
+    /// Like `get_events`, but returns `None` when the events have not changed
+    /// since this client last fetched them.
+    pub async fn get_events_if_modified(&self, req: EventsRequest) -> Result<Option<Vec<Event>>, LooksRareApiError> {
+        let api = self.api();
+        let url = format!("{}/events", api);
+
+        let query = req.query()?;
+        let request = self.client.get(url).query(&query).query(&req.extra_params);
+
+        self.get_if_modified(request, |text| parse_events_response(text, self.parse_mode))
+            .await
+            .context_endpoint("GET /events")
+    }
+
+    /// Posts a signed maker order, returning the order as the API stored it.
+    ///
+    /// A refused order fails with an error whose `root` is `LooksRareApiError::OrderRejected`.
+    pub async fn create_order(&self, order: &SignedOrder) -> Result<Order, LooksRareApiError> {
+        let api = self.api();
+        let url = format!("{}/orders", api);
+
+        let body = CreateOrderRequest::from(order);
+
+        let created = async {
+            let res = self.send(self.client.post(url).json(&body)).await?;
+            let text = match self.read(res).await {
+                Err(LooksRareApiError::BadRequest { message }) => return Err(OrderRejection::from_message(&message).into()),
+                text => text?,
+            };
+            parse_create_order_response(&text, self.parse_mode)
+        }
+        .await
+        .context_endpoint("POST /orders");
+
+        let outcome = created.as_ref().map(|order| order.hash.clone()).map_err(|e| e.to_string());
+        self.audit(AuditAction::OrderCreated, vec![OrderRef::from(&order.order)], order, outcome);
+
+        created
+    }
+
+    /// Sends a GET to any path under the API base, e.g. `/collections/stats`, returning the raw JSON body.
+    ///
+    /// For endpoints this crate does not have typed methods for yet.
+    pub async fn raw_get(&self, path: &str, params: &[(String, String)]) -> Result<serde_json::Value, LooksRareApiError> {
+        let url = format!("{}{}", self.api(), path);
+        let request = self.client.get(url).query(params);
+
+        self.get_parsed(request, |text| Ok(serde_json::from_str(text)?))
+            .await
+            .context_endpoint(format!("GET {}", path))
+    }
+
+    /// Sends a JSON POST to any path under the API base, returning the raw JSON body.
+    pub async fn raw_post(&self, path: &str, body: &serde_json::Value) -> Result<serde_json::Value, LooksRareApiError> {
+        let url = format!("{}{}", self.api(), path);
+
+        let posted = async {
+            let res = self.send(self.client.post(url).json(body)).await?;
+            let text = self.read(res).await?;
+            Ok::<_, LooksRareApiError>(serde_json::from_str(&text)?)
+        };
+
+        posted.await.context_endpoint(format!("POST {}", path))
+    }
+
+    /// Sends `request` and parses the body of a successful response with `parse`.
+    async fn get_parsed<T>(
+        &self,
+        request: RequestBuilder,
+        parse: impl FnOnce(&str) -> Result<T, LooksRareApiError>,
+    ) -> Result<T, LooksRareApiError> {
+        let res = self.send(request).await?;
+        let text = self.read(res).await?;
+
+        parse(&text)
+    }
+
+    /// Sends a request once the rate limiter allows it.
+    async fn send(&self, request: RequestBuilder) -> Result<Response, LooksRareApiError> {
+        let mut request = request.build()?;
+        self.authorize(&mut request)?;
+        let priority = if request.method() == Method::POST {
+            Priority::High
+        } else {
+            self.priority
+        };
+
+        self.execute(request, priority).await
+    }
+
+    /// Executes `request` under the rate limiter, retrying transient failures per the retry policy.
+    async fn execute(&self, mut request: Request, priority: Priority) -> Result<Response, LooksRareApiError> {
+        let method = request.method().clone();
+        let mut retries = 0;
+
+        loop {
+            // a request whose body can't be cloned is only sent once
+            let next = request.try_clone().filter(|_| retries + 1 < self.retry.max_attempts);
+            self.acquire(priority).await?;
+
+            let retry_after = match self.client.execute(request).await {
+                Ok(res) => {
+                    self.observe(&res);
+                    if next.is_none() || !retry::retryable_status(&method, res.status()) {
+                        return Ok(res);
+                    }
+                    retry::retry_after(res.headers())
+                }
+                Err(e) if next.is_some() && retry::retryable_error(&method, &e) => None,
+                Err(e) => return Err(e.into()),
+            };
+
+            let delay = self.retry.delay(retries, retry_after);
+            log::debug!("retrying {} request in {:?} (retry {})", method, delay, retries + 1);
+            tokio::time::sleep(delay).await;
+
+            retries += 1;
+            request = next.unwrap();
+        }
+    }
+
+    fn authorize(&self, request: &mut Request) -> Result<(), LooksRareApiError> {
+        if let Some(key) = self.api_key.0.read().unwrap().as_ref() {
+            let mut value = HeaderValue::from_str(key.expose()).map_err(|_| LooksRareApiError::InvalidApiKey)?;
+            value.set_sensitive(true);
+            request.headers_mut().insert(constants::API_KEY_HEADER, value);
+        }
+
+        Ok(())
+    }
+
+    async fn acquire(&self, priority: Priority) -> Result<(), LooksRareApiError> {
+        match &self.limiter {
+            Some(limiter) => limiter
+                .acquire(priority)
+                .await
+                .map_err(|priority| LooksRareApiError::Shed { priority }),
+            None => Ok(()),
+        }
+    }
+
+    fn observe(&self, res: &Response) {
+        if let Some(limit) = ServerRateLimit::from_headers(res.headers()) {
+            *self.server_limit.lock().unwrap() = limit;
+        }
+    }
+
+    /// Reads the response body, recording it first if recording is on.
+    ///
+    /// Fails with a typed error for 429, 5xx and 4xx responses other than 404,
+    /// which is left to each endpoint's own not-found error.
+    async fn read(&self, res: Response) -> Result<String, LooksRareApiError> {
+        let url = res.url().clone();
+        let status = res.status();
+        let retry_after = retry::retry_after(res.headers());
         let text = res.text().await?;
-        println!("{}",text);
-        let resp: Top5ListingRewardsCollectionsResponse = serde_json::from_str(&text)?;
-        let top_5_listing_rewards_collections: Vec<CollectionRewards> = resp.data;
 
-        Ok(top_5_listing_rewards_collections)
+        if let Some(recorder) = &self.recorder {
+            if let Err(e) = recorder.record(&self.api(), &url, status.as_u16(), &text) {
+                log::warn!("failed to record the response from {}: {}", url, e);
+            }
+        }
+
+        match status_error(status, retry_after, &text) {
+            Some(err) => Err(err),
+            None => Ok(text),
+        }
+    }
+
+    /// Sends a conditional GET using the last `ETag` seen for the same URL.
+    /// Returns `None` on 304 Not Modified.
+    ///
+    /// The new `ETag` is only remembered once the body has been read and parsed,
+    /// so a failed response is fetched in full on the next call.
+    async fn get_if_modified<T>(
+        &self,
+        request: RequestBuilder,
+        parse: impl FnOnce(&str) -> Result<T, LooksRareApiError>,
+    ) -> Result<Option<T>, LooksRareApiError> {
+        let mut request = request.build()?;
+        self.authorize(&mut request)?;
+        let key = request.url().to_string();
+
+        let etag = self.etags.lock().unwrap().get(&key).cloned();
+        if let Some(value) = etag.and_then(|etag| HeaderValue::from_str(&etag).ok()) {
+            request.headers_mut().insert(IF_NONE_MATCH, value);
+        }
+
+        let res = self.execute(request, self.priority).await?;
+        if res.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        let etag = res.headers().get(ETAG).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let text = self.read(res).await?;
+        let parsed = parse(&text)?;
+
+        if let Some(etag) = etag {
+            self.etags.lock().unwrap().insert(key, etag);
+        }
+
+        Ok(Some(parsed))
     }
 
 }
@@ -153,70 +802,636 @@ pub struct AccountRequest {
 struct AccountResponse {
     success: bool,
     message: Option<String>,
-    data: Option<Account>,
+    data: Option<v1::Account>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct OrdersRequest {
     pub is_order_ask: Option<bool>,
     pub collection: Option<Address>,
     pub token_id: Option<u64>,
     pub signer: Option<Address>,
     pub nonce: Option<u64>,
-    pub strategy: Option<Address>,
-    pub currency: Option<Address>,
+    pub strategy: Option<Strategy>,
+    pub currency: Option<Currency>,
     pub price: Option<Price>,
     pub start_time: Option<u64>,
     pub end_time: Option<u64>,
     pub status: Option<Vec<Status>>,
     pub pagination: Option<Pagination>,
     pub sort: Option<Sort>,
+    /// Query parameters sent as-is after the typed filters, for filters this crate doesn't know yet.
+    pub extra_params: Vec<(String, String)>,
+}
+
+impl OrdersRequest {
+    /// Valid collection offers on `collection`, which any of its tokens can fill, highest first.
+    pub fn collection_bids(collection: Address) -> Self {
+        Self {
+            is_order_ask: Some(false),
+            collection: Some(collection),
+            strategy: Some(Strategy::CollectionOffer),
+            status: Some(vec![Status::Valid]),
+            sort: Some(Sort::PriceDesc),
+            ..Default::default()
+        }
+    }
+
+    /// Valid offers on one token of `collection`, highest first. Collection
+    /// offers the token can also fill are not included.
+    pub fn token_bids(collection: Address, token_id: u64) -> Self {
+        Self {
+            is_order_ask: Some(false),
+            collection: Some(collection),
+            token_id: Some(token_id),
+            strategy: Some(Strategy::StandardSale),
+            status: Some(vec![Status::Valid]),
+            sort: Some(Sort::PriceDesc),
+            ..Default::default()
+        }
+    }
+
+    pub fn extra_params(mut self, params: Vec<(String, String)>) -> Self {
+        self.extra_params.extend(params);
+        self
+    }
+
+    /// The strategy and currency are sent as their addresses on `network`.
+    fn query(&self, network: &Network) -> Result<Vec<(&'static str, serde_json::Value)>, LooksRareApiError> {
+        let mut query = vec![];
+
+        if let Some(is_order_ask) = &self.is_order_ask { query.push(("isOrderAsk", serde_json::to_value(is_order_ask)?)); };
+        if let Some(collection) = &self.collection { query.push(("collection", serde_json::to_value(collection)?)); };
+        if let Some(token_id) = &self.token_id { query.push(("tokenId", serde_json::to_value(token_id.to_string())?)); };
+        if let Some(signer) = &self.signer { query.push(("signer", serde_json::to_value(signer)?)); };
+        if let Some(nonce) = &self.nonce { query.push(("nonce", serde_json::to_value(nonce.to_string())?)); };
+        if let Some(strategy) = self.strategy { query.push(("strategy", serde_json::to_value(network.strategy(strategy))?)); };
+        if let Some(currency) = self.currency { query.push(("currency", serde_json::to_value(network.currency(currency))?)); };
+        if let Some(price) = &self.price {
+            if let Some(min) = &price.min { query.push(("price[min]", serde_json::to_value(min.to_string())?)); };
+            if let Some(max) = &price.max { query.push(("price[max]", serde_json::to_value(max.to_string())?)); };
+        };
+        if let Some(start_time) = &self.start_time { query.push(("startTime", serde_json::to_value(start_time.to_string())?)); };
+        if let Some(end_time) = &self.end_time { query.push(("endTime", serde_json::to_value(end_time.to_string())?)); };
+        if let Some(status) = &self.status {
+            for x in status { query.push(("status[]", serde_json::to_value(x.to_str())?)); }
+        };
+        if let Some(pagination) = &self.pagination {
+            if let Some(first) = &pagination.first { query.push(("pagination[first]", serde_json::to_value(first.to_string())?)); };
+            if let Some(cursor) = &pagination.cursor { query.push(("pagination[cursor]", serde_json::to_value(cursor)?)); };
+        };
+        if let Some(sort) = &self.sort { query.push(("sort", serde_json::to_value(sort.to_str())?)); };
+
+        Ok(query)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct OrdersResponse {
     success: bool,
     message: Option<String>,
-    data: Option<Vec<Order>>,
+    data: Option<Vec<v1::Order>>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct NonceResponse {
-    success: bool,
-    message: Option<String>,
-    data: Option<String>,
+/// The body of `POST /orders`, with every number as a decimal string.
+#[derive(Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateOrderRequest {
+    signature: String,
+    token_id: String,
+    collection: Address,
+    strategy: Address,
+    currency: Address,
+    signer: Address,
+    is_order_ask: bool,
+    nonce: String,
+    amount: String,
+    price: String,
+    start_time: String,
+    end_time: String,
+    min_percentage_to_ask: String,
+    params: String,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct CollectionInformationResponse {
-    success: bool,
-    message: Option<String>,
-    data: Option<CollectionInformation>,
+impl std::fmt::Debug for CreateOrderRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CreateOrderRequest")
+            .field("signature", &Redacted(&self.signature))
+            .field("token_id", &self.token_id)
+            .field("collection", &self.collection)
+            .field("strategy", &self.strategy)
+            .field("currency", &self.currency)
+            .field("signer", &self.signer)
+            .field("is_order_ask", &self.is_order_ask)
+            .field("nonce", &self.nonce)
+            .field("amount", &self.amount)
+            .field("price", &self.price)
+            .field("start_time", &self.start_time)
+            .field("end_time", &self.end_time)
+            .field("min_percentage_to_ask", &self.min_percentage_to_ask)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+impl From<&SignedOrder> for CreateOrderRequest {
+    fn from(signed: &SignedOrder) -> Self {
+        let order = &signed.order;
+        Self {
+            signature: format!("0x{}", ethers::utils::hex::encode(signed.signature.to_vec())),
+            token_id: order.token_id.to_string(),
+            collection: order.collection,
+            strategy: order.strategy,
+            currency: order.currency,
+            signer: order.signer,
+            is_order_ask: order.is_order_ask,
+            nonce: order.nonce.to_string(),
+            amount: order.amount.to_string(),
+            price: order.price.to_string(),
+            start_time: order.start_time.to_string(),
+            end_time: order.end_time.to_string(),
+            min_percentage_to_ask: order.min_percentage_to_ask.to_string(),
+            params: format!("0x{}", ethers::utils::hex::encode(&order.params)),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-struct CollectionStatsResponse {
+struct CreateOrderResponse {
     success: bool,
     message: Option<String>,
-    data: Option<CollectionStats>,
+    data: Option<v1::Order>,
+}
+
+fn parse_create_order_response(text: &str, mode: ParseMode) -> Result<Order, LooksRareApiError> {
+    let resp: CreateOrderResponse = decode::<_, v1::Order>(text, mode)?;
+
+    match resp.data {
+        Some(order) if resp.success => Ok(order.into()),
+        _ => {
+            let message = resp.message.unwrap_or_default();
+            Err(OrderRejection::from_message(&message).into())
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-struct Top5ListingRewardsCollectionsResponse {
+struct NonceResponse {
     success: bool,
     message: Option<String>,
-    data: Vec<CollectionRewards>,
+    data: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Pagination {
-    pub first: Option<u64>,
-    pub cursor: Option<String>,
+fn parse_account_response(text: &str, address: Address, mode: ParseMode) -> Result<Account, LooksRareApiError> {
+    let resp: AccountResponse = decode::<_, v1::Account>(text, mode)?;
+    let account = resp.data.ok_or(LooksRareApiError::AccountNotFound { address })?;
+    Ok(Account::from(account))
 }
 
-#[derive(Debug, Error)]
-pub enum LooksRareApiError {
-    #[error(transparent)]
+fn parse_orders_response(text: &str, mode: ParseMode) -> Result<Vec<Order>, LooksRareApiError> {
+    let resp: OrdersResponse = decode::<_, v1::Order>(text, mode)?;
+    let orders = resp.data.ok_or(LooksRareApiError::OrdersNotFound)?;
+    Ok(orders.into_iter().map(Order::from).collect())
+}
+
+fn parse_nonce_response(text: &str, address: Address, mode: ParseMode) -> Result<U256, LooksRareApiError> {
+    let resp: NonceResponse = decode::<_, String>(text, mode)?;
+    let nonce_string: String = resp.data.ok_or(LooksRareApiError::NonceNotFound { address })?;
+    U256::from_dec_str(&nonce_string).map_err(|_| LooksRareApiError::NonceNotFound { address })
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EventsRequest {
+    pub collection: Option<Address>,
+    pub token_id: Option<u64>,
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub event_type: Option<EventType>,
+    pub pagination: Option<Pagination>,
+    /// Query parameters sent as-is after the typed filters, for filters this crate doesn't know yet.
+    pub extra_params: Vec<(String, String)>,
+}
+
+impl EventsRequest {
+    pub fn extra_params(mut self, params: Vec<(String, String)>) -> Self {
+        self.extra_params.extend(params);
+        self
+    }
+
+    fn query(&self) -> Result<Vec<(&'static str, serde_json::Value)>, LooksRareApiError> {
+        let mut query = vec![];
+
+        if let Some(collection) = &self.collection { query.push(("collection", serde_json::to_value(collection)?)); };
+        if let Some(token_id) = &self.token_id { query.push(("tokenId", serde_json::to_value(token_id.to_string())?)); };
+        if let Some(from) = &self.from { query.push(("from", serde_json::to_value(from)?)); };
+        if let Some(to) = &self.to { query.push(("to", serde_json::to_value(to)?)); };
+        if let Some(event_type) = &self.event_type { query.push(("type", serde_json::to_value(event_type.to_str())?)); };
+        if let Some(pagination) = &self.pagination {
+            if let Some(first) = &pagination.first { query.push(("pagination[first]", serde_json::to_value(first.to_string())?)); };
+            if let Some(cursor) = &pagination.cursor { query.push(("pagination[cursor]", serde_json::to_value(cursor)?)); };
+        };
+
+        Ok(query)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct EventsResponse {
+    success: bool,
+    message: Option<String>,
+    data: Option<Vec<v1::Event>>,
+}
+
+fn parse_events_response(text: &str, mode: ParseMode) -> Result<Vec<Event>, LooksRareApiError> {
+    let resp: EventsResponse = decode::<_, v1::Event>(text, mode)?;
+    let events = resp.data.ok_or(LooksRareApiError::EventsNotFound)?;
+    Ok(events.into_iter().map(Event::from).collect())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CollectionInformationResponse {
+    success: bool,
+    message: Option<String>,
+    data: Option<CollectionInformation>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CollectionsRequest {
+    pub pagination: Option<Pagination>,
+    pub sort: Option<CollectionSort>,
+}
+
+impl CollectionsRequest {
+    fn query(&self) -> Result<Vec<(&'static str, serde_json::Value)>, LooksRareApiError> {
+        let mut query = vec![];
+
+        if let Some(pagination) = &self.pagination {
+            if let Some(first) = &pagination.first { query.push(("pagination[first]", serde_json::to_value(first.to_string())?)); };
+            if let Some(cursor) = &pagination.cursor { query.push(("pagination[cursor]", serde_json::to_value(cursor)?)); };
+        };
+        if let Some(sort) = &self.sort { query.push(("sort", serde_json::to_value(sort.to_str())?)); };
+
+        Ok(query)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CollectionsResponse {
+    success: bool,
+    message: Option<String>,
+    data: Option<Vec<v1::Collection>>,
+}
+
+fn parse_collections_response(text: &str, mode: ParseMode) -> Result<Vec<Collection>, LooksRareApiError> {
+    let resp: CollectionsResponse = decode::<_, v1::Collection>(text, mode)?;
+    let collections = resp.data.ok_or(LooksRareApiError::CollectionsNotFound)?;
+    Ok(collections.into_iter().map(Collection::from).collect())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CollectionStatsResponse {
+    success: bool,
+    message: Option<String>,
+    data: Option<v1::CollectionStats>,
+}
+
+fn parse_collection_stats_response(text: &str, address: Address, mode: ParseMode) -> Result<CollectionStats, LooksRareApiError> {
+    let resp: CollectionStatsResponse = decode::<_, v1::CollectionStats>(text, mode)?;
+    let stats = resp.data.ok_or(LooksRareApiError::CollectionNotFound { address })?;
+    Ok(stats.into())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Top5ListingRewardsCollectionsResponse {
+    success: bool,
+    message: Option<String>,
+    data: Vec<CollectionRewards>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TokenResponse {
+    success: bool,
+    message: Option<String>,
+    data: Option<Token>,
+}
+
+fn parse_token_response(text: &str, collection: Address, token_id: U256, mode: ParseMode) -> Result<Token, LooksRareApiError> {
+    let resp: TokenResponse = decode::<_, Token>(text, mode)?;
+    resp.data.ok_or(LooksRareApiError::TokenNotFound { collection, token_id })
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RewardsResponse {
+    success: bool,
+    message: Option<String>,
+    data: Option<Rewards>,
+}
+
+fn parse_rewards_response(text: &str, address: Address, mode: ParseMode) -> Result<Rewards, LooksRareApiError> {
+    let resp: RewardsResponse = decode::<_, Rewards>(text, mode)?;
+    resp.data.ok_or(LooksRareApiError::RewardsNotFound { address })
+}
+
+/// A response type a `Recording` can be parsed back into, through the parser and
+/// error mapping the client used; see `Recording::parse`.
+pub trait Replay: Sized {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError>;
+}
+
+/// The recorded body, or the error the client made of its status.
+fn recorded_body(recording: &Recording) -> Result<&str, LooksRareApiError> {
+    let status = StatusCode::from_u16(recording.status).map_err(|_| LooksRareApiError::ServerError { status: recording.status })?;
+    match status_error(status, None, &recording.body) {
+        Some(err) => Err(err),
+        None => Ok(&recording.body),
+    }
+}
+
+/// The address in the recorded URL's `key` query parameter, or zero.
+fn recorded_address(recording: &Recording, key: &str) -> Address {
+    recording.query_param(key).and_then(|value| value.parse().ok()).unwrap_or_default()
+}
+
+impl Replay for Account {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        parse_account_response(recorded_body(recording)?, recorded_address(recording, "address"), mode)
+    }
+}
+
+impl Replay for Vec<Order> {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        parse_orders_response(recorded_body(recording)?, mode)
+    }
+}
+
+/// The order returned by `POST /orders`.
+impl Replay for Order {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        let text = match recorded_body(recording) {
+            Err(LooksRareApiError::BadRequest { message }) => return Err(OrderRejection::from_message(&message).into()),
+            text => text?,
+        };
+        parse_create_order_response(text, mode)
+    }
+}
+
+/// The nonce returned by `/orders/nonce`.
+impl Replay for U256 {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        parse_nonce_response(recorded_body(recording)?, recorded_address(recording, "address"), mode)
+    }
+}
+
+impl Replay for Vec<Event> {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        parse_events_response(recorded_body(recording)?, mode)
+    }
+}
+
+impl Replay for Vec<Collection> {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        parse_collections_response(recorded_body(recording)?, mode)
+    }
+}
+
+impl Replay for CollectionStats {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        parse_collection_stats_response(recorded_body(recording)?, recorded_address(recording, "address"), mode)
+    }
+}
+
+impl Replay for Token {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        let token_id = recording.query_param("tokenId").and_then(|id| U256::from_dec_str(&id).ok()).unwrap_or_default();
+        parse_token_response(recorded_body(recording)?, recorded_address(recording, "collection"), token_id, mode)
+    }
+}
+
+impl Replay for Rewards {
+    fn replay(recording: &Recording, mode: ParseMode) -> Result<Self, LooksRareApiError> {
+        parse_rewards_response(recorded_body(recording)?, recorded_address(recording, "address"), mode)
+    }
+}
+
+/// One page of orders.
+#[derive(Clone, Debug, Default)]
+pub struct OrdersPage {
+    pub orders: Vec<Order>,
+    /// The cursor of the next page, or `None` if this is the last one.
+    pub next_cursor: Option<String>,
+}
+
+impl OrdersPage {
+    /// The API's cursor is the last order's hash; a page shorter than `first` is the last one.
+    fn new(orders: Vec<Order>, first: u64) -> Self {
+        let next_cursor = match orders.last() {
+            Some(last) if orders.len() as u64 >= first => Some(last.hash.clone()),
+            _ => None,
+        };
+
+        Self { orders, next_cursor }
+    }
+}
+
+struct PageState<F> {
+    fetch: F,
+    cursor: Option<String>,
+    pending: VecDeque<Order>,
+    done: bool,
+}
+
+/// Streams the orders of successive pages, starting at `cursor`, until a page has no next cursor.
+fn paginate<F, Fut>(fetch: F, cursor: Option<String>) -> impl Stream<Item = Result<Order, LooksRareApiError>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<OrdersPage, LooksRareApiError>>,
+{
+    let state = PageState {
+        fetch,
+        cursor,
+        pending: VecDeque::new(),
+        done: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(order) = state.pending.pop_front() {
+                return Some((Ok(order), state));
+            }
+            if state.done {
+                return None;
+            }
+
+            match (state.fetch)(state.cursor.clone()).await {
+                Ok(page) => {
+                    state.done = page.next_cursor.is_none();
+                    state.cursor = page.next_cursor;
+                    state.pending.extend(page.orders);
+                }
+                Err(e) => {
+                    // the same page is retried if the stream is read again
+                    return Some((Err(e), state));
+                }
+            }
+        }
+    })
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Pagination {
+    pub first: Option<u64>,
+    pub cursor: Option<String>,
+}
+
+/// How response bodies that don't match the expected types are handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// A required field that is missing or null fails the request. Best for tests.
+    #[default]
+    Strict,
+    /// A required field that is missing or null is given its type's default
+    /// and a warning is logged, so one bad field doesn't take down a service.
+    ///
+    /// Fields identifying an item, e.g. an order's hash, signer or price, are
+    /// never defaulted: a list item missing one is dropped with a warning.
+    Lenient,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    message: Option<String>,
+}
+
+/// The error for a response that failed with `status`, if it did.
+fn status_error(status: StatusCode, retry_after: Option<Duration>, text: &str) -> Option<LooksRareApiError> {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Some(LooksRareApiError::RateLimited { retry_after });
+    }
+    if status.is_server_error() {
+        return Some(LooksRareApiError::ServerError { status: status.as_u16() });
+    }
+    if !status.is_client_error() || status == StatusCode::NOT_FOUND {
+        return None;
+    }
+
+    let message = serde_json::from_str::<ErrorResponse>(text)
+        .ok()
+        .and_then(|resp| resp.message)
+        .unwrap_or_else(|| status.canonical_reason().unwrap_or_default().to_string());
+
+    Some(LooksRareApiError::BadRequest { message })
+}
+
+/// Fields that identify what a response describes and are never given a default
+/// in lenient mode; an item missing one is dropped from a list instead.
+const IDENTITY_FIELDS: [&str; 9] = [
+    "id",
+    "hash",
+    "signer",
+    "collectionAddress",
+    "tokenId",
+    "price",
+    "nonce",
+    "currencyAddress",
+    "createdAt",
+];
+
+/// Deserializes a response whose `data` holds a `T` or a list of them.
+fn decode<R, T>(text: &str, mode: ParseMode) -> Result<R, serde_json::Error>
+where
+    R: DeserializeOwned,
+    T: Serialize + DeserializeOwned + Default,
+{
+    if mode == ParseMode::Strict {
+        return serde_json::from_str(text);
+    }
+
+    let mut value: Value = serde_json::from_str(text)?;
+    let defaults = serde_json::to_value(T::default())?;
+    match value.get_mut("data") {
+        Some(Value::Array(items)) => items.retain_mut(|item| {
+            let repaired = repair::<T>(item, &defaults, "data[]");
+            if !repaired {
+                log::warn!("dropping malformed item from LooksRare response: {}", item);
+            }
+            repaired
+        }),
+        Some(data) => {
+            repair::<T>(data, &defaults, "data");
+        }
+        None => {}
+    }
+
+    serde_json::from_value(value)
+}
+
+/// Fills in what `value` is missing to parse as a `T`, returning whether it now does.
+///
+/// Optional nested objects have no defaults to fill in from, so one that doesn't
+/// parse is dropped, keeping the rest of `value`.
+fn repair<T: DeserializeOwned>(value: &mut Value, defaults: &Value, path: &str) -> bool {
+    if !fill_defaults(value, defaults, path) {
+        return false;
+    }
+
+    let parses = |value: &Value| serde_json::from_value::<T>(value.clone()).is_ok();
+    if parses(value) {
+        return true;
+    }
+
+    let optional: Vec<String> = match (&*value, defaults) {
+        (Value::Object(fields), Value::Object(defaults)) => defaults
+            .iter()
+            .filter(|(key, default)| default.is_null() && fields.get(*key).is_some_and(Value::is_object))
+            .map(|(key, _)| key.clone())
+            .collect(),
+        _ => return false,
+    };
+    for key in optional {
+        log::warn!("LooksRare response has a malformed {}.{}, dropping it", path, key);
+        value[key.as_str()] = Value::Null;
+        if parses(value) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Fills missing or null fields of `value` from `defaults`, recursively. Returns
+/// false, filling nothing more, if an identity field is missing.
+fn fill_defaults(value: &mut Value, defaults: &Value, path: &str) -> bool {
+    let (fields, defaults) = match (value, defaults) {
+        (Value::Object(fields), Value::Object(defaults)) => (fields, defaults),
+        _ => return true,
+    };
+
+    for (key, default) in defaults {
+        let path = format!("{}.{}", path, key);
+        match fields.get_mut(key) {
+            Some(Value::Null) | None if !default.is_null() => {
+                if IDENTITY_FIELDS.contains(&key.as_str()) {
+                    log::warn!("LooksRare response is missing {}", path);
+                    return false;
+                }
+                log::warn!("LooksRare response is missing {}, using {}", path, default);
+                fields.insert(key.clone(), default.clone());
+            }
+            Some(field) => {
+                if !fill_defaults(field, default, &path) {
+                    return false;
+                }
+            }
+            None => {}
+        }
+    }
+
+    true
+}
+
+#[derive(Debug, Error)]
+pub enum LooksRareApiError {
+    #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
@@ -224,10 +1439,99 @@ pub enum LooksRareApiError {
     AccountNotFound { address: Address },
     #[error("Orders not found")]
     OrdersNotFound,
+    #[error("Events not found")]
+    EventsNotFound,
     #[error("Nonce not found (address: {address}")]
     NonceNotFound { address: Address },
     #[error("Collection not found (address: {address}")]
     CollectionNotFound { address: Address },
+    #[error("Collections not found")]
+    CollectionsNotFound,
+    #[error("Token not found (collection: {collection}, token id: {token_id})")]
+    TokenNotFound { collection: Address, token_id: U256 },
+    #[error("Rewards not found (address: {address}")]
+    RewardsNotFound { address: Address },
+    #[error(transparent)]
+    OrderRejected(#[from] OrderRejection),
+    #[error("Rate limited by the API (retry after: {retry_after:?})")]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("Bad request: {message}")]
+    BadRequest { message: String },
+    #[error("LooksRare API server error (status: {status})")]
+    ServerError { status: u16 },
+    #[error("API key is not a valid header value")]
+    InvalidApiKey,
+    #[error("Base URL is invalid: {0}")]
+    InvalidBaseUrl(String),
+    #[error("{url} is not an API host of {network:?}")]
+    WrongNetwork { url: String, network: Network },
+    #[error("Request shed while rate limited (priority: {priority:?})")]
+    Shed { priority: Priority },
+    #[error("{endpoint}: {source}")]
+    Context {
+        endpoint: String,
+        source: Box<LooksRareApiError>,
+    },
+}
+
+impl LooksRareApiError {
+    /// The underlying error, with any context layers removed.
+    pub fn root(&self) -> &LooksRareApiError {
+        match self {
+            LooksRareApiError::Context { source, .. } => source.root(),
+            err => err,
+        }
+    }
+}
+
+/// Adds the endpoint or operation that failed to an error's Display output,
+/// keeping the original error reachable through `LooksRareApiError::root`.
+pub trait ErrorContext<T> {
+    fn context_endpoint(self, endpoint: impl Into<String>) -> Result<T, LooksRareApiError>;
+}
+
+impl<T, E: Into<LooksRareApiError>> ErrorContext<T> for Result<T, E> {
+    fn context_endpoint(self, endpoint: impl Into<String>) -> Result<T, LooksRareApiError> {
+        self.map_err(|err| LooksRareApiError::Context {
+            endpoint: endpoint.into(),
+            source: Box::new(err.into()),
+        })
+    }
+}
+
+/// Why the order creation endpoint refused an order, so callers can branch
+/// (resync the nonce, re-sign, or drop the order) without parsing messages.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum OrderRejection {
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("Nonce too low: {0}")]
+    NonceTooLow(String),
+    #[error("Unsupported currency: {0}")]
+    UnsupportedCurrency(String),
+    #[error("Order expired: {0}")]
+    Expired(String),
+    #[error("Order rejected: {0}")]
+    Other(String),
+}
+
+impl OrderRejection {
+    pub fn from_message(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let message = message.to_string();
+
+        if lower.contains("signature") {
+            OrderRejection::InvalidSignature(message)
+        } else if lower.contains("nonce") {
+            OrderRejection::NonceTooLow(message)
+        } else if lower.contains("currency") {
+            OrderRejection::UnsupportedCurrency(message)
+        } else if lower.contains("expired") || lower.contains("endtime") || lower.contains("end time") {
+            OrderRejection::Expired(message)
+        } else {
+            OrderRejection::Other(message)
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -269,6 +1573,28 @@ impl Sort {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum CollectionSort {
+    Alphabetical,
+    Newest,
+    Highest24h,
+    Highest7d,
+    HighestAll,
+}
+
+impl CollectionSort {
+    fn to_str(&self) -> &str {
+        match &self {
+            CollectionSort::Alphabetical => "ALPHABETICAL",
+            CollectionSort::Newest => "NEWEST",
+            CollectionSort::Highest24h => "HIGHEST_24H",
+            CollectionSort::Highest7d => "HIGHEST_7D",
+            CollectionSort::HighestAll => "HIGHEST_ALL",
+        }
+    }
+}
+
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Price {
     pub min: Option<u128>,
     pub max: Option<u128>,
@@ -277,6 +1603,477 @@ pub struct Price {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::order::OrderTemplate;
+    use crate::types::TokenFlag;
+    use ethers::prelude::{Signature, H256};
+
+    fn address() -> Address {
+        "0x3d67b76CF3dcc881255eb2262E788BE03b2f5B9F".parse().unwrap()
+    }
+
+    #[test]
+    fn empty_orders_request_has_no_query() {
+        let query = OrdersRequest::default().query(&Network::Mainnet).unwrap();
+        assert!(query.is_empty());
+    }
+
+    #[test]
+    fn orders_request_builds_query() {
+        let req = OrdersRequest {
+            is_order_ask: Some(true),
+            collection: Some("0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258".parse().unwrap()),
+            token_id: Some(62962),
+            price: Some(Price { min: Some(1), max: None }),
+            status: Some(vec![Status::Cancelled, Status::Expired]),
+            pagination: Some(Pagination {
+                first: Some(4),
+                cursor: Some(String::from("0xabc")),
+            }),
+            sort: Some(Sort::PriceAsc),
+            ..Default::default()
+        };
+
+        let query = req.query(&Network::Mainnet).unwrap();
+
+        assert_eq!(query, vec![
+            ("isOrderAsk", serde_json::json!(true)),
+            ("collection", serde_json::json!("0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258")),
+            ("tokenId", serde_json::json!("62962")),
+            ("price[min]", serde_json::json!("1")),
+            ("status[]", serde_json::json!("CANCELLED")),
+            ("status[]", serde_json::json!("EXPIRED")),
+            ("pagination[first]", serde_json::json!("4")),
+            ("pagination[cursor]", serde_json::json!("0xabc")),
+            ("sort", serde_json::json!("PRICE_ASC")),
+        ]);
+    }
+
+    #[test]
+    fn bid_requests_preset_side_and_strategy() {
+        let network = Network::Mainnet;
+        let collection: Address = "0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258".parse().unwrap();
+
+        let collection_bids = OrdersRequest::collection_bids(collection);
+        assert_eq!(collection_bids.is_order_ask, Some(false));
+        assert_eq!(collection_bids.strategy, Some(Strategy::CollectionOffer));
+        assert_eq!(collection_bids.token_id, None);
+
+        let token_bids = OrdersRequest::token_bids(collection, 42).query(&network).unwrap();
+        assert!(token_bids.contains(&("tokenId", serde_json::json!("42"))));
+        assert!(token_bids.contains(&("sort", serde_json::json!("PRICE_DESC"))));
+        assert!(token_bids.contains(&(
+            "strategy",
+            serde_json::to_value(network.strategy(Strategy::StandardSale)).unwrap()
+        )));
+    }
+
+    #[test]
+    fn custom_strategy_is_sent_as_given() {
+        let req = OrdersRequest {
+            strategy: Some(Strategy::Custom(address())),
+            ..Default::default()
+        };
+
+        assert_eq!(req.query(&Network::Mainnet).unwrap(), vec![
+            ("strategy", serde_json::json!("0x3d67b76cf3dcc881255eb2262e788be03b2f5b9f")),
+        ]);
+    }
+
+    #[test]
+    fn extra_params_follow_typed_filters() {
+        let req = OrdersRequest {
+            is_order_ask: Some(false),
+            ..Default::default()
+        }
+        .extra_params(vec![(String::from("newFilter"), String::from("1"))]);
+
+        let request = Client::new()
+            .get("https://api.looksrare.org/api/v1/orders")
+            .query(&req.query(&Network::Mainnet).unwrap())
+            .query(&req.extra_params)
+            .build()
+            .unwrap();
+
+        assert_eq!(request.url().query(), Some("isOrderAsk=false&newFilter=1"));
+    }
+
+    #[test]
+    fn collections_request_to_query() {
+        let req = CollectionsRequest {
+            pagination: Some(Pagination {
+                first: Some(20),
+                cursor: Some(String::from("0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258")),
+            }),
+            sort: Some(CollectionSort::Highest24h),
+        };
+
+        assert_eq!(req.query().unwrap(), vec![
+            ("pagination[first]", serde_json::json!("20")),
+            ("pagination[cursor]", serde_json::json!("0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258")),
+            ("sort", serde_json::json!("HIGHEST_24H")),
+        ]);
+    }
+
+    #[test]
+    fn events_request_to_query() {
+        let req = EventsRequest {
+            collection: Some("0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258".parse().unwrap()),
+            token_id: Some(42),
+            from: Some(address()),
+            event_type: Some(EventType::CancelOffer),
+            ..Default::default()
+        };
+
+        assert_eq!(req.query().unwrap(), vec![
+            ("collection", serde_json::json!("0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258")),
+            ("tokenId", serde_json::json!("42")),
+            ("from", serde_json::json!("0x3d67b76cf3dcc881255eb2262e788be03b2f5b9f")),
+            ("type", serde_json::json!("CANCEL_OFFER")),
+        ]);
+    }
+
+    #[test]
+    fn status_to_str() {
+        assert_eq!(Status::Cancelled.to_str(), "CANCELLED");
+        assert_eq!(Status::Executed.to_str(), "EXECUTED");
+        assert_eq!(Status::Expired.to_str(), "EXPIRED");
+        assert_eq!(Status::Valid.to_str(), "VALID");
+    }
+
+    #[test]
+    fn sort_to_str() {
+        assert_eq!(Sort::ExpiringSoon.to_str(), "EXPIRING_SOON");
+        assert_eq!(Sort::Newest.to_str(), "NEWEST");
+        assert_eq!(Sort::PriceAsc.to_str(), "PRICE_ASC");
+        assert_eq!(Sort::PriceDesc.to_str(), "PRICE_DESC");
+    }
+
+    #[test]
+    fn missing_account_maps_to_account_not_found() {
+        let text = r#"{"success":true,"message":null,"data":null}"#;
+        let res = parse_account_response(text, address(), ParseMode::Strict);
+        assert!(matches!(res, Err(LooksRareApiError::AccountNotFound { .. })));
+    }
+
+    #[test]
+    fn missing_orders_maps_to_orders_not_found() {
+        let text = r#"{"success":false,"message":"error","data":null}"#;
+        let res = parse_orders_response(text, ParseMode::Strict);
+        assert!(matches!(res, Err(LooksRareApiError::OrdersNotFound)));
+    }
+
+    #[test]
+    fn nonce_is_parsed() {
+        let text = r#"{"success":true,"message":null,"data":"17832"}"#;
+        assert_eq!(parse_nonce_response(text, address(), ParseMode::Strict).unwrap(), U256::from(17832));
+
+        let text = r#"{"success":true,"message":null,"data":"340282366920938463463374607431768211456"}"#;
+        assert_eq!(parse_nonce_response(text, address(), ParseMode::Strict).unwrap(), U256::from(u128::MAX) + 1);
+
+        let text = r#"{"success":true,"message":null,"data":null}"#;
+        let res = parse_nonce_response(text, address(), ParseMode::Strict);
+        assert!(matches!(res, Err(LooksRareApiError::NonceNotFound { .. })));
+    }
+
+    #[test]
+    fn builder_selects_the_api_host() {
+        let api = LooksRareApi::new_with_network(Network::Mainnet);
+        assert_eq!(api.api(), "https://api.looksrare.org/api/v1");
+
+        let api = LooksRareApi::builder().base_url("http://localhost:8080/").build();
+        assert_eq!(api.api(), "http://localhost:8080/api/v1");
+        assert_eq!(api.network(), &Network::Mainnet);
+
+        let api = LooksRareApi::builder().base_url("https://API.looksrare.org").try_build();
+        assert!(api.is_ok());
+        let api = LooksRareApi::builder().base_url("https://api-goerli.looksrare.org/").try_build();
+        assert!(matches!(api, Err(LooksRareApiError::WrongNetwork { .. })));
+        let api = LooksRareApi::builder().base_url("not a url").try_build();
+        assert!(matches!(api, Err(LooksRareApiError::InvalidBaseUrl(_))));
+    }
+
+    #[test]
+    fn replays_recorded_responses() {
+        let recording = |endpoint: &str, url: &str, status: u16, body: &str| Recording {
+            recorded_at: chrono::Utc::now(),
+            endpoint: endpoint.to_string(),
+            url: url.to_string(),
+            status,
+            body: body.to_string(),
+        };
+
+        let orders = recording("/orders", "https://api.looksrare.org/api/v1/orders", 200, r#"{"success":true,"data":[]}"#);
+        assert_eq!(orders.parse::<Vec<Order>>(ParseMode::Strict).unwrap().len(), 0);
+
+        let url = format!("https://api.looksrare.org/api/v1/accounts?address={:?}", address());
+        let missing = recording("/accounts", &url, 404, r#"{"success":false,"data":null}"#);
+        assert!(matches!(
+            missing.parse::<Account>(ParseMode::Strict),
+            Err(LooksRareApiError::AccountNotFound { address: found }) if found == address()
+        ));
+
+        let limited = recording("/orders", "https://api.looksrare.org/api/v1/orders", 429, "");
+        assert!(matches!(limited.parse::<Vec<Order>>(ParseMode::Strict), Err(LooksRareApiError::RateLimited { .. })));
+    }
+
+    #[test]
+    fn orders_stream_follows_cursors_until_a_short_page() {
+        let order = |hash: &str| {
+            let mut order = crate::fixtures::order();
+            order.hash = hash.to_string();
+            order
+        };
+        let mut cursors = vec![];
+        let fetch = |cursor: Option<String>| {
+            cursors.push(cursor.clone());
+            let page = match cursor.as_deref() {
+                None => vec![order("a"), order("b")],
+                Some("b") => vec![order("c"), order("d")],
+                _ => vec![order("e")],
+            };
+            async move { Ok(OrdersPage::new(page, 2)) }
+        };
+
+        let orders: Vec<Order> = futures::executor::block_on(paginate(fetch, None).try_collect()).unwrap();
+
+        let hashes: Vec<&str> = orders.iter().map(|order| order.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["a", "b", "c", "d", "e"]);
+        assert_eq!(cursors, vec![None, Some(String::from("b")), Some(String::from("d"))]);
+        assert_eq!(OrdersPage::new(vec![], 2).next_cursor, None);
+    }
+
+    #[test]
+    fn builder_rate_limits_by_default() {
+        let api = LooksRareApi::new();
+        assert_eq!(api.rate_limit_status().remaining, Some(constants::DEFAULT_REQUESTS_PER_MINUTE));
+
+        let api = LooksRareApi::builder().rate_limit(10, Duration::from_secs(1)).build();
+        assert_eq!(api.rate_limit_status().remaining, Some(10));
+
+        assert_eq!(LooksRareApi::builder().unlimited().build().rate_limit_status().remaining, None);
+    }
+
+    #[test]
+    fn api_key_is_sent_and_rotated_across_clones() {
+        let api = LooksRareApi::builder().api_key("first").build();
+        let clone = api.with_priority(Priority::Low);
+        let header = |api: &LooksRareApi| {
+            let mut request = api.client.get("https://api.looksrare.org/api/v1/orders").build().unwrap();
+            api.authorize(&mut request).unwrap();
+            request.headers().get(constants::API_KEY_HEADER).map(|value| value.to_str().unwrap().to_string())
+        };
+
+        assert_eq!(header(&api).as_deref(), Some("first"));
+        assert!(!format!("{:?}", api).contains("first"));
+
+        api.set_api_key(Some(String::from("second")));
+        assert_eq!(header(&clone).as_deref(), Some("second"));
+
+        clone.set_api_key(None);
+        assert_eq!(header(&api), None);
+        assert!(!api.has_api_key());
+    }
+
+    #[test]
+    fn token_is_parsed_with_flag() {
+        let text = r#"{"success":true,"message":null,"data":{
+            "collectionAddress":"0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258","tokenId":"42",
+            "tokenURI":"ipfs://QmToken/42","imageURI":"https://static.looksnice.org/42.png",
+            "name":"Token #42","description":null,"isExplicit":false,"flag":"FLAGGED",
+            "attributes":[
+                {"traitType":"Background","value":"Blue","displayType":null},
+                {"traitType":"Level","value":3,"displayType":"number"}
+            ]
+        }}"#;
+        let token = parse_token_response(text, address(), U256::from(42), ParseMode::Strict).unwrap();
+
+        assert_eq!(token.token_uri.as_deref(), Some("ipfs://QmToken/42"));
+        assert_eq!(token.attributes[0].trait_type, "Background");
+        assert_eq!(token.attributes[1].value, 3);
+        assert!(token.is_flagged());
+
+        let text = text.replace("FLAGGED", "QUARANTINED");
+        let token = parse_token_response(&text, address(), U256::from(42), ParseMode::Strict).unwrap();
+        assert_eq!(token.flag, TokenFlag::Unknown);
+
+        let text = r#"{"success":true,"message":null,"data":null}"#;
+        let res = parse_token_response(text, address(), U256::from(42), ParseMode::Strict);
+        assert!(matches!(res, Err(LooksRareApiError::TokenNotFound { .. })));
+    }
+
+    #[test]
+    fn rewards_are_parsed_with_proofs() {
+        let text = format!(
+            r#"{{"success":true,"message":null,"data":{{"trading":{{"amount":"1500000000000000000","proof":["0x{}"]}},"listing":null}}}}"#,
+            "11".repeat(32)
+        );
+        let rewards = parse_rewards_response(&text, address(), ParseMode::Strict).unwrap();
+
+        let trading = rewards.trading.unwrap();
+        assert_eq!(trading.amount, "1500000000000000000");
+        assert_eq!(trading.proof, vec![H256::repeat_byte(0x11)]);
+        assert!(rewards.listing.is_none());
+
+        let text = r#"{"success":false,"message":"Not found","data":null}"#;
+        let res = parse_rewards_response(text, address(), ParseMode::Strict);
+        assert!(matches!(res, Err(LooksRareApiError::RewardsNotFound { .. })));
+    }
+
+    #[test]
+    fn error_statuses_are_typed() {
+        let res = status_error(StatusCode::TOO_MANY_REQUESTS, Some(Duration::from_secs(3)), "");
+        assert!(matches!(res, Some(LooksRareApiError::RateLimited { retry_after: Some(after) }) if after == Duration::from_secs(3)));
+
+        let res = status_error(StatusCode::BAD_GATEWAY, None, "<html>");
+        assert!(matches!(res, Some(LooksRareApiError::ServerError { status: 502 })));
+
+        let res = status_error(StatusCode::BAD_REQUEST, None, r#"{"success":false,"message":"Invalid address"}"#);
+        assert!(matches!(res, Some(LooksRareApiError::BadRequest { message }) if message == "Invalid address"));
+
+        let res = status_error(StatusCode::UNAUTHORIZED, None, "");
+        assert!(matches!(res, Some(LooksRareApiError::BadRequest { message }) if message == "Unauthorized"));
+
+        assert!(status_error(StatusCode::NOT_FOUND, None, "").is_none());
+        assert!(status_error(StatusCode::OK, None, "").is_none());
+    }
+
+    #[test]
+    fn order_rejections_are_classified() {
+        assert!(matches!(OrderRejection::from_message("Invalid signature"), OrderRejection::InvalidSignature(_)));
+        assert!(matches!(OrderRejection::from_message("Nonce is too low"), OrderRejection::NonceTooLow(_)));
+        assert!(matches!(OrderRejection::from_message("Currency not whitelisted"), OrderRejection::UnsupportedCurrency(_)));
+        assert!(matches!(OrderRejection::from_message("Order has expired"), OrderRejection::Expired(_)));
+        assert!(matches!(OrderRejection::from_message("endTime must be in the future"), OrderRejection::Expired(_)));
+        assert_eq!(OrderRejection::from_message("Unknown"), OrderRejection::Other(String::from("Unknown")));
+    }
+
+    #[test]
+    fn create_order_body_uses_decimal_strings() {
+        let template = OrderTemplate::ask(&Network::Mainnet, address(), address());
+        let signed = SignedOrder {
+            order: template.instantiate(U256::from(7), U256::exp10(18), U256::from(3), 1_000),
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 28,
+            },
+        };
+
+        let body = serde_json::to_value(CreateOrderRequest::from(&signed)).unwrap();
+
+        assert_eq!(body["tokenId"], "7");
+        assert_eq!(body["price"], "1000000000000000000");
+        assert_eq!(body["nonce"], "3");
+        assert_eq!(body["params"], "0x");
+        assert!(body["signature"].as_str().unwrap().ends_with("1c"));
+    }
+
+    #[test]
+    fn refused_order_maps_to_rejection() {
+        let text = r#"{"success":false,"message":"Nonce is too low","data":null}"#;
+        let res = parse_create_order_response(text, ParseMode::Strict);
+        assert!(matches!(res, Err(LooksRareApiError::OrderRejected(OrderRejection::NonceTooLow(_)))));
+    }
+
+    #[test]
+    fn malformed_body_maps_to_serde_error() {
+        let res = parse_orders_response("<html>Too Many Requests</html>", ParseMode::Strict);
+        assert!(matches!(res, Err(LooksRareApiError::SerdeJson(_))));
+    }
+
+    #[test]
+    fn lenient_mode_fills_missing_fields() {
+        let text = r#"{"success":true,"message":null,"data":{"address":"0x3d67b76cf3dcc881255eb2262e788be03b2f5b9f","isVerified":null}}"#;
+
+        assert!(matches!(
+            parse_account_response(text, address(), ParseMode::Strict),
+            Err(LooksRareApiError::SerdeJson(_))
+        ));
+
+        let account = parse_account_response(text, address(), ParseMode::Lenient).unwrap();
+        assert!(!account.is_verified);
+        assert_eq!(account.name, None);
+    }
+
+    #[test]
+    fn lenient_mode_drops_items_missing_identity_fields() {
+        let event = serde_json::to_value(Event { id: 1, ..Default::default() }).unwrap();
+        let mut nested = event.clone();
+        nested["id"] = 2.into();
+        nested["collection"] = serde_json::json!({ "address": format!("{:?}", address()) });
+        let mut undated = event.clone();
+        undated["id"] = 3.into();
+        undated.as_object_mut().unwrap().remove("createdAt");
+        let text = serde_json::json!({ "success": true, "data": [event, nested, undated] }).to_string();
+
+        assert!(parse_events_response(&text, ParseMode::Strict).is_err());
+
+        let events = parse_events_response(&text, ParseMode::Lenient).unwrap();
+        assert_eq!(events.iter().map(|event| event.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(events[1].collection.is_none());
+    }
+
+    #[test]
+    fn context_keeps_the_root_error() {
+        let res: Result<(), _> = Err(LooksRareApiError::OrdersNotFound);
+        let err = res.context_endpoint("orders").context_endpoint("floor sweep").unwrap_err();
+
+        assert_eq!(err.to_string(), "floor sweep: orders: Orders not found");
+        assert!(matches!(err.root(), LooksRareApiError::OrdersNotFound));
+    }
+
+    /// Serves a truncated page tagged `"v1"`, then 304 to any request sent with
+    /// `If-None-Match` and an empty page otherwise.
+    fn serve_truncated_then_empty() -> String {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().take(2).enumerate() {
+                let mut stream = stream.unwrap();
+                let mut conditional = false;
+                for line in BufReader::new(stream.try_clone().unwrap()).lines() {
+                    let line = line.unwrap();
+                    conditional |= line.to_ascii_lowercase().starts_with("if-none-match:");
+                    if line.is_empty() {
+                        break;
+                    }
+                }
+
+                let (status, body) = match (i, conditional) {
+                    (0, _) => ("200 OK", r#"{"success":true,"data":[{"hash":"#),
+                    (_, true) => ("304 Not Modified", ""),
+                    (_, false) => ("200 OK", r#"{"success":true,"data":[]}"#),
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn failed_conditional_get_is_fetched_again() {
+        let api = LooksRareApi::builder().base_url(serve_truncated_then_empty()).unlimited().build();
+
+        assert!(api.get_orders_if_modified(OrdersRequest::default()).await.is_err());
+
+        let orders = api.get_orders_if_modified(OrdersRequest::default()).await.unwrap();
+        assert_eq!(orders.map(|orders| orders.len()), Some(0));
+    }
+}
+
+#[cfg(all(test, feature = "live-tests"))]
+mod live_tests {
     use crate::types::Account;
 
     use super::*;
@@ -301,8 +2098,8 @@ mod tests {
             token_id: Some(62962),
             signer: Some("0x9E69b59b8d2A094CB1117f92Ff7DCf51Ed467B41".parse().unwrap()),
             nonce: Some(17832),
-            strategy: Some("0x579af6fd30bf83a5ac0d636bc619f98dbdeb930c".parse().unwrap()), 
-            currency: Some("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2".parse().unwrap()), 
+            strategy: Some(Strategy::StandardSale), 
+            currency: Some(Currency::Weth), 
             price: Some(Price{
                 min: Some(12000000000000000000000000000000000000),
                 max: Some(13000000000000000000000000000000000000),                
@@ -315,6 +2112,7 @@ mod tests {
                 cursor: None,
             }),
             sort: Some(Sort::Newest), 
+            extra_params: vec![],
         };
         
         let input_is_order_ask: bool = req.is_order_ask.unwrap();
@@ -322,8 +2120,8 @@ mod tests {
         let input_token_id: u64 = req.clone().token_id.unwrap();
         let input_signer: Address = req.signer.unwrap();
         let input_nonce: u64 = req.clone().nonce.unwrap();
-        let input_strategy: Address = req.strategy.unwrap();
-        let input_currency: Address = req.currency.unwrap();
+        let input_strategy: Address = Network::Mainnet.strategy(req.strategy.unwrap());
+        let input_currency: Address = Network::Mainnet.currency(req.currency.unwrap());
         let input_min_price: u128 = req.price.clone().unwrap().min.unwrap();
         let input_max_price: u128 = req.price.clone().unwrap().max.unwrap();
         let input_start_time: u64 = req.start_time.unwrap();
@@ -382,6 +2180,7 @@ mod tests {
                 cursor: Some(String::from("0xd12240238374bbb1b23078fc71feeffa1d6c54b81888dfc5d9ea54d17c6a30a7")),
             }),
             sort: None, 
+            extra_params: vec![],
         };
         
         let input_pagination_first: usize = req.clone().pagination.unwrap().first.unwrap().try_into().unwrap();
@@ -427,7 +2226,6 @@ mod tests {
         let top_5_listing_rewards_collections: Vec<CollectionRewards> = api.get_top_5_listing_rewards_collections().await.unwrap();
 
         let num_of_collections: usize = top_5_listing_rewards_collections.len();
-        println!("{}", num_of_collections);
         assert_eq!(num_of_collections, 5);
     }
 }
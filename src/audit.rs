@@ -0,0 +1,268 @@
+use crate::order::MakerOrder;
+use chrono::{DateTime, Utc};
+use ethers::prelude::{Address, H256, U256};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use thiserror::Error;
+
+/// A mutating action taken through the client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AuditAction {
+    OrderCreated,
+    OrderCancelled,
+    OrderExecuted,
+    /// A signed order shared outside the API, e.g. OTC, taken in for execution.
+    OrderImported,
+    /// A token sent on from the client's account, e.g. to a vault after a purchase.
+    TokenTransferred,
+    /// An order signed, or refused, by a `SessionKey`.
+    OrderSigned,
+    /// An allowance granted by a cold key to a `SessionKey`.
+    AllowanceGranted,
+}
+
+impl AuditAction {
+    pub fn to_str(&self) -> &str {
+        match &self {
+            AuditAction::OrderCreated => "ORDER_CREATED",
+            AuditAction::OrderCancelled => "ORDER_CANCELLED",
+            AuditAction::OrderExecuted => "ORDER_EXECUTED",
+            AuditAction::OrderImported => "ORDER_IMPORTED",
+            AuditAction::TokenTransferred => "TOKEN_TRANSFERRED",
+            AuditAction::OrderSigned => "ORDER_SIGNED",
+            AuditAction::AllowanceGranted => "ALLOWANCE_GRANTED",
+        }
+    }
+}
+
+/// An order a record acts on, by the signer and nonce that identify it on chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OrderRef {
+    pub signer: Address,
+    pub nonce: U256,
+}
+
+impl From<&MakerOrder> for OrderRef {
+    fn from(order: &MakerOrder) -> Self {
+        Self {
+            signer: order.signer,
+            nonce: order.nonce,
+        }
+    }
+}
+
+/// One line of the audit log.
+///
+/// `hash` covers every other field, including the previous record's hash, so
+/// editing, dropping or reordering a record breaks the chain from there on.
+/// Dropping records from the end leaves an intact, shorter chain; compare the
+/// last hash against one kept elsewhere to detect that, see `verify_head`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    pub action: AuditAction,
+    /// The orders acted on, e.g. the one filled or every nonce cancelled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub orders: Vec<OrderRef>,
+    /// Keccak-256 of the action's payload as JSON, e.g. the signed order or the cancelled nonces.
+    pub payload_digest: H256,
+    pub ok: bool,
+    /// The order or transaction hash on success, the error otherwise.
+    pub detail: Option<String>,
+    pub prev_hash: H256,
+    pub hash: H256,
+}
+
+impl AuditRecord {
+    fn seal(&self) -> H256 {
+        let unsealed = AuditRecord { hash: H256::zero(), ..self.clone() };
+        let json = serde_json::to_vec(&unsealed).expect("audit records always serialize");
+        H256(looksrare_core::keccak256(&json))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("Audit log was tampered with at record {seq}")]
+    Tampered { seq: u64 },
+    #[error("Audit log does not end at record {expected:?}")]
+    Truncated { expected: H256 },
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// An append-only, hash-chained log of orders created, signed, cancelled,
+/// imported and executed, and tokens transferred, stored as JSON lines.
+///
+/// Attach it with `LooksRareApi::audit_log`; see `verify` and `export_json`.
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    head: Mutex<(u64, H256)>,
+}
+
+impl AuditLog {
+    /// Opens the log at `path`, creating it on the first append.
+    ///
+    /// Fails if the records already there don't form an intact chain.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, AuditError> {
+        let path = path.into();
+        let records = match path.exists() {
+            true => verify(&path)?,
+            false => vec![],
+        };
+        let head = records.last().map_or((0, H256::zero()), |last| (last.seq + 1, last.hash));
+
+        Ok(Self {
+            path,
+            head: Mutex::new(head),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The hash of the last record, or zero for an empty log. Keep it where
+    /// whoever can write the log can't, to check it later with `verify_head`.
+    pub fn head(&self) -> H256 {
+        self.head.lock().unwrap().1
+    }
+
+    /// Appends a record of `action` on `payload` and flushes it to disk.
+    pub fn append<P: Serialize>(
+        &self,
+        action: AuditAction,
+        orders: Vec<OrderRef>,
+        payload: &P,
+        ok: bool,
+        detail: Option<String>,
+    ) -> Result<AuditRecord, AuditError> {
+        let mut head = self.head.lock().unwrap();
+
+        let mut record = AuditRecord {
+            seq: head.0,
+            at: Utc::now(),
+            action,
+            orders,
+            payload_digest: digest(payload)?,
+            ok,
+            detail,
+            prev_hash: head.1,
+            hash: H256::zero(),
+        };
+        record.hash = record.seal();
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        file.sync_data()?;
+
+        *head = (record.seq + 1, record.hash);
+        Ok(record)
+    }
+
+    /// Every record, verified, oldest first.
+    pub fn records(&self) -> Result<Vec<AuditRecord>, AuditError> {
+        let _head = self.head.lock().unwrap();
+        match self.path.exists() {
+            true => verify(&self.path),
+            false => Ok(vec![]),
+        }
+    }
+
+    /// Every record, verified, as one JSON array for compliance review.
+    pub fn export_json(&self) -> Result<Value, AuditError> {
+        Ok(serde_json::to_value(self.records()?)?)
+    }
+}
+
+/// The `payload_digest` a record of `payload` gets.
+pub fn digest<P: Serialize>(payload: &P) -> Result<H256, AuditError> {
+    Ok(H256(looksrare_core::keccak256(&serde_json::to_vec(payload)?)))
+}
+
+/// Reads the log at `path`, checking every record's hash and link to the one before.
+pub fn verify(path: impl AsRef<Path>) -> Result<Vec<AuditRecord>, AuditError> {
+    let text = fs::read_to_string(path)?;
+    let mut records: Vec<AuditRecord> = vec![];
+
+    for (seq, line) in text.lines().enumerate() {
+        let seq = seq as u64;
+        let record: AuditRecord = serde_json::from_str(line).map_err(|_| AuditError::Tampered { seq })?;
+        let prev_hash = records.last().map_or(H256::zero(), |prev| prev.hash);
+
+        if record.seq != seq || record.prev_hash != prev_hash || record.hash != record.seal() {
+            return Err(AuditError::Tampered { seq });
+        }
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Like `verify`, but also fails if the last record isn't `head`, as returned
+/// by `AuditLog::head` earlier, so records dropped from the end are caught.
+pub fn verify_head(path: impl AsRef<Path>, head: H256) -> Result<Vec<AuditRecord>, AuditError> {
+    let records = verify(path)?;
+    let last = records.last().map_or(H256::zero(), |last| last.hash);
+
+    match last == head {
+        true => Ok(records),
+        false => Err(AuditError::Truncated { expected: head }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chains_records_and_detects_tampering() {
+        let path = std::env::temp_dir().join(format!("looksrare-audit-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let log = AuditLog::open(&path).unwrap();
+        let first = log.append(AuditAction::OrderCreated, vec![], &"order", true, Some(String::from("0x01"))).unwrap();
+        let cancelled = OrderRef {
+            signer: Address::from_low_u64_be(1),
+            nonce: U256::from(2),
+        };
+        log.append(AuditAction::OrderCancelled, vec![cancelled], &vec![2], false, Some(String::from("reverted"))).unwrap();
+        assert_eq!(first.prev_hash, H256::zero());
+
+        // reopening continues the chain
+        let reopened = AuditLog::open(&path).unwrap();
+        let third = reopened.append(AuditAction::OrderExecuted, vec![], &"tx", true, None).unwrap();
+        assert_eq!(third.seq, 2);
+        assert_eq!(reopened.records().unwrap()[1].orders, vec![cancelled]);
+        assert_eq!(reopened.export_json().unwrap().as_array().unwrap().len(), 3);
+
+        // a copy without its last record still chains, but not to the head
+        let text = fs::read_to_string(&path).unwrap();
+        let truncated = path.with_extension("truncated");
+        fs::write(&truncated, text.lines().take(2).collect::<Vec<_>>().join("\n")).unwrap();
+        assert_eq!(verify(&truncated).unwrap().len(), 2);
+        assert!(verify_head(&path, reopened.head()).is_ok());
+        assert!(matches!(
+            verify_head(&truncated, reopened.head()),
+            Err(AuditError::Truncated { .. })
+        ));
+        let _ = fs::remove_file(&truncated);
+
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replacen("\"ok\":false", "\"ok\":true", 1)).unwrap();
+        assert!(matches!(verify(&path), Err(AuditError::Tampered { seq: 1 })));
+        assert!(AuditLog::open(&path).is_err());
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::api::{EventType, EventsRequest, LooksRareApi, Pagination};
+use crate::constants;
+use crate::types::{Event, Order};
+use crate::ClientError;
+use ethers::prelude::{Address, Middleware, U256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Thresholds for spotting listings placed to fake a lower floor.
+///
+/// A listing is only flagged when it is far below the floor and its signer
+/// also looks disposable, so genuine panic sales from established wallets pass.
+#[derive(Clone, Debug)]
+pub struct BaitHeuristics {
+    /// Listings more than this many percent below the floor are considered far below it.
+    pub max_discount: u64,
+    /// Signers that have sent at most this many transactions count as fresh wallets.
+    pub fresh_wallet_max_txs: u64,
+    /// A listing cancelled within this window of being placed counts as a quick cancel.
+    pub quick_cancel_window: Duration,
+    /// Signers with at least this many recent quick cancels count as serial cancellers.
+    pub quick_cancel_threshold: usize,
+}
+
+impl Default for BaitHeuristics {
+    fn default() -> Self {
+        Self {
+            max_discount: 50,
+            fresh_wallet_max_txs: 5,
+            quick_cancel_window: Duration::from_secs(600),
+            quick_cancel_threshold: 2,
+        }
+    }
+}
+
+/// What is known about a signer's history.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SignerHistory {
+    pub tx_count: Option<u64>,
+    pub quick_cancels: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Suspicion {
+    FarBelowFloor { price: U256, floor: U256 },
+    FreshWallet { tx_count: u64 },
+    QuickCancels { count: usize },
+}
+
+/// A listing together with everything that looked off about it.
+#[derive(Clone, Debug)]
+pub struct Assessment {
+    pub order: Order,
+    pub reasons: Vec<Suspicion>,
+}
+
+impl Assessment {
+    /// True when the listing is far below the floor from a fresh wallet or a serial canceller.
+    pub fn is_suspicious(&self) -> bool {
+        let below_floor = self
+            .reasons
+            .iter()
+            .any(|reason| matches!(reason, Suspicion::FarBelowFloor { .. }));
+        below_floor && self.reasons.len() > 1
+    }
+}
+
+impl BaitHeuristics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assesses each ask against `floor`, using what is known about the signers.
+    pub fn assess(
+        &self,
+        asks: &[Order],
+        floor: Option<U256>,
+        signers: &HashMap<Address, SignerHistory>,
+    ) -> Vec<Assessment> {
+        asks.iter()
+            .map(|order| {
+                let mut reasons = vec![];
+                let price = U256::from_dec_str(&order.price).unwrap_or_default();
+
+                if let Some(floor) = floor.filter(|floor| !floor.is_zero()) {
+                    if price * U256::from(100) < floor * U256::from(100u64.saturating_sub(self.max_discount)) {
+                        reasons.push(Suspicion::FarBelowFloor { price, floor });
+                    }
+                }
+
+                let history = signers.get(&order.signer).cloned().unwrap_or_default();
+                if let Some(tx_count) = history.tx_count.filter(|count| *count <= self.fresh_wallet_max_txs) {
+                    reasons.push(Suspicion::FreshWallet { tx_count });
+                }
+                if history.quick_cancels >= self.quick_cancel_threshold {
+                    reasons.push(Suspicion::QuickCancels {
+                        count: history.quick_cancels,
+                    });
+                }
+
+                Assessment {
+                    order: order.clone(),
+                    reasons,
+                }
+            })
+            .collect()
+    }
+
+    /// Fetches the collection floor, signer transaction counts and recent
+    /// list/cancel events, then assesses `asks`.
+    pub async fn flag_listings<M: Middleware>(
+        &self,
+        api: &LooksRareApi,
+        provider: Arc<M>,
+        collection: Address,
+        asks: &[Order],
+    ) -> Result<Vec<Assessment>, ClientError> {
+        let stats = api.get_collection_stats(collection).await?;
+        let floor = stats.floor_price;
+
+        let lists = api.get_events(recent_events(collection, EventType::List)).await?;
+        let cancels = api.get_events(recent_events(collection, EventType::CancelList)).await?;
+        let quick_cancels = quick_cancels(&lists, &cancels, self.quick_cancel_window);
+
+        let mut signers = HashMap::new();
+        for signer in asks.iter().map(|order| order.signer).collect::<HashSet<_>>() {
+            let tx_count = provider
+                .get_transaction_count(signer, None)
+                .await
+                .map_err(|e| ClientError::ProviderError(e.to_string()))?;
+
+            signers.insert(
+                signer,
+                SignerHistory {
+                    tx_count: Some(tx_count.low_u64()),
+                    quick_cancels: quick_cancels.get(&signer).copied().unwrap_or_default(),
+                },
+            );
+        }
+
+        Ok(self.assess(asks, floor, &signers))
+    }
+}
+
+/// The asks that were not flagged, for floor calculations that should ignore likely bait.
+pub fn exclude_suspicious(assessments: &[Assessment]) -> Vec<Order> {
+    assessments
+        .iter()
+        .filter(|assessment| !assessment.is_suspicious())
+        .map(|assessment| assessment.order.clone())
+        .collect()
+}
+
+fn recent_events(collection: Address, event_type: EventType) -> EventsRequest {
+    EventsRequest {
+        collection: Some(collection),
+        event_type: Some(event_type),
+        pagination: Some(Pagination {
+            first: Some(constants::MAX_ORDERS_PER_PAGE),
+            cursor: None,
+        }),
+        ..Default::default()
+    }
+}
+
+/// Counts, per signer, listings cancelled within `window` of being placed on the same token.
+fn quick_cancels(lists: &[Event], cancels: &[Event], window: Duration) -> HashMap<Address, usize> {
+    let window = chrono::Duration::seconds(window.as_secs() as i64);
+    let mut counts = HashMap::new();
+
+    for cancel in cancels {
+        let token = cancel.token.as_ref().map(|token| &token.token_id);
+        let quick = lists.iter().any(|list| {
+            list.from == cancel.from
+                && list.token.as_ref().map(|token| &token.token_id) == token
+                && list.created_at <= cancel.created_at
+                && cancel.created_at - list.created_at <= window
+        });
+
+        if quick {
+            *counts.entry(cancel.from).or_default() += 1;
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn ask(signer: u64, price: &str) -> Order {
+        let mut order = fixtures::order();
+        order.signer = Address::from_low_u64_be(signer);
+        order.price = price.to_string();
+        order
+    }
+
+    #[test]
+    fn flags_cheap_listings_from_fresh_wallets_only() {
+        let heuristics = BaitHeuristics::new();
+        let floor = Some(U256::from(1000));
+        let signers = HashMap::from([
+            (Address::from_low_u64_be(1), SignerHistory { tx_count: Some(1), quick_cancels: 0 }),
+            (Address::from_low_u64_be(2), SignerHistory { tx_count: Some(900), quick_cancels: 0 }),
+        ]);
+
+        let assessments = heuristics.assess(&[ask(1, "100"), ask(2, "100"), ask(1, "990")], floor, &signers);
+
+        assert!(assessments[0].is_suspicious());
+        assert!(!assessments[1].is_suspicious());
+        assert!(!assessments[2].is_suspicious());
+        assert_eq!(assessments[2].reasons, vec![Suspicion::FreshWallet { tx_count: 1 }]);
+        assert_eq!(exclude_suspicious(&assessments).len(), 2);
+    }
+
+    #[test]
+    fn counts_quick_cancels() {
+        let lists = vec![fixtures::event("LIST", "2022-11-06T16:00:00Z")];
+        let cancels = vec![
+            fixtures::event("CANCEL_LIST", "2022-11-06T16:05:00Z"),
+            fixtures::event("CANCEL_LIST", "2022-11-06T18:00:00Z"),
+        ];
+
+        let counts = quick_cancels(&lists, &cancels, Duration::from_secs(600));
+
+        assert_eq!(counts.get(&lists[0].from), Some(&1));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::rates::{ConvertedPrice, Converter};
+use crate::types::Order;
+use ethers::prelude::{Address, U256};
+use std::collections::HashMap;
+
+/// Several valid asks from one signer for the same token, e.g. a relisting that did not cancel the old nonce.
+#[derive(Clone, Debug)]
+pub struct DuplicateListing {
+    pub signer: Address,
+    pub collection: Address,
+    pub token_id: String,
+    /// The asks, cheapest first.
+    pub orders: Vec<Order>,
+}
+
+/// Whether an order is still valid and inside its time window at `now`.
+pub fn is_live(order: &Order, now: u64) -> bool {
+    order.status == "VALID" && order.start_time <= now && now <= order.end_time
+}
+
+/// Whether an ask can be filled at `now`.
+pub fn is_executable(order: &Order, now: u64) -> bool {
+    order.is_order_ask && is_live(order, now)
+}
+
+/// Finds tokens with more than one valid ask from the same signer.
+pub fn duplicate_listings(asks: &[Order]) -> Vec<DuplicateListing> {
+    let mut grouped: HashMap<(Address, Address, &str), Vec<&Order>> = HashMap::new();
+    for order in asks.iter().filter(|order| order.is_order_ask && order.status == "VALID") {
+        grouped
+            .entry((order.signer, order.collection_address, order.token_id.as_str()))
+            .or_default()
+            .push(order);
+    }
+
+    let mut duplicates: Vec<DuplicateListing> = grouped
+        .into_iter()
+        .filter(|(_, orders)| orders.len() > 1)
+        .map(|((signer, collection, token_id), orders)| {
+            let mut orders: Vec<Order> = orders.into_iter().cloned().collect();
+            orders.sort_by_key(price);
+            DuplicateListing {
+                signer,
+                collection,
+                token_id: token_id.to_string(),
+                orders,
+            }
+        })
+        .collect();
+
+    duplicates.sort_by(|a, b| (a.collection, &a.token_id).cmp(&(b.collection, &b.token_id)));
+    duplicates
+}
+
+/// Picks the ask that would actually be filled for a token: the cheapest executable one.
+pub fn effective_ask(asks: &[Order], now: u64) -> Option<&Order> {
+    asks.iter()
+        .filter(|order| is_executable(order, now))
+        .min_by_key(|order| price(order))
+}
+
+/// Reduces asks to one effective ask per token, cheapest first, so that
+/// duplicate listings are not counted twice by floor or sweep calculations.
+pub fn effective_asks(asks: &[Order], now: u64) -> Vec<&Order> {
+    cheapest_per_token(asks, now, |order| Some(price(order)))
+}
+
+/// Lowest effective ask price.
+pub fn floor_price(asks: &[Order], now: u64) -> Option<U256> {
+    effective_asks(asks, now).first().map(|order| price(order))
+}
+
+/// Like `effective_asks`, but compares prices in the converter's base currency.
+/// Asks in currencies the converter has no rate for are skipped.
+pub fn effective_asks_in<'a>(
+    asks: &'a [Order],
+    now: u64,
+    converter: &Converter,
+) -> Vec<(&'a Order, ConvertedPrice)> {
+    cheapest_per_token(asks, now, |order| converter.convert_order(order).map(|price| price.converted))
+        .into_iter()
+        .filter_map(|order| converter.convert_order(order).map(|price| (order, price)))
+        .collect()
+}
+
+/// Lowest effective ask price in the converter's base currency.
+pub fn floor_price_in(asks: &[Order], now: u64, converter: &Converter) -> Option<ConvertedPrice> {
+    effective_asks_in(asks, now, converter)
+        .into_iter()
+        .next()
+        .map(|(_, price)| price)
+}
+
+/// Highest live bid in the converter's base currency.
+pub fn best_bid_in<'a>(
+    bids: &'a [Order],
+    now: u64,
+    converter: &Converter,
+) -> Option<(&'a Order, ConvertedPrice)> {
+    bids.iter()
+        .filter(|order| !order.is_order_ask && is_live(order, now))
+        .filter_map(|order| converter.convert_order(order).map(|price| (order, price)))
+        .max_by_key(|(_, price)| price.converted)
+}
+
+fn cheapest_per_token<'a>(
+    asks: &'a [Order],
+    now: u64,
+    price_of: impl Fn(&Order) -> Option<U256>,
+) -> Vec<&'a Order> {
+    let mut best: HashMap<(Address, &str), (&Order, U256)> = HashMap::new();
+    for order in asks.iter().filter(|order| is_executable(order, now)) {
+        let order_price = match price_of(order) {
+            Some(order_price) => order_price,
+            None => continue,
+        };
+        let key = (order.collection_address, order.token_id.as_str());
+        if best.get(&key).is_none_or(|(_, current)| order_price < *current) {
+            best.insert(key, (order, order_price));
+        }
+    }
+
+    let mut asks: Vec<(&Order, U256)> = best.into_values().collect();
+    asks.sort_by_key(|(_, order_price)| *order_price);
+    asks.into_iter().map(|(order, _)| order).collect()
+}
+
+/// Best prices for one token. `mid` is set only when both sides are present.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Quote {
+    pub best_ask: Option<U256>,
+    pub best_bid: Option<U256>,
+    pub mid: Option<U256>,
+}
+
+/// The live asks and bids for a single token, keyed by order hash.
+#[derive(Clone, Debug, Default)]
+pub struct TokenBook {
+    asks: HashMap<String, Order>,
+    bids: HashMap<String, Order>,
+}
+
+impl TokenBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reconciles the book with a fresh snapshot of valid orders, dropping
+    /// anything that was filled or cancelled since the last one.
+    pub fn replace(&mut self, asks: Vec<Order>, bids: Vec<Order>) {
+        self.asks = asks
+            .into_iter()
+            .filter(|order| order.is_order_ask)
+            .map(|order| (order.hash.clone(), order))
+            .collect();
+        self.bids = bids
+            .into_iter()
+            .filter(|order| !order.is_order_ask)
+            .map(|order| (order.hash.clone(), order))
+            .collect();
+    }
+
+    pub fn quote(&self, now: u64) -> Quote {
+        let best_ask = self
+            .asks
+            .values()
+            .filter(|order| is_executable(order, now))
+            .map(price)
+            .min();
+        let best_bid = self
+            .bids
+            .values()
+            .filter(|order| is_live(order, now))
+            .map(price)
+            .max();
+        let mid = match (best_ask, best_bid) {
+            (Some(ask), Some(bid)) => Some((ask + bid) / 2),
+            _ => None,
+        };
+
+        Quote {
+            best_ask,
+            best_bid,
+            mid,
+        }
+    }
+}
+
+fn price(order: &Order) -> U256 {
+    U256::from_dec_str(&order.price).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::rates::{StaticRates, RATE_SCALE};
+
+    const NOW: u64 = 1667750000;
+
+    fn ask(token_id: &str, nonce: &str, price: u64) -> Order {
+        let mut order = fixtures::order();
+        order.token_id = token_id.to_string();
+        order.nonce = nonce.to_string();
+        order.price = price.to_string();
+        order
+    }
+
+    #[test]
+    fn detects_duplicate_listings() {
+        let asks = vec![ask("1", "0", 300), ask("1", "1", 200), ask("2", "2", 100)];
+
+        let duplicates = duplicate_listings(&asks);
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].token_id, "1");
+        assert_eq!(duplicates[0].orders[0].nonce, "1");
+        assert_eq!(duplicates[0].orders[1].nonce, "0");
+    }
+
+    #[test]
+    fn effective_ask_is_cheapest_executable() {
+        let mut expired = ask("1", "0", 100);
+        expired.end_time = NOW - 1;
+        let asks = vec![expired, ask("1", "1", 300), ask("1", "2", 200)];
+
+        let effective = effective_ask(&asks, NOW).unwrap();
+
+        assert_eq!(effective.nonce, "2");
+    }
+
+    #[test]
+    fn floor_and_sweep_count_each_token_once() {
+        let asks = vec![ask("1", "0", 300), ask("1", "1", 200), ask("2", "2", 250)];
+
+        let effective = effective_asks(&asks, NOW);
+
+        assert_eq!(effective.len(), 2);
+        assert_eq!(effective[0].nonce, "1");
+        assert_eq!(effective[1].nonce, "2");
+        assert_eq!(floor_price(&asks, NOW), Some(U256::from(200)));
+    }
+
+    #[test]
+    fn floor_compares_across_currencies() {
+        let looks = Address::from_low_u64_be(2);
+        let converter = Converter::new(
+            fixtures::order().currency_address,
+            StaticRates::new().with_rate(looks, fixtures::order().currency_address, U256::from(RATE_SCALE / 100)),
+        );
+
+        // 10000 LOOKS units convert to 100 WETH units, below the 200 WETH ask
+        let mut in_looks = ask("2", "3", 10000);
+        in_looks.currency_address = looks;
+        let asks = vec![ask("1", "1", 200), in_looks];
+
+        let floor = floor_price_in(&asks, NOW, &converter).unwrap();
+
+        assert_eq!(floor.converted, U256::from(100));
+        assert_eq!(floor.currency, looks);
+        assert_eq!(floor_price(&asks, NOW), Some(U256::from(200)));
+    }
+
+    #[test]
+    fn best_bid_is_highest_converted() {
+        let converter = Converter::new(fixtures::order().currency_address, StaticRates::new());
+        let mut low = ask("1", "1", 100);
+        low.is_order_ask = false;
+        let mut high = ask("1", "2", 150);
+        high.is_order_ask = false;
+        let bids = vec![low, high];
+
+        let (best, price) = best_bid_in(&bids, NOW, &converter).unwrap();
+
+        assert_eq!(best.nonce, "2");
+        assert_eq!(price.converted, U256::from(150));
+    }
+
+    #[test]
+    fn token_book_quotes_mid() {
+        let mut bid = ask("1", "3", 80);
+        bid.is_order_ask = false;
+        let mut book = TokenBook::new();
+        book.replace(vec![ask("1", "1", 120), ask("1", "2", 100)], vec![bid]);
+
+        assert_eq!(book.quote(NOW), Quote {
+            best_ask: Some(U256::from(100)),
+            best_bid: Some(U256::from(80)),
+            mid: Some(U256::from(90)),
+        });
+
+        book.replace(vec![ask("1", "1", 120)], vec![]);
+        assert_eq!(book.quote(NOW), Quote {
+            best_ask: Some(U256::from(120)),
+            best_bid: None,
+            mid: None,
+        });
+    }
+}
@@ -0,0 +1,152 @@
+use std::future::Future;
+use std::time::Instant;
+
+/// How a bulk operation reacts to a failed item.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BulkMode {
+    /// Attempt every item and report each failure.
+    #[default]
+    ContinueOnError,
+    /// Stop at the first failure and report the remaining items as skipped.
+    AbortOnFirstError,
+}
+
+/// How a bulk operation runs: its error mode and an optional deadline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BulkOptions {
+    pub mode: BulkMode,
+    /// No new item is started after this instant; the rest are reported as skipped.
+    pub deadline: Option<Instant>,
+}
+
+impl BulkOptions {
+    pub fn new(mode: BulkMode) -> Self {
+        Self { mode, deadline: None }
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+}
+
+/// Outcome of a bulk operation, keyed by each item's index in the input.
+///
+/// Items that went through already consumed their nonces, so a bulk call
+/// never rolls back; it reports what happened to each item instead.
+#[derive(Debug)]
+pub struct BulkResult<T, E> {
+    pub succeeded: Vec<(usize, T)>,
+    pub failed: Vec<(usize, E)>,
+    pub skipped: Vec<usize>,
+    /// Whether items were skipped because the deadline passed.
+    pub deadline_exceeded: bool,
+}
+
+impl<T, E> Default for BulkResult<T, E> {
+    fn default() -> Self {
+        Self {
+            succeeded: vec![],
+            failed: vec![],
+            skipped: vec![],
+            deadline_exceeded: false,
+        }
+    }
+}
+
+impl<T, E> BulkResult<T, E> {
+    pub fn is_complete_success(&self) -> bool {
+        self.failed.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// Runs `op` over `items` one after another, in order, collecting per-item outcomes.
+pub async fn execute<I, F, Fut, T, E>(items: I, mode: BulkMode, op: F) -> BulkResult<T, E>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    execute_with(items, BulkOptions::new(mode), op).await
+}
+
+/// Like `execute`, stopping at `options.deadline` if one is set.
+///
+/// An item already started when the deadline passes runs to completion.
+/// Dropping the future instead loses the outcomes gathered so far, so prefer a
+/// deadline over wrapping a bulk call in a timeout or `select!`.
+pub async fn execute_with<I, F, Fut, T, E>(items: I, options: BulkOptions, mut op: F) -> BulkResult<T, E>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut result = BulkResult::default();
+    let mut aborted = false;
+
+    for (index, item) in items.into_iter().enumerate() {
+        if !aborted && options.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            aborted = true;
+            result.deadline_exceeded = true;
+        }
+
+        if aborted {
+            result.skipped.push(index);
+            continue;
+        }
+
+        match op(item).await {
+            Ok(value) => result.succeeded.push((index, value)),
+            Err(e) => {
+                result.failed.push((index, e));
+                aborted = options.mode == BulkMode::AbortOnFirstError;
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    async fn even(n: u64) -> Result<u64, String> {
+        if n % 2 == 1 {
+            Err(format!("{} is odd", n))
+        } else {
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn continues_past_failures() {
+        let result = block_on(execute(vec![0, 1, 2, 3], BulkMode::ContinueOnError, even));
+
+        assert_eq!(result.succeeded, vec![(0, 0), (2, 2)]);
+        assert_eq!(result.failed, vec![(1, String::from("1 is odd")), (3, String::from("3 is odd"))]);
+        assert!(result.skipped.is_empty());
+        assert!(!result.is_complete_success());
+    }
+
+    #[test]
+    fn aborts_on_first_failure() {
+        let result = block_on(execute(vec![0, 1, 2, 3], BulkMode::AbortOnFirstError, even));
+
+        assert_eq!(result.succeeded, vec![(0, 0)]);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.skipped, vec![2, 3]);
+        assert!(!result.deadline_exceeded);
+    }
+
+    #[test]
+    fn stops_at_deadline() {
+        let options = BulkOptions::new(BulkMode::ContinueOnError).with_deadline(Instant::now());
+        let result = block_on(execute_with(vec![0, 2], options, even));
+
+        assert!(result.succeeded.is_empty());
+        assert_eq!(result.skipped, vec![0, 1]);
+        assert!(result.deadline_exceeded);
+    }
+}
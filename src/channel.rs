@@ -0,0 +1,265 @@
+use futures::future::poll_fn;
+use futures::{Stream, StreamExt};
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// What a bounded channel does with a new item when it is full.
+pub enum Overflow<T> {
+    /// Wait until the consumer makes room. Nothing is lost, the producer slows down.
+    Block,
+    /// Drop the oldest queued item to make room.
+    DropOldest,
+    /// Replace the queued item with the same key in place, keeping only the
+    /// latest state per key; items with a new key wait for room.
+    CoalesceByKey(fn(&T) -> String),
+}
+
+impl<T> Clone for Overflow<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Overflow<T> {}
+
+impl<T> fmt::Debug for Overflow<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Overflow::Block => f.write_str("Block"),
+            Overflow::DropOldest => f.write_str("DropOldest"),
+            Overflow::CoalesceByKey(_) => f.write_str("CoalesceByKey"),
+        }
+    }
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    receiver_alive: bool,
+    dropped: u64,
+    receiver_waker: Option<Waker>,
+    sender_wakers: Vec<Waker>,
+}
+
+struct Shared<T> {
+    capacity: usize,
+    overflow: Overflow<T>,
+    state: Mutex<State<T>>,
+}
+
+/// Creates a channel holding at most `capacity` items.
+pub fn bounded<T>(capacity: usize, overflow: Overflow<T>) -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        capacity: capacity.max(1),
+        overflow,
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            senders: 1,
+            receiver_alive: true,
+            dropped: 0,
+            receiver_waker: None,
+            sender_wakers: vec![],
+        }),
+    });
+
+    (Sender { shared: shared.clone() }, Receiver { shared })
+}
+
+/// Sends a stream's items into a bounded channel until either side ends.
+///
+/// The returned future does the work and should be spawned, e.g. with `tokio::spawn`.
+pub fn buffered<S, T>(stream: S, capacity: usize, overflow: Overflow<T>) -> (impl Future<Output = ()>, Receiver<T>)
+where
+    S: Stream<Item = T>,
+{
+    let (sender, receiver) = bounded(capacity, overflow);
+    let pump = async move {
+        let mut stream = Box::pin(stream);
+        while let Some(item) = stream.next().await {
+            if sender.send(item).await.is_err() {
+                break;
+            }
+        }
+    };
+
+    (pump, receiver)
+}
+
+/// The receiver was dropped; the unsent item is returned.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Closed<T>(pub T);
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Queues `item` as the overflow policy allows, waiting for room only under
+    /// `Block`, or for a new key under `CoalesceByKey`.
+    pub async fn send(&self, item: T) -> Result<(), Closed<T>> {
+        let mut item = Some(item);
+        poll_fn(|cx| self.poll_send(cx, &mut item)).await
+    }
+
+    fn poll_send(&self, cx: &mut Context<'_>, item: &mut Option<T>) -> Poll<Result<(), Closed<T>>> {
+        let mut state = self.shared.state.lock().unwrap();
+        let value = item.take().expect("polled after completion");
+        if !state.receiver_alive {
+            return Poll::Ready(Err(Closed(value)));
+        }
+
+        if let Overflow::CoalesceByKey(key_of) = self.shared.overflow {
+            let key = key_of(&value);
+            if let Some(queued) = state.queue.iter_mut().find(|queued| key_of(queued) == key) {
+                *queued = value;
+                state.dropped += 1;
+                return Poll::Ready(Ok(()));
+            }
+        }
+
+        if state.queue.len() >= self.shared.capacity {
+            match self.shared.overflow {
+                Overflow::DropOldest => {
+                    state.queue.pop_front();
+                    state.dropped += 1;
+                }
+                Overflow::Block | Overflow::CoalesceByKey(_) => {
+                    state.sender_wakers.push(cx.waker().clone());
+                    *item = Some(value);
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        state.queue.push_back(value);
+        if let Some(waker) = state.receiver_waker.take() {
+            waker.wake();
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.state.lock().unwrap().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.senders -= 1;
+        if state.senders == 0 {
+            if let Some(waker) = state.receiver_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// The consuming end; a stream that ends once every sender is gone and the queue is drained.
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Items dropped or replaced by the overflow policy so far.
+    pub fn dropped(&self) -> u64 {
+        self.shared.state.lock().unwrap().dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.state.lock().unwrap().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.shared.state.lock().unwrap();
+
+        if let Some(item) = state.queue.pop_front() {
+            for waker in state.sender_wakers.drain(..) {
+                waker.wake();
+            }
+            return Poll::Ready(Some(item));
+        }
+
+        if state.senders == 0 {
+            return Poll::Ready(None);
+        }
+
+        state.receiver_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.receiver_alive = false;
+        for waker in state.sender_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn drop_oldest_keeps_the_newest() {
+        let (sender, receiver) = bounded(2, Overflow::DropOldest);
+        block_on(async {
+            for n in 1..=3 {
+                sender.send(n).await.unwrap();
+            }
+        });
+        drop(sender);
+
+        assert_eq!(receiver.dropped(), 1);
+        assert_eq!(block_on(receiver.collect::<Vec<_>>()), vec![2, 3]);
+    }
+
+    #[test]
+    fn coalesces_by_key_in_place() {
+        let (sender, receiver) = bounded(2, Overflow::CoalesceByKey(|item: &(u8, u64)| item.0.to_string()));
+        block_on(async {
+            sender.send((1, 100)).await.unwrap();
+            sender.send((2, 200)).await.unwrap();
+            sender.send((1, 90)).await.unwrap();
+        });
+        drop(sender);
+
+        assert_eq!(block_on(receiver.collect::<Vec<_>>()), vec![(1, 90), (2, 200)]);
+    }
+
+    #[test]
+    fn block_waits_for_room() {
+        let (sender, mut receiver) = bounded(1, Overflow::Block);
+        block_on(async {
+            sender.send(1).await.unwrap();
+
+            let mut second = Box::pin(sender.send(2));
+            assert!(futures::poll!(&mut second).is_pending());
+            assert_eq!(receiver.next().await, Some(1));
+            second.await.unwrap();
+            assert_eq!(receiver.next().await, Some(2));
+        });
+    }
+}
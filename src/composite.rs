@@ -0,0 +1,75 @@
+use crate::ClientError;
+use std::future::Future;
+use std::time::Duration;
+use thiserror::Error;
+
+pub const DEFAULT_SUBTASK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why one part of a composite result is missing.
+#[derive(Debug, Error)]
+pub enum SubtaskError {
+    #[error("{name} timed out after {timeout:?}")]
+    TimedOut { name: &'static str, timeout: Duration },
+    #[error("{name} failed: {source}")]
+    Failed { name: &'static str, source: ClientError },
+}
+
+impl SubtaskError {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SubtaskError::TimedOut { name, .. } => name,
+            SubtaskError::Failed { name, .. } => name,
+        }
+    }
+}
+
+/// Runs one part of a composite operation under its own timeout.
+///
+/// Composite helpers join several of these, so a slow or failing endpoint
+/// only leaves its own part empty instead of blocking or aborting the rest.
+pub async fn subtask<T, E, F>(name: &'static str, timeout: Duration, fut: F) -> Result<T, SubtaskError>
+where
+    E: Into<ClientError>,
+    F: Future<Output = Result<T, E>>,
+{
+    match tokio::time::timeout(timeout, fut).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(SubtaskError::Failed {
+            name,
+            source: e.into(),
+        }),
+        Err(_) => Err(SubtaskError::TimedOut { name, timeout }),
+    }
+}
+
+/// Keeps a subtask's value, or records its error and returns `None`.
+pub fn partial<T>(result: Result<T, SubtaskError>, errors: &mut Vec<SubtaskError>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            errors.push(e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn slow_subtask_times_out_without_blocking_others() {
+        let timeout = Duration::from_millis(10);
+        let (slow, fast) = futures::join!(
+            subtask("slow", timeout, futures::future::pending::<Result<u64, ClientError>>()),
+            subtask("fast", timeout, async { Ok::<u64, ClientError>(1) }),
+        );
+
+        let mut errors = vec![];
+        assert_eq!(partial(slow, &mut errors), None);
+        assert_eq!(partial(fast, &mut errors), Some(1));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].name(), "slow");
+        assert!(matches!(errors[0], SubtaskError::TimedOut { .. }));
+    }
+}
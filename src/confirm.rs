@@ -0,0 +1,179 @@
+use crate::contracts::LooksRareExchangeEvents;
+use crate::types::Network;
+use crate::ClientError;
+use ethers::abi::RawLog;
+use ethers::contract::EthLogDecode;
+use ethers::prelude::{Address, Middleware, TransactionReceipt, H256};
+use std::sync::Arc;
+
+/// Whether a fill seen in a receipt still stands on the canonical chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Confirmation {
+    /// The fill is on chain but not yet buried under enough blocks.
+    Pending { confirmations: u64 },
+    /// The fill is buried under at least the required number of blocks.
+    Confirmed { block_number: u64, block_hash: H256 },
+    /// The transaction or its fill is no longer on the canonical chain, so
+    /// the fill must not be accounted for.
+    Reversed,
+}
+
+impl Confirmation {
+    /// Whether the fill can be recorded, or dropped, for good.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Confirmation::Pending { .. })
+    }
+}
+
+/// Checks that the `TakerBid` or `TakerAsk` log filling `order_hash` in `receipt`
+/// is still on chain `confirmations` blocks deep.
+///
+/// Only the transaction hash is taken from `receipt`: the receipt is fetched
+/// again, since a reorg may have dropped the transaction or included it in
+/// another block. Call it again while the result is `Pending`.
+pub async fn confirm_execution<M: Middleware>(
+    provider: Arc<M>,
+    network: &Network,
+    order_hash: H256,
+    receipt: &TransactionReceipt,
+    confirmations: u64,
+) -> Result<Confirmation, ClientError> {
+    let current = provider
+        .get_transaction_receipt(receipt.transaction_hash)
+        .await
+        .map_err(|e| ClientError::ProviderError(e.to_string()))?;
+    let head = provider
+        .get_block_number()
+        .await
+        .map_err(|e| ClientError::ProviderError(e.to_string()))?
+        .as_u64();
+
+    Ok(assess(current.as_ref(), network.exchange(), order_hash, head, confirmations))
+}
+
+/// The confirmation status given the receipt as the chain now reports it and the chain head.
+fn assess(
+    receipt: Option<&TransactionReceipt>,
+    exchange: Address,
+    order_hash: H256,
+    head: u64,
+    confirmations: u64,
+) -> Confirmation {
+    let receipt = match receipt {
+        Some(receipt) if find_fill(receipt, exchange, order_hash).is_some() => receipt,
+        _ => return Confirmation::Reversed,
+    };
+    let (block_number, block_hash) = match (receipt.block_number, receipt.block_hash) {
+        (Some(number), Some(hash)) => (number.as_u64(), hash),
+        // pending again after being dropped from its block
+        _ => return Confirmation::Pending { confirmations: 0 },
+    };
+
+    let depth = (head + 1).saturating_sub(block_number);
+    if depth < confirmations {
+        return Confirmation::Pending { confirmations: depth };
+    }
+
+    Confirmation::Confirmed {
+        block_number,
+        block_hash,
+    }
+}
+
+/// The index of the exchange log in `receipt` that filled `order_hash`.
+fn find_fill(receipt: &TransactionReceipt, exchange: Address, order_hash: H256) -> Option<usize> {
+    if receipt.status.is_some_and(|status| status.is_zero()) {
+        return None;
+    }
+
+    receipt.logs.iter().position(|log| {
+        if log.address != exchange || log.removed == Some(true) {
+            return false;
+        }
+
+        let filled = match LooksRareExchangeEvents::decode_log(&RawLog::from(log.clone())) {
+            Ok(LooksRareExchangeEvents::TakerBidFilter(fill)) => fill.order_hash,
+            Ok(LooksRareExchangeEvents::TakerAskFilter(fill)) => fill.order_hash,
+            _ => return false,
+        };
+        H256(filled) == order_hash
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::TakerBidFilter;
+    use ethers::abi::{encode, Token};
+    use ethers::contract::EthEvent;
+    use ethers::prelude::{Log, U256, U64};
+
+    fn receipt(exchange: Address, order_hash: H256, block_number: u64) -> TransactionReceipt {
+        let data = encode(&[
+            Token::FixedBytes(order_hash.as_bytes().to_vec()),
+            Token::Uint(U256::from(7)),
+            Token::Address(Address::from_low_u64_be(10)),
+            Token::Address(Address::from_low_u64_be(11)),
+            Token::Uint(U256::one()),
+            Token::Uint(U256::one()),
+            Token::Uint(U256::exp10(18)),
+        ]);
+        let log = Log {
+            address: exchange,
+            topics: vec![
+                TakerBidFilter::signature(),
+                H256::from(Address::from_low_u64_be(1)),
+                H256::from(Address::from_low_u64_be(2)),
+                H256::from(Address::from_low_u64_be(3)),
+            ],
+            data: data.into(),
+            ..Default::default()
+        };
+
+        TransactionReceipt {
+            block_number: Some(U64::from(block_number)),
+            block_hash: Some(H256::repeat_byte(block_number as u8)),
+            status: Some(U64::one()),
+            logs: vec![log],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn confirms_fills_once_deep_enough() {
+        let exchange = Network::Mainnet.exchange();
+        let order_hash = H256::repeat_byte(0xaa);
+        let receipt = receipt(exchange, order_hash, 100);
+
+        assert_eq!(find_fill(&receipt, exchange, order_hash), Some(0));
+        assert_eq!(find_fill(&receipt, exchange, H256::zero()), None);
+
+        assert_eq!(
+            assess(Some(&receipt), exchange, order_hash, 101, 3),
+            Confirmation::Pending { confirmations: 2 }
+        );
+        assert_eq!(
+            assess(Some(&receipt), exchange, order_hash, 102, 3),
+            Confirmation::Confirmed {
+                block_number: 100,
+                block_hash: H256::repeat_byte(100),
+            }
+        );
+    }
+
+    #[test]
+    fn flags_dropped_and_failed_fills_as_reversed() {
+        let exchange = Network::Mainnet.exchange();
+        let order_hash = H256::repeat_byte(0xaa);
+
+        assert_eq!(assess(None, exchange, order_hash, 200, 3), Confirmation::Reversed);
+
+        let mut failed = receipt(exchange, order_hash, 100);
+        failed.status = Some(U64::zero());
+        assert_eq!(assess(Some(&failed), exchange, order_hash, 200, 3), Confirmation::Reversed);
+
+        let mut removed = receipt(exchange, order_hash, 100);
+        removed.logs[0].removed = Some(true);
+        assert_eq!(assess(Some(&removed), exchange, order_hash, 200, 3), Confirmation::Reversed);
+    }
+}
@@ -1,4 +1,49 @@
 pub const VERSION: u64 = 1;
 pub const API_PATH: &str = "/api/v";
 
-pub const API_BASE_MAINNET: &str = "https://api.looksrare.org";
\ No newline at end of file
+pub const API_BASE_MAINNET: &str = "https://api.looksrare.org";
+pub const API_KEY_HEADER: &str = "X-Looks-Api-Key";
+pub const APP_MAINNET: &str = "https://looksrare.org";
+pub const EXPLORER_MAINNET: &str = "https://etherscan.io";
+
+pub const CHAIN_ID_MAINNET: u64 = 1;
+
+pub const MAX_ORDERS_PER_PAGE: u64 = 150;
+/// Page size the API uses when a request doesn't set `pagination[first]`.
+pub const DEFAULT_ORDERS_PER_PAGE: u64 = 20;
+
+pub const ACCOUNT_LOOKUP_CONCURRENCY: usize = 8;
+
+/// Requests per minute the API allows an unauthenticated client.
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 120;
+
+pub const WETH_MAINNET: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+pub const LOOKS_MAINNET: &str = "0xf4d2888d29D722226FafA5d9B24F9164c092421E";
+pub const ROYALTY_FEE_MANAGER_MAINNET: &str = "0x7358182024c9f1B2e6b0153e60bf6156B7eF4906";
+pub const EXCHANGE_MAINNET: &str = "0x59728544B08AB483533076417FbBB2fD0B17CE3a";
+pub const TRANSFER_MANAGER_ERC721_MAINNET: &str = "0xf42aa99F011A1fA7CDA90E5E98b277E306BcA83e";
+pub const TRANSFER_MANAGER_ERC1155_MAINNET: &str = "0xFED24eC7E22f573c2e08AEF55aA6797Ca2b3A051";
+
+pub const STRATEGY_STANDARD_SALE_MAINNET: &str = "0x579af6FD30BF83a5Ac0D636bc619f98DBdeb930c";
+pub const STRATEGY_COLLECTION_OFFER_MAINNET: &str = "0x09F93623019049C76209C26517AcC2aF9d49C69B";
+pub const STRATEGY_PRIVATE_SALE_MAINNET: &str = "0x58D83536D3EfeDB9F7f2A1Ec3bDaAd2b1A4DD98C";
+pub const STRATEGY_DUTCH_AUCTION_MAINNET: &str = "0x3E80795Cae5Ee215EBbDf518689467Bf4243BAe0";
+
+pub const GEM_ROUTER_MAINNET: &str = "0x83c8f28c26bf6aaca652df1dbbe0e1b56f8baba2";
+pub const GEM_ROUTER_LEGACY_MAINNET: &str = "0x0000000035634b55f3d99b071b5a354f48e10bef";
+pub const GENIE_ROUTER_MAINNET: &str = "0x0a267cf51ef038fc00e71801f5a524aec06e4f07";
+pub const BLUR_ROUTER_MAINNET: &str = "0x39da41747a83aee658334415666f3ef92dd0d541";
+pub const UNIVERSAL_ROUTER_MAINNET: &str = "0xef1c6e67703c7bd7107eed8303fbe6ec2554bf6b";
+
+/// Typical gas of one listing filled in its own exchange transaction, base cost included.
+pub const FILL_GAS: u64 = 200_000;
+/// Typical fixed gas of an aggregator transaction, before the orders it fills.
+pub const AGGREGATOR_BASE_GAS: u64 = 60_000;
+/// Typical gas each listing adds to an aggregator transaction.
+pub const AGGREGATOR_GAS_PER_ORDER: u64 = 150_000;
+
+/// Protocol fee of the standard sale and collection offer strategies, in basis points.
+pub const PROTOCOL_FEE_BPS: u64 = 200;
+
+pub const EXCHANGE_DOMAIN_NAME: &str = "LooksRareExchange";
+pub const EXCHANGE_DOMAIN_VERSION: &str = "1";
\ No newline at end of file
@@ -0,0 +1,149 @@
+use crate::types::Network;
+use ethers::prelude::{Address, ContractCall, Middleware, U256};
+use std::sync::Arc;
+
+mod erc20 {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        ERC20,
+        r#"[
+            function balanceOf(address account) external view returns (uint256)
+            function allowance(address owner, address spender) external view returns (uint256)
+            function approve(address spender, uint256 amount) external returns (bool)
+            function transferFrom(address from, address to, uint256 amount) external returns (bool)
+            function decimals() external view returns (uint8)
+        ]"#,
+    );
+}
+
+mod erc721 {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        ERC721,
+        r#"[
+            function balanceOf(address owner) external view returns (uint256)
+            function ownerOf(uint256 tokenId) external view returns (address)
+            function tokenURI(uint256 tokenId) external view returns (string)
+            function totalSupply() external view returns (uint256)
+            function tokenByIndex(uint256 index) external view returns (uint256)
+            function isApprovedForAll(address owner, address operator) external view returns (bool)
+            function safeTransferFrom(address from, address to, uint256 tokenId) external
+            function supportsInterface(bytes4 interfaceId) external view returns (bool)
+            event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)
+        ]"#,
+    );
+}
+
+mod erc1155 {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        ERC1155,
+        r#"[
+            function balanceOf(address account, uint256 id) external view returns (uint256)
+            function isApprovedForAll(address account, address operator) external view returns (bool)
+            function safeTransferFrom(address from, address to, uint256 id, uint256 amount, bytes data) external
+            function supportsInterface(bytes4 interfaceId) external view returns (bool)
+        ]"#,
+    );
+}
+
+mod exchange {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        LooksRareExchange,
+        r#"[
+            struct MakerOrder { bool isOrderAsk; address signer; address collection; uint256 price; uint256 tokenId; uint256 amount; address strategy; address currency; uint256 nonce; uint256 startTime; uint256 endTime; uint256 minPercentageToAsk; bytes params; uint8 v; bytes32 r; bytes32 s; }
+            struct TakerOrder { bool isOrderAsk; address taker; uint256 price; uint256 tokenId; uint256 minPercentageToAsk; bytes params; }
+            function DOMAIN_SEPARATOR() external view returns (bytes32)
+            function matchAskWithTakerBid(TakerOrder takerBid, MakerOrder makerAsk) external
+            function matchAskWithTakerBidUsingETHAndWETH(TakerOrder takerBid, MakerOrder makerAsk) external payable
+            function matchBidWithTakerAsk(TakerOrder takerAsk, MakerOrder makerBid) external
+            function cancelMultipleMakerOrders(uint256[] orderNonces) external
+            function cancelAllOrdersForSender(uint256 minNonce) external
+            function userMinOrderNonce(address user) external view returns (uint256)
+            function isUserOrderNonceExecutedOrCancelled(address user, uint256 orderNonce) external view returns (bool)
+            event TakerAsk(bytes32 orderHash, uint256 orderNonce, address indexed taker, address indexed maker, address indexed strategy, address currency, address collection, uint256 tokenId, uint256 amount, uint256 price)
+            event TakerBid(bytes32 orderHash, uint256 orderNonce, address indexed taker, address indexed maker, address indexed strategy, address currency, address collection, uint256 tokenId, uint256 amount, uint256 price)
+        ]"#,
+    );
+}
+
+mod looksrare_aggregator {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        LooksRareAggregator,
+        r#"[
+            struct BasicOrder { address signer; address collection; uint8 collectionType; uint256[] tokenIds; uint256[] amounts; uint256 price; address currency; uint256 startTime; uint256 endTime; bytes signature; }
+            struct TradeData { address proxy; bytes4 selector; BasicOrder[] orders; bytes[] ordersExtraData; bytes extraData; }
+            struct TokenTransfer { uint256 amount; address currency; }
+            function execute(TokenTransfer[] tokenTransfers, TradeData[] tradeData, address originator, address recipient, bool isAtomic) external payable
+        ]"#,
+    );
+}
+
+mod execution_strategy {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        ExecutionStrategy,
+        r#"[
+            function viewProtocolFee() external view returns (uint256)
+        ]"#,
+    );
+}
+
+mod royalty_fee_manager {
+    use ethers::prelude::abigen;
+
+    abigen!(
+        RoyaltyFeeManager,
+        r#"[
+            function calculateRoyaltyFeeAndGetRecipient(address collection, uint256 tokenId, uint256 amount) external view returns (address, uint256)
+        ]"#,
+    );
+}
+
+pub use erc20::ERC20;
+pub use erc1155::ERC1155;
+pub use erc721::{TransferFilter, ERC721};
+pub use exchange::{
+    LooksRareExchange, LooksRareExchangeEvents, MakerOrder as ExchangeMakerOrder, TakerAskFilter, TakerBidFilter,
+    TakerOrder,
+};
+pub use execution_strategy::ExecutionStrategy;
+pub use looksrare_aggregator::{BasicOrder, LooksRareAggregator, TokenTransfer, TradeData};
+pub use royalty_fee_manager::RoyaltyFeeManager;
+
+/// The exchange call cancelling `signer`'s orders with `nonces`, sent from `signer`.
+///
+/// Sent through `keepers::cancel_nonces`, which audits it.
+pub(crate) fn cancel_orders<M: Middleware>(
+    network: &Network,
+    provider: Arc<M>,
+    nonces: Vec<U256>,
+    signer: Address,
+) -> ContractCall<M, ()> {
+    LooksRareExchange::new(network.exchange(), provider)
+        .cancel_multiple_maker_orders(nonces)
+        .from(signer)
+}
+
+/// The exchange call cancelling every order of `signer` with a nonce below `min_nonce`,
+/// sent from `signer`. It must be above the signer's current `userMinOrderNonce`.
+///
+/// Sent through `cancel_all_orders`, which audits it.
+pub(crate) fn cancel_all_orders_for_sender<M: Middleware>(
+    network: &Network,
+    provider: Arc<M>,
+    min_nonce: U256,
+    signer: Address,
+) -> ContractCall<M, ()> {
+    LooksRareExchange::new(network.exchange(), provider)
+        .cancel_all_orders_for_sender(min_nonce)
+        .from(signer)
+}
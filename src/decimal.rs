@@ -0,0 +1,75 @@
+//! `rust_decimal` views of wei amounts, for reporting code that prefers decimal math.
+//!
+//! Conversions are exact: anything that would round, overflow or go negative gives `None`.
+use crate::amount::{Amount, ETHER_DECIMALS};
+use ethers::prelude::U256;
+use rust_decimal::Decimal;
+
+pub trait ToDecimal {
+    /// The value in whole units of a currency with `decimals` decimals.
+    fn to_decimal(&self, decimals: u8) -> Option<Decimal>;
+}
+
+impl ToDecimal for U256 {
+    fn to_decimal(&self, decimals: u8) -> Option<Decimal> {
+        Amount::new(*self, decimals).to_decimal()
+    }
+}
+
+pub trait ToU256 {
+    /// The value in the smallest unit of a currency with `decimals` decimals, e.g. wei.
+    fn to_u256(&self, decimals: u8) -> Option<U256>;
+}
+
+impl ToU256 for Decimal {
+    fn to_u256(&self, decimals: u8) -> Option<U256> {
+        Amount::from_decimal(*self, decimals).map(|amount| amount.value)
+    }
+}
+
+impl Amount {
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        Decimal::from_str_exact(&self.to_string()).ok()
+    }
+
+    pub fn from_decimal(value: Decimal, decimals: u8) -> Option<Self> {
+        Amount::parse(&value.normalize().to_string(), decimals)
+    }
+}
+
+/// Serializes wei as a decimal ETH string, for `#[serde(with = "looksrare::decimal::ether")]`
+/// on `U256` fields of reporting types.
+pub mod ether {
+    use super::*;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(wei: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        Amount::wei(*wei).to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Amount::parse(&s, ETHER_DECIMALS)
+            .map(|amount| amount.value)
+            .ok_or_else(|| D::Error::custom(format!("not an ETH amount: {}", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn converts_exactly() {
+        let wei = U256::from(1_250_000_000_000_000_000u64);
+        let eth = wei.to_decimal(ETHER_DECIMALS).unwrap();
+
+        assert_eq!(eth, Decimal::from_str("1.25").unwrap());
+        assert_eq!(eth.to_u256(ETHER_DECIMALS), Some(wei));
+        assert_eq!(Decimal::from_str("0.0000001").unwrap().to_u256(6), None);
+        assert_eq!(Decimal::from_str("-1").unwrap().to_u256(6), None);
+        assert_eq!(U256::MAX.to_decimal(ETHER_DECIMALS), None);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::api::LooksRareApi;
+use crate::contracts::{ERC20, ERC721};
+use ethers::prelude::{Address, Middleware};
+use ethers::signers::Signer;
+use std::sync::Arc;
+
+/// What `self_test` should check beyond API reachability and contract resolution.
+#[derive(Debug)]
+pub struct SelfTestConfig<M, S> {
+    pub provider: Arc<M>,
+    pub signer: Option<S>,
+    /// Address the signer is expected to derive, e.g. from deployment configuration.
+    pub expected_signer: Option<Address>,
+    /// Collections whose transfer manager approval should be checked for the signer.
+    pub collections: Vec<Address>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SelfTestReport {
+    pub checks: Vec<Check>,
+}
+
+impl SelfTestReport {
+    /// True when no check failed. Skipped checks do not count as failures.
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| !matches!(check.status, CheckStatus::Failed(_)))
+    }
+
+    pub fn failures(&self) -> Vec<&Check> {
+        self.checks
+            .iter()
+            .filter(|check| matches!(check.status, CheckStatus::Failed(_)))
+            .collect()
+    }
+
+    fn record(&mut self, name: impl Into<String>, status: CheckStatus) {
+        self.checks.push(Check {
+            name: name.into(),
+            status,
+        });
+    }
+}
+
+/// Runs every preflight check and reports each outcome rather than stopping at the first failure.
+pub async fn self_test<M: Middleware, S: Signer>(
+    api: &LooksRareApi,
+    config: &SelfTestConfig<M, S>,
+) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+    let network = api.network();
+
+    let status = match api.get_nonce(Address::zero()).await {
+        Ok(_) => CheckStatus::Passed,
+        Err(e) => CheckStatus::Failed(e.to_string()),
+    };
+    let key_status = match (&status, api.has_api_key()) {
+        (_, false) => CheckStatus::Skipped(String::from("client has no API key configured")),
+        (CheckStatus::Passed, true) => CheckStatus::Passed,
+        (_, true) => CheckStatus::Failed(String::from("request sent with the API key failed")),
+    };
+    report.record("api reachable", status);
+    report.record("api key", key_status);
+
+    let signer = config.signer.as_ref().map(|signer| signer.address());
+    let status = match (signer, config.expected_signer) {
+        (None, _) => CheckStatus::Skipped(String::from("no signer configured")),
+        (Some(_), None) => CheckStatus::Passed,
+        (Some(derived), Some(expected)) if derived == expected => CheckStatus::Passed,
+        (Some(derived), Some(expected)) => CheckStatus::Failed(format!(
+            "signer derives {:?}, expected {:?}",
+            derived, expected
+        )),
+    };
+    report.record("signer address", status);
+
+    let contracts = [
+        ("exchange", network.exchange()),
+        ("transfer manager erc721", network.transfer_manager_erc721()),
+        ("royalty fee manager", network.royalty_fee_manager()),
+        ("weth", network.weth()),
+        ("looks", network.looks()),
+    ];
+    for (name, address) in contracts {
+        let status = match config.provider.get_code(address, None).await {
+            Ok(code) if !code.as_ref().is_empty() => CheckStatus::Passed,
+            Ok(_) => CheckStatus::Failed(format!("no code at {:?}", address)),
+            Err(e) => CheckStatus::Failed(e.to_string()),
+        };
+        report.record(format!("contract {}", name), status);
+    }
+
+    let owner = match signer {
+        Some(owner) => owner,
+        None => {
+            report.record(
+                "approvals",
+                CheckStatus::Skipped(String::from("no signer configured")),
+            );
+            return report;
+        }
+    };
+
+    let weth = ERC20::new(network.weth(), config.provider.clone());
+    let status = match weth.allowance(owner, network.exchange()).call().await {
+        Ok(allowance) if !allowance.is_zero() => CheckStatus::Passed,
+        Ok(_) => CheckStatus::Failed(String::from("exchange has no WETH allowance")),
+        Err(e) => CheckStatus::Failed(e.to_string()),
+    };
+    report.record("weth allowance", status);
+
+    for collection in &config.collections {
+        let contract = ERC721::new(*collection, config.provider.clone());
+        let status = match contract
+            .is_approved_for_all(owner, network.transfer_manager_erc721())
+            .call()
+            .await
+        {
+            Ok(true) => CheckStatus::Passed,
+            Ok(false) => CheckStatus::Failed(String::from("transfer manager is not approved")),
+            Err(e) => CheckStatus::Failed(e.to_string()),
+        };
+        report.record(format!("approval {:?}", collection), status);
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skipped_checks_do_not_fail_the_report() {
+        let mut report = SelfTestReport::default();
+        report.record("a", CheckStatus::Passed);
+        report.record("b", CheckStatus::Skipped(String::from("n/a")));
+        assert!(report.passed());
+
+        report.record("c", CheckStatus::Failed(String::from("boom")));
+        assert!(!report.passed());
+        assert_eq!(report.failures().len(), 1);
+        assert_eq!(report.failures()[0].name, "c");
+    }
+}
@@ -0,0 +1,260 @@
+//! Backends that send the transactions buying a set of listings.
+
+use crate::api::LooksRareApi;
+use crate::audit::{AuditAction, OrderRef};
+use crate::contracts::{BasicOrder, LooksRareAggregator, TradeData};
+use crate::order::SignedOrder;
+use crate::transfer::{self, TokenStandard, VaultPurchase};
+use crate::types::Network;
+use crate::ClientError;
+use async_trait::async_trait;
+use ethers::abi::{self, Token};
+use ethers::prelude::{Address, Bytes, Middleware, H256, U256};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Buys listings for `buyer`, who must be the account the backend's provider sends from.
+#[async_trait]
+pub trait Executor: Send + Sync {
+    /// Sends the transactions filling `listings` and returns their hashes, in order.
+    async fn execute(&self, listings: &[SignedOrder], buyer: Address) -> Result<Vec<H256>, ClientError>;
+}
+
+/// Fills each listing in its own exchange transaction; see `SignedOrder::fill`.
+///
+/// Every fill is audited in `api`'s audit log, if it has one.
+#[derive(Debug)]
+pub struct ExchangeExecutor<M> {
+    api: LooksRareApi,
+    provider: Arc<M>,
+}
+
+impl<M: Middleware> ExchangeExecutor<M> {
+    pub fn new(api: LooksRareApi, provider: Arc<M>) -> Self {
+        Self { api, provider }
+    }
+
+    /// Fills `listings` as `buyer` like `execute`, then sends each token on to
+    /// `vault` with `transfer::deliver_to_vault`, returning the purchases in order.
+    ///
+    /// Fails if a purchase couldn't be sent, before any token is delivered, so
+    /// tokens already bought stay with `buyer`. Otherwise see each purchase's
+    /// `Delivery` for where its token ended up.
+    pub async fn execute_to(
+        &self,
+        listings: &[SignedOrder],
+        buyer: Address,
+        vault: Address,
+    ) -> Result<Vec<VaultPurchase>, ClientError>
+    where
+        M: 'static,
+    {
+        let tx_hashes = self.execute(listings, buyer).await?;
+        let mut purchases = vec![];
+
+        for (listing, purchase_tx) in listings.iter().zip(tx_hashes) {
+            let order = &listing.order;
+            let purchase = transfer::deliver_to_vault(
+                &self.api,
+                self.provider.clone(),
+                purchase_tx,
+                order.collection,
+                order.token_id,
+                buyer,
+                vault,
+            )
+            .await;
+            purchases.push(purchase);
+        }
+
+        Ok(purchases)
+    }
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> Executor for ExchangeExecutor<M> {
+    async fn execute(&self, listings: &[SignedOrder], buyer: Address) -> Result<Vec<H256>, ClientError> {
+        if listings.is_empty() {
+            return Ok(vec![]);
+        }
+        self.api.domain_separator(self.provider.clone()).await?;
+
+        let mut tx_hashes = vec![];
+        for listing in listings {
+            let call = listing.fill(self.api.network(), self.provider.clone(), buyer);
+            let sent = call.send().await.map(|pending| *pending).map_err(ClientError::from);
+
+            let outcome = sent.as_ref().map(|tx_hash| format!("{:?}", tx_hash)).map_err(|e| e.to_string());
+            self.api
+                .audit(AuditAction::OrderExecuted, vec![OrderRef::from(&listing.order)], listing, outcome);
+            tx_hashes.push(sent?);
+        }
+
+        Ok(tx_hashes)
+    }
+}
+
+/// The `LooksRareProxy.execute` signature the aggregator calls for each trade.
+const LOOKSRARE_PROXY_EXECUTE: &str =
+    "execute((address,address,uint8,uint256[],uint256[],uint256,address,uint256,uint256,bytes)[],bytes[],bytes,address,bool)";
+
+/// Fills every listing in one transaction through the LooksRare aggregator,
+/// which sends the tokens straight to the recipient: `buyer` unless set with
+/// `recipient`, e.g. a vault.
+///
+/// Listings are routed to `proxy`, the aggregator's proxy for the LooksRare
+/// exchange; the crate doesn't assume either address. Only asks priced in WETH
+/// can be batched, as they are paid in ETH. See `SweepEstimate::prefers_batching`
+/// for when this is cheaper than `ExchangeExecutor`.
+///
+/// The transaction is audited in `api`'s audit log, if it has one, as the
+/// execution of every listing it fills.
+#[derive(Debug)]
+pub struct AggregatorExecutor<M> {
+    api: LooksRareApi,
+    provider: Arc<M>,
+    aggregator: Address,
+    proxy: Address,
+    recipient: Option<Address>,
+}
+
+impl<M: Middleware> AggregatorExecutor<M> {
+    pub fn new(api: LooksRareApi, provider: Arc<M>, aggregator: Address, proxy: Address) -> Self {
+        Self {
+            api,
+            provider,
+            aggregator,
+            proxy,
+            recipient: None,
+        }
+    }
+
+    /// Sends the tokens to `recipient` instead of the buyer.
+    pub fn recipient(mut self, recipient: Address) -> Self {
+        self.recipient = Some(recipient);
+        self
+    }
+
+    /// The aggregator trade filling `listings`, whose collections have the given standards.
+    fn trade(&self, listings: &[SignedOrder], standards: &HashMap<Address, TokenStandard>) -> TradeData {
+        let orders = listings
+            .iter()
+            .map(|listing| {
+                let order = &listing.order;
+                BasicOrder {
+                    signer: order.signer,
+                    collection: order.collection,
+                    collection_type: match standards[&order.collection] {
+                        TokenStandard::Erc721 => 0,
+                        TokenStandard::Erc1155 => 1,
+                    },
+                    token_ids: vec![order.token_id],
+                    amounts: vec![order.amount],
+                    price: order.price,
+                    currency: order.currency,
+                    start_time: order.start_time.into(),
+                    end_time: order.end_time.into(),
+                    signature: Bytes::from(listing.signature.to_vec()),
+                }
+            })
+            .collect();
+        let orders_extra_data = listings.iter().map(order_extra_data).collect();
+
+        TradeData {
+            proxy: self.proxy,
+            selector: ethers::utils::id(LOOKSRARE_PROXY_EXECUTE),
+            orders,
+            orders_extra_data,
+            extra_data: Bytes::default(),
+        }
+    }
+}
+
+/// What the LooksRare proxy needs of an order beyond `BasicOrder`.
+fn order_extra_data(listing: &SignedOrder) -> Bytes {
+    let order = &listing.order;
+    let encoded = abi::encode(&[
+        Token::Uint(order.price),
+        Token::Uint(order.min_percentage_to_ask.into()),
+        Token::Uint(order.nonce),
+        Token::Address(order.strategy),
+    ]);
+
+    Bytes::from(encoded)
+}
+
+/// The ETH paying for `listings`, or an error if one of them can't be paid in ETH.
+fn eth_value(network: &Network, listings: &[SignedOrder]) -> Result<U256, ClientError> {
+    listings.iter().try_fold(U256::zero(), |total, listing| {
+        match listing.order.is_order_ask && listing.order.currency == network.weth() {
+            true => Ok(total + listing.order.price),
+            false => Err(ClientError::IncompleteOrder("ETH-payable ask")),
+        }
+    })
+}
+
+#[async_trait]
+impl<M: Middleware + 'static> Executor for AggregatorExecutor<M> {
+    async fn execute(&self, listings: &[SignedOrder], buyer: Address) -> Result<Vec<H256>, ClientError> {
+        if listings.is_empty() {
+            return Ok(vec![]);
+        }
+        let value = eth_value(self.api.network(), listings)?;
+        self.api.domain_separator(self.provider.clone()).await?;
+
+        let mut standards = HashMap::new();
+        for listing in listings {
+            let collection = listing.order.collection;
+            if !standards.contains_key(&collection) {
+                standards.insert(collection, transfer::detect_standard(self.provider.clone(), collection).await?);
+            }
+        }
+
+        let recipient = self.recipient.unwrap_or(buyer);
+        let call = LooksRareAggregator::new(self.aggregator, self.provider.clone())
+            .execute(vec![], vec![self.trade(listings, &standards)], buyer, recipient, true)
+            .value(value)
+            .from(buyer);
+        let sent = call.send().await.map(|pending| *pending).map_err(ClientError::from);
+
+        let outcome = sent.as_ref().map(|tx_hash| format!("{:?}", tx_hash)).map_err(|e| e.to_string());
+        let orders = listings.iter().map(|listing| OrderRef::from(&listing.order)).collect();
+        self.api.audit(AuditAction::OrderExecuted, orders, &listings, outcome);
+
+        Ok(vec![sent?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(currency: Address) -> SignedOrder {
+        let mut order = crate::fixtures::order();
+        order.currency_address = currency;
+        order.v = Some(27);
+        order.r = Some(H256::repeat_byte(1));
+        order.s = Some(H256::repeat_byte(2));
+        SignedOrder::try_from(&order).unwrap()
+    }
+
+    #[test]
+    fn pays_only_weth_asks_in_eth() {
+        let network = Network::Mainnet;
+        let weth = listing(network.weth());
+
+        assert_eq!(eth_value(&network, &[weth.clone(), weth.clone()]).unwrap(), weth.order.price * 2);
+        assert!(eth_value(&network, &[weth.clone(), listing(network.looks())]).is_err());
+
+        let mut bid = weth.clone();
+        bid.order.is_order_ask = false;
+        assert!(eth_value(&network, &[weth.clone(), bid]).is_err());
+
+        let extra = abi::decode(
+            &[abi::ParamType::Uint(256), abi::ParamType::Uint(256), abi::ParamType::Uint(256), abi::ParamType::Address],
+            &order_extra_data(&weth),
+        )
+        .unwrap();
+        assert_eq!(extra[3], Token::Address(weth.order.strategy));
+    }
+}
@@ -0,0 +1,103 @@
+//! Test-only constructors for API types and signers.
+
+use crate::signer;
+use crate::types::{Event, Network, Order};
+use ethers::prelude::{Address, Bytes, MockProvider, Provider};
+use ethers::signers::LocalWallet;
+use std::sync::Arc;
+
+/// The wallet of private key 1, whose address is fixed.
+pub fn wallet() -> LocalWallet {
+    "0000000000000000000000000000000000000000000000000000000000000001"
+        .parse()
+        .unwrap()
+}
+
+pub fn order() -> Order {
+    serde_json::from_value(serde_json::json!({
+        "hash": "0x0a6ea2fd7b5ab8f9c0d1d8b3f2c2b0fa2b7bdb2f5a9d6c7e1e4b3a2f1d0c9b8a",
+        "collectionAddress": "0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258",
+        "tokenId": "1",
+        "isOrderAsk": true,
+        "signer": "0x9e69b59b8d2a094cb1117f92ff7dcf51ed467b41",
+        "strategy": "0x579af6fd30bf83a5ac0d636bc619f98dbdeb930c",
+        "currencyAddress": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+        "amount": "1",
+        "price": "1000000000000000000",
+        "nonce": "0",
+        "startTime": 1667747434,
+        "endTime": 1667754634,
+        "minPercentageToAsk": 8500,
+        "params": "",
+        "status": "VALID",
+        "signature": null,
+        "v": null,
+        "r": null,
+        "s": null
+    }))
+    .unwrap()
+}
+
+/// A provider answering one `DOMAIN_SEPARATOR()` call with `network`'s
+/// separator, enough for a client's first domain check.
+pub fn exchange_provider(network: &Network) -> Arc<Provider<MockProvider>> {
+    let (provider, mock) = Provider::mocked();
+    mock.push(Bytes::from(signer::domain(network).separator().to_vec())).unwrap();
+    Arc::new(provider)
+}
+
+/// `order()` with another token id and price.
+pub fn listing(token_id: &str, price: &str) -> Order {
+    Order {
+        token_id: token_id.to_string(),
+        price: price.to_string(),
+        ..order()
+    }
+}
+
+/// `event(type_, created_at)` on another token at another price.
+pub fn event_at(type_: &str, created_at: &str, token_id: &str, price: &str) -> Event {
+    let mut event = event(type_, created_at);
+    event.token.as_mut().unwrap().token_id = token_id.to_string();
+    event.order.as_mut().unwrap().price = price.to_string();
+    event
+}
+
+/// A sale of token 1 from `from` to `to` at `price`.
+pub fn sale(from: Address, to: Address, price: &str) -> Event {
+    Event {
+        from,
+        to: Some(to),
+        ..event_at("SALE", "2022-11-06T16:00:00Z", "1", price)
+    }
+}
+
+pub fn event(type_: &str, created_at: &str) -> Event {
+    serde_json::from_value(serde_json::json!({
+        "id": 1,
+        "from": "0x9e69b59b8d2a094cb1117f92ff7dcf51ed467b41",
+        "to": "0x3d67b76cf3dcc881255eb2262e788be03b2f5b9f",
+        "type": type_,
+        "hash": "0x5f2b1c7e0d1bd9a7c3e7f8c2a4b6d8e0f1a3c5e7b9d1f3a5c7e9b1d3f5a7c9e1",
+        "createdAt": created_at,
+        "collection": {
+            "address": "0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258",
+            "name": "Otherdeed"
+        },
+        "token": {
+            "tokenId": "1",
+            "name": null,
+            "imageURI": null
+        },
+        "order": {
+            "isOrderAsk": true,
+            "signer": "0x9e69b59b8d2a094cb1117f92ff7dcf51ed467b41",
+            "strategy": "0x579af6fd30bf83a5ac0d636bc619f98dbdeb930c",
+            "currencyAddress": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "price": "1000000000000000000",
+            "startTime": 1667747434,
+            "endTime": 1667754634
+        }
+    }))
+    .unwrap()
+}
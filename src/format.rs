@@ -0,0 +1,108 @@
+use crate::amount::Amount;
+use crate::gateways::{self, DEFAULT_IPFS_GATEWAYS};
+use crate::links;
+use crate::types::{Event, EventType, Network};
+use ethers::prelude::{Address, U256};
+use serde_json::{json, Value};
+
+const SALE_COLOR: u32 = 0x2ecc71;
+const LISTING_COLOR: u32 = 0x3498db;
+
+/// A Discord embed for a sale or listing event on `network`, ready to send as one
+/// of a webhook message's `embeds`. Other event types have no embed.
+///
+/// IPFS images are linked through the first of `DEFAULT_IPFS_GATEWAYS`, as
+/// Discord only loads HTTP(S) images.
+pub fn discord_embed(network: &Network, event: &Event) -> Option<Value> {
+    let (verb, color) = match event.type_ {
+        EventType::Sale => ("Sale", SALE_COLOR),
+        EventType::List => ("Listing", LISTING_COLOR),
+        _ => return None,
+    };
+
+    let collection = event.collection.as_ref()?;
+    let token = event.token.as_ref()?;
+    let name = token
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("{} #{}", collection.name, token.token_id));
+
+    let mut fields = vec![];
+    if let Some(order) = &event.order {
+        fields.push(field("Price", price(network, &order.price, order.currency_address)));
+    }
+    let buyer = event.to.filter(|_| event.type_ == EventType::Sale);
+    fields.push(field(if buyer.is_some() { "Seller" } else { "Lister" }, address_link(network, event.from)));
+    if let Some(buyer) = buyer {
+        fields.push(field("Buyer", address_link(network, buyer)));
+    }
+    if let Some(hash) = &event.hash {
+        fields.push(field("Transaction", format!("[Etherscan]({})", links::tx_url(network, hash))));
+    }
+
+    let mut embed = json!({
+        "title": format!("{}: {}", verb, name),
+        "url": links::token_url(network, collection.address, &token.token_id),
+        "color": color,
+        "fields": fields,
+        "timestamp": event.created_at.to_rfc3339(),
+    });
+    if let Some(image) = token.image_uri.as_deref().and_then(|uri| gateways::http_url(uri, DEFAULT_IPFS_GATEWAYS[0])) {
+        embed["image"] = json!({ "url": image });
+    }
+
+    Some(embed)
+}
+
+fn field(name: &str, value: String) -> Value {
+    json!({ "name": name, "value": value, "inline": true })
+}
+
+/// The price in whole units of its currency, e.g. `1.5 WETH`.
+fn price(network: &Network, wei: &str, currency: Address) -> String {
+    let amount = U256::from_dec_str(wei).map(|value| Amount::wei(value).to_string()).unwrap_or_else(|_| wei.to_string());
+    let symbol = if currency == network.weth() {
+        "WETH"
+    } else if currency.is_zero() {
+        "ETH"
+    } else {
+        return format!("{} ({:?})", amount, currency);
+    };
+
+    format!("{} {}", amount, symbol)
+}
+
+fn address_link(network: &Network, address: Address) -> String {
+    let full = format!("{:?}", address);
+    format!("[{}…{}]({})", &full[..6], &full[38..], links::address_url(network, address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_sales_and_skips_other_events() {
+        let network = Network::Mainnet;
+        let mut sale = crate::fixtures::event("SALE", "2022-11-06T14:00:00Z");
+        let embed = discord_embed(&network, &sale).unwrap();
+
+        assert_eq!(embed["title"], "Sale: Otherdeed #1");
+        assert_eq!(
+            embed["url"],
+            "https://looksrare.org/collections/0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258/1"
+        );
+        assert_eq!(embed["fields"][0]["value"], "1 WETH");
+        assert_eq!(embed["fields"][2]["name"], "Buyer");
+        assert!(embed.get("image").is_none());
+
+        sale.token.as_mut().unwrap().image_uri = Some(String::from("ipfs://QmHash/1.png"));
+        let embed = discord_embed(&network, &sale).unwrap();
+        assert_eq!(embed["image"]["url"], "https://ipfs.io/ipfs/QmHash/1.png");
+
+        let listing = discord_embed(&network, &crate::fixtures::event("LIST", "2022-11-06T14:00:00Z")).unwrap();
+        assert_eq!(listing["fields"][1]["name"], "Lister");
+
+        assert!(discord_embed(&network, &crate::fixtures::event("TRANSFER", "2022-11-06T14:00:00Z")).is_none());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::health::Health;
+use crate::limiter::{Priority, RateLimiter};
+use reqwest::Client;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Public gateways tried for `ipfs://` URIs when no pool is configured.
+pub const DEFAULT_IPFS_GATEWAYS: [&str; 3] = [
+    "https://ipfs.io/ipfs/",
+    "https://cloudflare-ipfs.com/ipfs/",
+    "https://gateway.pinata.cloud/ipfs/",
+];
+
+/// IPFS gateways fetched from in rotation, skipping ones that keep failing.
+///
+/// A gateway that fails `max_failures` times in a row is left out for
+/// `cooldown`, unless every gateway is out, in which case all are tried.
+/// Share one pool between fetchers so they see the same health and limits.
+#[derive(Debug)]
+pub struct GatewayPool {
+    gateways: Vec<Gateway>,
+    health: Health,
+}
+
+#[derive(Debug)]
+struct Gateway {
+    url: String,
+    limiter: Option<RateLimiter>,
+}
+
+/// A gateway's health as last observed by the pool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GatewayStatus {
+    pub url: String,
+    /// Consecutive failures since the last success.
+    pub failures: u32,
+    pub healthy: bool,
+}
+
+impl Default for GatewayPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_IPFS_GATEWAYS)
+    }
+}
+
+impl GatewayPool {
+    /// A pool of gateways, each a URL prefix the CID path is appended to.
+    pub fn new<I>(urls: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let gateways: Vec<Gateway> = urls
+            .into_iter()
+            .map(|url| Gateway {
+                url: url.into(),
+                limiter: None,
+            })
+            .collect();
+
+        Self {
+            health: Health::new(gateways.len()),
+            gateways,
+        }
+    }
+
+    /// Sends at most `requests` requests per `period` to the gateway at `url`.
+    pub fn rate_limit(mut self, url: &str, requests: u32, period: Duration) -> Self {
+        if let Some(gateway) = self.gateways.iter_mut().find(|gateway| gateway.url == url) {
+            gateway.limiter = Some(RateLimiter::new(requests, period));
+        }
+        self
+    }
+
+    /// Leaves a gateway out for `cooldown` after `max_failures` failures in a row.
+    pub fn cooldown(mut self, max_failures: u32, cooldown: Duration) -> Self {
+        self.health.set_cooldown(max_failures, cooldown);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gateways.is_empty()
+    }
+
+    /// The gateway URLs in configuration order.
+    pub fn urls(&self) -> Vec<&str> {
+        self.gateways.iter().map(|gateway| gateway.url.as_str()).collect()
+    }
+
+    pub fn status(&self) -> Vec<GatewayStatus> {
+        let now = Instant::now();
+
+        self.gateways
+            .iter()
+            .enumerate()
+            .map(|(index, gateway)| {
+                let (failures, healthy) = self.health.status(index, now);
+                GatewayStatus {
+                    url: gateway.url.clone(),
+                    failures,
+                    healthy,
+                }
+            })
+            .collect()
+    }
+
+    /// Fetches `path` from a gateway, failing over to the next until one succeeds.
+    ///
+    /// Returns the last gateway's error when all of them fail, or `None` when the pool is empty.
+    pub async fn get(&self, client: &Client, path: &str) -> Option<Result<Vec<u8>, reqwest::Error>> {
+        self.get_parsed(client, path, usize::MAX, |body| Ok::<_, reqwest::Error>(body.to_vec())).await
+    }
+
+    /// Like `get`, but a gateway whose body `parse` rejects, e.g. an HTML error
+    /// page served with status 200, counts as failed and the next one is tried.
+    ///
+    /// At most `max_bytes` and one more are read of each body, so `parse` can
+    /// refuse a larger one without it being held in memory.
+    pub async fn get_parsed<T, E>(
+        &self,
+        client: &Client,
+        path: &str,
+        max_bytes: usize,
+        parse: impl Fn(&[u8]) -> Result<T, E>,
+    ) -> Option<Result<T, E>>
+    where
+        E: From<reqwest::Error> + fmt::Display,
+    {
+        let mut last = None;
+
+        for index in self.health.order(Instant::now()) {
+            let gateway = &self.gateways[index];
+            if let Some(limiter) = &gateway.limiter {
+                // without a shed policy the limiter only ever waits
+                let _ = limiter.acquire(Priority::Normal).await;
+            }
+
+            let url = format!("{}{}", gateway.url, path);
+            match fetch(client, &url, max_bytes).await.map_err(E::from).and_then(|body| parse(&body)) {
+                Ok(parsed) => {
+                    self.health.report(index, true, Instant::now());
+                    return Some(Ok(parsed));
+                }
+                Err(e) => {
+                    log::debug!("gateway fetch from {} failed: {}", url, e);
+                    self.health.report(index, false, Instant::now());
+                    last = Some(Err(e));
+                }
+            }
+        }
+
+        last
+    }
+
+    /// Probes every gateway with `path`, e.g. a small well-known CID, and records the outcome.
+    pub async fn health_check(&self, client: &Client, path: &str) -> Vec<GatewayStatus> {
+        for (index, gateway) in self.gateways.iter().enumerate() {
+            let ok = fetch(client, &format!("{}{}", gateway.url, path), usize::MAX).await.is_ok();
+            self.health.report(index, ok, Instant::now());
+        }
+
+        self.status()
+    }
+}
+
+/// The CID and path of an `ipfs://` URI, accepting the common `ipfs://ipfs/` mistake.
+pub fn ipfs_path(uri: &str) -> Option<&str> {
+    let path = uri.strip_prefix("ipfs://")?;
+    Some(path.strip_prefix("ipfs/").unwrap_or(path))
+}
+
+/// An HTTP(S) URL for `uri`, through `gateway` if it's an `ipfs://` URI, for
+/// clients that can only load the web, e.g. chat embeds. Other schemes have none.
+pub fn http_url(uri: &str, gateway: &str) -> Option<String> {
+    if let Some(path) = ipfs_path(uri) {
+        return Some(format!("{}{}", gateway, path));
+    }
+
+    (uri.starts_with("https://") || uri.starts_with("http://")).then(|| uri.to_string())
+}
+
+/// Fetches the body at `url`, stopping once more than `max_bytes` have been read.
+pub(crate) async fn fetch(client: &Client, url: &str, max_bytes: usize) -> Result<Vec<u8>, reqwest::Error> {
+    let mut res = client.get(url).send().await?.error_for_status()?;
+
+    let mut body = vec![];
+    while let Some(chunk) = res.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() > max_bytes {
+            break;
+        }
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_gateway_health_by_url() {
+        let pool = GatewayPool::new(["a/", "b/"])
+            .rate_limit("b/", 1, Duration::from_secs(1))
+            .cooldown(1, Duration::from_secs(60));
+        pool.health.report(1, false, Instant::now());
+
+        let status = pool.status();
+        assert_eq!(status[0], GatewayStatus { url: String::from("a/"), failures: 0, healthy: true });
+        assert!(!status[1].healthy);
+        assert!(pool.gateways[1].limiter.is_some());
+        assert_eq!(pool.urls(), vec!["a/", "b/"]);
+    }
+
+    #[test]
+    fn builds_http_urls_for_ipfs_uris() {
+        let gateway = DEFAULT_IPFS_GATEWAYS[0];
+        assert_eq!(http_url("ipfs://ipfs/QmHash/1.png", gateway).unwrap(), "https://ipfs.io/ipfs/QmHash/1.png");
+        assert_eq!(http_url("https://x.example/1.png", gateway).unwrap(), "https://x.example/1.png");
+        assert_eq!(http_url("data:image/png;base64,AAAA", gateway), None);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::contracts::ERC721;
+use crate::secret::Redacted;
+use crate::watch::unix_now;
+use crate::ClientError;
+use ethers::prelude::{Address, Middleware, Signature, U256};
+use ethers::signers::Signer;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A signed claim that `owner` holds a token, for gating services to check.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipProof {
+    pub collection: Address,
+    pub token_id: U256,
+    pub owner: Address,
+    /// Unix timestamp the proof was signed at, bounding how long it can be replayed.
+    pub issued_at: u64,
+    pub signature: Signature,
+}
+
+impl fmt::Debug for OwnershipProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnershipProof")
+            .field("collection", &self.collection)
+            .field("token_id", &self.token_id)
+            .field("owner", &self.owner)
+            .field("issued_at", &self.issued_at)
+            .field("signature", &Redacted(&self.signature))
+            .finish()
+    }
+}
+
+impl OwnershipProof {
+    /// The personal message the owner signs.
+    pub fn message(&self) -> String {
+        message(self.collection, self.token_id, self.owner, self.issued_at)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verification {
+    Valid,
+    /// The signature was not made by the claimed owner.
+    BadSignature,
+    /// The proof is older than the allowed age, or dated in the future.
+    Expired,
+    /// The claimed owner does not hold the token any more.
+    NotOwner { owner: Address },
+}
+
+/// Signs an ownership proof for a token held by `signer`.
+///
+/// Ownership itself is not checked here; the verifier checks it on-chain.
+pub async fn prove_ownership<S: Signer>(
+    collection: Address,
+    token_id: U256,
+    signer: &S,
+) -> Result<OwnershipProof, ClientError> {
+    let owner = signer.address();
+    let issued_at = unix_now();
+
+    let signature = signer
+        .sign_message(message(collection, token_id, owner, issued_at))
+        .await
+        .map_err(|e| ClientError::SignerError(e.to_string()))?;
+
+    Ok(OwnershipProof {
+        collection,
+        token_id,
+        owner,
+        issued_at,
+        signature,
+    })
+}
+
+/// Checks a proof's signature and age, then that the owner still holds the token on-chain.
+pub async fn verify_proof<M: Middleware>(
+    provider: Arc<M>,
+    proof: &OwnershipProof,
+    max_age: Duration,
+) -> Result<Verification, ClientError> {
+    if let Some(verification) = check_signature(proof, unix_now(), max_age) {
+        return Ok(verification);
+    }
+
+    let contract = ERC721::new(proof.collection, provider);
+    let owner = contract.owner_of(proof.token_id).call().await?;
+
+    if owner != proof.owner {
+        return Ok(Verification::NotOwner { owner });
+    }
+
+    Ok(Verification::Valid)
+}
+
+/// The verdict when the proof fails before ownership needs checking.
+fn check_signature(proof: &OwnershipProof, now: u64, max_age: Duration) -> Option<Verification> {
+    if proof.signature.verify(proof.message(), proof.owner).is_err() {
+        return Some(Verification::BadSignature);
+    }
+
+    if proof.issued_at > now || now - proof.issued_at > max_age.as_secs() {
+        return Some(Verification::Expired);
+    }
+
+    None
+}
+
+fn message(collection: Address, token_id: U256, owner: Address, issued_at: u64) -> String {
+    format!(
+        "LooksRare ownership proof\ncollection: {:?}\ntoken: {}\nowner: {:?}\nissued at: {}",
+        collection, token_id, owner, issued_at
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn checks_signer_and_age() {
+        let wallet = crate::fixtures::wallet();
+        let proof = block_on(prove_ownership(Address::from_low_u64_be(1), U256::from(7), &wallet)).unwrap();
+        let hour = Duration::from_secs(3600);
+
+        assert_eq!(check_signature(&proof, proof.issued_at, hour), None);
+        assert_eq!(check_signature(&proof, proof.issued_at + 7200, hour), Some(Verification::Expired));
+
+        let mut forged = proof.clone();
+        forged.owner = Address::from_low_u64_be(2);
+        assert_eq!(check_signature(&forged, proof.issued_at, hour), Some(Verification::BadSignature));
+    }
+}
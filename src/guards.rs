@@ -0,0 +1,170 @@
+use crate::api::{LooksRareApi, OrdersRequest, Pagination, Sort, Status};
+use crate::ClientError;
+use ethers::prelude::{Address, U256};
+use thiserror::Error;
+
+/// Opt-in fat-finger protection checked when an order is constructed.
+///
+/// Every guard is disabled by default, so `OrderGuards::default()` accepts any price.
+#[derive(Clone, Debug, Default)]
+pub struct OrderGuards {
+    /// Reject asks priced more than this many percent above or below the collection floor.
+    pub max_floor_deviation: Option<u64>,
+    /// Reject bids priced above the current best ask.
+    pub reject_bids_above_best_ask: bool,
+}
+
+impl OrderGuards {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_floor_deviation(mut self, percent: u64) -> Self {
+        self.max_floor_deviation = Some(percent);
+        self
+    }
+
+    pub fn reject_bids_above_best_ask(mut self) -> Self {
+        self.reject_bids_above_best_ask = true;
+        self
+    }
+
+    /// Checks an ask price against a known floor. A missing or zero floor passes.
+    pub fn check_ask(&self, price: U256, floor: Option<U256>) -> Result<(), GuardViolation> {
+        let (max_deviation, floor) = match (self.max_floor_deviation, floor) {
+            (Some(max_deviation), Some(floor)) if !floor.is_zero() => (max_deviation, floor),
+            _ => return Ok(()),
+        };
+
+        let diff = if price > floor { price - floor } else { floor - price };
+        if diff * U256::from(100) > floor * U256::from(max_deviation) {
+            return Err(GuardViolation::FloorDeviation {
+                price,
+                floor,
+                max_deviation,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks a bid price against a known best ask. A missing best ask passes.
+    pub fn check_bid(&self, price: U256, best_ask: Option<U256>) -> Result<(), GuardViolation> {
+        if !self.reject_bids_above_best_ask {
+            return Ok(());
+        }
+
+        match best_ask {
+            Some(best_ask) if price > best_ask => Err(GuardViolation::BidAboveBestAsk {
+                price,
+                best_ask,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fetches the collection floor and checks an ask price against it.
+    pub async fn validate_ask(
+        &self,
+        api: &LooksRareApi,
+        collection: Address,
+        price: U256,
+    ) -> Result<(), ClientError> {
+        if self.max_floor_deviation.is_none() {
+            return Ok(());
+        }
+
+        let stats = api.get_collection_stats(collection).await?;
+        let floor = stats.floor_price;
+
+        self.check_ask(price, floor)?;
+
+        Ok(())
+    }
+
+    /// Fetches the best valid ask (for the token, or the whole collection) and checks a bid price against it.
+    pub async fn validate_bid(
+        &self,
+        api: &LooksRareApi,
+        collection: Address,
+        token_id: Option<u64>,
+        price: U256,
+    ) -> Result<(), ClientError> {
+        if !self.reject_bids_above_best_ask {
+            return Ok(());
+        }
+
+        let req = OrdersRequest {
+            is_order_ask: Some(true),
+            collection: Some(collection),
+            token_id,
+            signer: None,
+            nonce: None,
+            strategy: None,
+            currency: None,
+            price: None,
+            start_time: None,
+            end_time: None,
+            status: Some(vec![Status::Valid]),
+            pagination: Some(Pagination { first: Some(1), cursor: None }),
+            sort: Some(Sort::PriceAsc),
+            extra_params: vec![],
+        };
+
+        let orders = api.get_orders(req).await?;
+        let best_ask = orders
+            .first()
+            .and_then(|order| U256::from_dec_str(&order.price).ok());
+
+        self.check_bid(price, best_ask)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GuardViolation {
+    #[error("Ask price {price} deviates more than {max_deviation}% from floor {floor}")]
+    FloorDeviation { price: U256, floor: U256, max_deviation: u64 },
+    #[error("Bid price {price} is above best ask {best_ask}")]
+    BidAboveBestAsk { price: U256, best_ask: U256 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_guards_accept_anything() {
+        let guards = OrderGuards::default();
+        assert!(guards.check_ask(U256::from(1), Some(U256::from(1000))).is_ok());
+        assert!(guards.check_bid(U256::from(1000), Some(U256::from(1))).is_ok());
+    }
+
+    #[test]
+    fn ask_within_floor_deviation_passes() {
+        let guards = OrderGuards::new().max_floor_deviation(20);
+        assert!(guards.check_ask(U256::from(80), Some(U256::from(100))).is_ok());
+        assert!(guards.check_ask(U256::from(120), Some(U256::from(100))).is_ok());
+        assert!(guards.check_ask(U256::from(5), None).is_ok());
+        assert!(guards.check_ask(U256::from(5), Some(U256::zero())).is_ok());
+    }
+
+    #[test]
+    fn ask_outside_floor_deviation_is_rejected() {
+        let guards = OrderGuards::new().max_floor_deviation(20);
+        let low = guards.check_ask(U256::from(79), Some(U256::from(100)));
+        let high = guards.check_ask(U256::from(121), Some(U256::from(100)));
+        assert!(matches!(low, Err(GuardViolation::FloorDeviation { .. })));
+        assert!(matches!(high, Err(GuardViolation::FloorDeviation { .. })));
+    }
+
+    #[test]
+    fn bid_above_best_ask_is_rejected() {
+        let guards = OrderGuards::new().reject_bids_above_best_ask();
+        assert!(guards.check_bid(U256::from(100), Some(U256::from(100))).is_ok());
+        assert!(guards.check_bid(U256::from(100), None).is_ok());
+        let res = guards.check_bid(U256::from(101), Some(U256::from(100)));
+        assert!(matches!(res, Err(GuardViolation::BidAboveBestAsk { .. })));
+    }
+}
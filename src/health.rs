@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Rotation and failure tracking over a fixed set of endpoints, by index.
+///
+/// An endpoint that fails `max_failures` times in a row is left out for
+/// `cooldown`, unless every endpoint is out, in which case all are tried.
+#[derive(Debug)]
+pub(crate) struct Health {
+    endpoints: Vec<Mutex<EndpointHealth>>,
+    next: AtomicUsize,
+    max_failures: u32,
+    cooldown: Duration,
+}
+
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    failures: u32,
+    down_until: Option<Instant>,
+}
+
+impl Health {
+    pub(crate) fn new(len: usize) -> Self {
+        Self {
+            endpoints: (0..len).map(|_| Mutex::new(EndpointHealth::default())).collect(),
+            next: AtomicUsize::new(0),
+            max_failures: 3,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+
+    pub(crate) fn set_cooldown(&mut self, max_failures: u32, cooldown: Duration) {
+        self.max_failures = max_failures.max(1);
+        self.cooldown = cooldown;
+    }
+
+    /// Consecutive failures of the endpoint at `index`, and whether it is in use at `now`.
+    pub(crate) fn status(&self, index: usize, now: Instant) -> (u32, bool) {
+        let health = self.endpoints[index].lock().unwrap();
+        (health.failures, health.down_until.is_none_or(|until| until <= now))
+    }
+
+    /// Endpoint indices to try, starting at the next in rotation: the healthy ones,
+    /// or all of them when none are.
+    pub(crate) fn order(&self, now: Instant) -> Vec<usize> {
+        let len = self.endpoints.len();
+        if len == 0 {
+            return vec![];
+        }
+
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % len;
+        let (up, down): (Vec<usize>, Vec<usize>) =
+            (0..len).map(|offset| (start + offset) % len).partition(|&index| self.status(index, now).1);
+
+        if up.is_empty() {
+            down
+        } else {
+            up
+        }
+    }
+
+    pub(crate) fn report(&self, index: usize, ok: bool, now: Instant) {
+        let mut health = self.endpoints[index].lock().unwrap();
+
+        if ok {
+            *health = EndpointHealth::default();
+            return;
+        }
+
+        health.failures += 1;
+        if health.failures >= self.max_failures {
+            health.down_until = Some(now + self.cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_and_skips_failing_endpoints() {
+        let mut health = Health::new(3);
+        health.set_cooldown(2, Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert_eq!(health.order(now), vec![0, 1, 2]);
+        assert_eq!(health.order(now), vec![1, 2, 0]);
+
+        health.report(1, false, now);
+        assert_eq!(health.order(now), vec![2, 0, 1], "one failure keeps the endpoint in");
+
+        health.report(1, false, now);
+        assert_eq!(health.order(now), vec![0, 2]);
+        assert_eq!(health.status(1, now), (2, false));
+
+        // back in after the cooldown, and a success clears its failures
+        assert_eq!(health.order(now + Duration::from_secs(61)), vec![1, 2, 0]);
+        health.report(1, true, now);
+        assert_eq!(health.status(1, now), (0, true));
+    }
+
+    #[test]
+    fn tries_every_endpoint_when_all_are_down() {
+        let mut health = Health::new(2);
+        health.set_cooldown(1, Duration::from_secs(60));
+        let now = Instant::now();
+        health.report(0, false, now);
+        health.report(1, false, now);
+
+        assert_eq!(health.order(now), vec![0, 1]);
+        assert!(Health::new(0).order(now).is_empty());
+    }
+}
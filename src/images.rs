@@ -0,0 +1,216 @@
+//! Token image thumbnails, downloaded once and kept on disk.
+//!
+//! Decoding and resizing run on tokio's blocking pool, so thumbnails need a
+//! tokio runtime. Sources over the download cap or the decoder's limits are refused.
+
+use crate::gateways::GatewayPool;
+use crate::metadata::{self, MetadataError};
+use crate::types::Token;
+use ethers::prelude::H256;
+use image::imageops::FilterType;
+use image::io::{Limits, Reader};
+use reqwest::Client;
+use std::fs;
+use std::io::{self, Cursor};
+use std::path::PathBuf;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// The largest width or height of a source image that is decoded.
+const MAX_SOURCE_DIMENSION: u32 = 16_384;
+/// The most memory decoding one source image may allocate.
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// A thumbnail stored in the cache directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Thumbnail {
+    /// The PNG file, named after `content_hash` and the thumbnail size.
+    pub path: PathBuf,
+    /// keccak256 of the source image as downloaded.
+    pub content_hash: H256,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Error)]
+pub enum ImageError {
+    #[error(transparent)]
+    Fetch(#[from] MetadataError),
+    #[error(transparent)]
+    Decode(#[from] image::ImageError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Thumbnail task failed: {0}")]
+    Task(String),
+}
+
+/// Downloads token images and stores thumbnails keyed by the source image's content hash,
+/// so the same image behind different URLs is resized and stored once.
+#[derive(Clone, Debug)]
+pub struct ThumbnailCache {
+    client: Client,
+    pool: Arc<GatewayPool>,
+    dir: PathBuf,
+    size: u32,
+    max_download: usize,
+}
+
+impl ThumbnailCache {
+    /// A cache in `dir` of thumbnails fitting in 256x256 pixels, made from
+    /// source images of at most 20 MiB.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            client: Client::new(),
+            pool: Arc::new(GatewayPool::default()),
+            dir: dir.into(),
+            size: 256,
+            max_download: 20 * 1024 * 1024,
+        }
+    }
+
+    /// Refuses source images larger than `bytes`, reading no more of them.
+    pub fn max_download(mut self, bytes: usize) -> Self {
+        self.max_download = bytes;
+        self
+    }
+
+    /// Thumbnails fit in `size`x`size` pixels, keeping the aspect ratio.
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = size.max(1);
+        self
+    }
+
+    /// Fetches IPFS images through `pool`, e.g. the one the metadata fetcher uses.
+    pub fn gateway_pool(mut self, pool: Arc<GatewayPool>) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// The thumbnail of the image at `url`, downloading and resizing it unless it is cached.
+    pub async fn thumbnail(&self, url: &str) -> Result<Thumbnail, ImageError> {
+        if let Some(thumbnail) = self.cached(url)? {
+            return Ok(thumbnail);
+        }
+
+        let source =
+            metadata::download_parsed(&self.client, &self.pool, url, self.max_download, |source| Ok(source.to_vec())).await?;
+        let content_hash = H256(looksrare_core::keccak256(&source));
+        let path = self.path(content_hash);
+
+        let (size, target) = (self.size, path.clone());
+        let resized = tokio::task::spawn_blocking(move || resize(source, size, &target))
+            .await
+            .map_err(|e| ImageError::Task(e.to_string()))?;
+        let (width, height) = resized?;
+        fs::write(self.link(url), ethers::utils::hex::encode(content_hash))?;
+
+        Ok(Thumbnail {
+            path,
+            content_hash,
+            width,
+            height,
+        })
+    }
+
+    /// Thumbnails for every token with an image, in order. Tokens without one are skipped;
+    /// a failed download is reported in place without stopping the rest.
+    pub async fn prefetch(&self, tokens: &[Token]) -> Vec<(usize, Result<Thumbnail, ImageError>)> {
+        let mut thumbnails = vec![];
+
+        for (index, token) in tokens.iter().enumerate() {
+            if let Some(url) = &token.image_uri {
+                thumbnails.push((index, self.thumbnail(url).await));
+            }
+        }
+
+        thumbnails
+    }
+
+    fn path(&self, content_hash: H256) -> PathBuf {
+        self.dir.join(format!("{}-{}.png", ethers::utils::hex::encode(content_hash), self.size))
+    }
+
+    fn link(&self, url: &str) -> PathBuf {
+        let key = ethers::utils::hex::encode(looksrare_core::keccak256(url.as_bytes()));
+        self.dir.join("urls").join(key)
+    }
+
+    fn cached(&self, url: &str) -> Result<Option<Thumbnail>, ImageError> {
+        let hash = match fs::read_to_string(self.link(url)) {
+            Ok(hash) => hash,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let content_hash = match hash.trim().parse::<H256>() {
+            Ok(content_hash) => content_hash,
+            Err(_) => return Ok(None),
+        };
+
+        let path = self.path(content_hash);
+        let (width, height) = match image::image_dimensions(&path) {
+            Ok(dimensions) => dimensions,
+            // linked by a cache of another size, or removed since
+            Err(_) => return Ok(None),
+        };
+
+        Ok(Some(Thumbnail {
+            path,
+            content_hash,
+            width,
+            height,
+        }))
+    }
+}
+
+/// Decodes `source` within the decoder limits and saves it resized to fit in
+/// `size`x`size` at `path`, unless a thumbnail is already there.
+fn resize(source: Vec<u8>, size: u32, path: &std::path::Path) -> Result<(u32, u32), ImageError> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+
+    let mut reader = Reader::new(Cursor::new(source)).with_guessed_format()?;
+    reader.limits(limits);
+    let thumbnail = reader.decode()?.resize(size, size, FilterType::Triangle);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir.join("urls"))?;
+    }
+    if !path.exists() {
+        thumbnail.save_with_format(path, image::ImageFormat::Png)?;
+    }
+
+    Ok((thumbnail.width(), thumbnail.height()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    #[tokio::test]
+    async fn resizes_and_serves_from_cache() {
+        let mut png = vec![];
+        image::DynamicImage::new_rgb8(64, 32)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let url = format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&png));
+
+        let dir = std::env::temp_dir().join(format!("looksrare-images-{}", std::process::id()));
+        let cache = ThumbnailCache::new(&dir).size(16);
+
+        let thumbnail = cache.thumbnail(&url).await.unwrap();
+        assert_eq!((thumbnail.width, thumbnail.height), (16, 8));
+        assert_eq!(thumbnail.content_hash, H256(looksrare_core::keccak256(&png)));
+        assert!(thumbnail.path.exists());
+
+        assert_eq!(cache.cached(&url).unwrap(), Some(thumbnail));
+
+        let capped = ThumbnailCache::new(&dir).size(8).max_download(png.len() - 1);
+        let res = capped.thumbnail(&url).await;
+        assert!(matches!(res, Err(ImageError::Fetch(MetadataError::TooLarge { .. }))));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
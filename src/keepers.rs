@@ -0,0 +1,408 @@
+use crate::api::{LooksRareApi, OrdersRequest, Pagination, Sort, Status};
+use crate::audit::{AuditAction, OrderRef};
+use crate::book;
+use crate::bulk::{self, BulkMode, BulkOptions, BulkResult};
+use crate::contracts::{self, ERC721};
+use crate::types::{Order, Strategy};
+use crate::ClientError;
+use ethers::prelude::{Address, Middleware, H256, U256};
+use futures::{stream, Stream};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Listings cancelled because their token left the signer's wallet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delisted {
+    pub collection: Address,
+    pub token_id: U256,
+    pub nonces: Vec<U256>,
+    pub tx_hash: H256,
+}
+
+/// Cancels the signer's listings for tokens transferred out of its wallet.
+///
+/// `provider` must be able to send transactions from `signer`, e.g. a `SignerMiddleware`.
+#[derive(Debug)]
+pub struct AutoDelist<M> {
+    pub api: Arc<LooksRareApi>,
+    pub provider: Arc<M>,
+    pub signer: Address,
+    pub collections: Vec<Address>,
+    pub interval: Duration,
+}
+
+impl<M: Middleware> AutoDelist<M> {
+    /// Checks transfers out of the signer's wallet since `from_block` and cancels
+    /// listings for tokens it no longer owns. Returns what was cancelled and the
+    /// block to resume from.
+    pub async fn poll(&self, from_block: u64) -> Result<(Vec<Delisted>, u64), ClientError> {
+        let to_block = self
+            .provider
+            .get_block_number()
+            .await
+            .map_err(|e| ClientError::ProviderError(e.to_string()))?
+            .as_u64();
+        if to_block < from_block {
+            return Ok((vec![], from_block));
+        }
+
+        let listings = listings_by_token(&self.api, self.signer).await?;
+        let mut stale: Vec<(Address, U256, Vec<U256>)> = vec![];
+
+        for collection in &self.collections {
+            let contract = ERC721::new(*collection, self.provider.clone());
+            let transfers = contract
+                .transfer_filter()
+                .from_block(from_block)
+                .to_block(to_block)
+                .topic1(H256::from(self.signer))
+                .query()
+                .await?;
+
+            for transfer in transfers {
+                let nonces = match listings.get(&(*collection, transfer.token_id)) {
+                    Some(nonces) => nonces,
+                    None => continue,
+                };
+                if stale.iter().any(|(c, t, _)| c == collection && *t == transfer.token_id) {
+                    continue;
+                }
+
+                // the token may have come back since
+                let owner = contract.owner_of(transfer.token_id).call().await?;
+                if owner != self.signer {
+                    stale.push((*collection, transfer.token_id, nonces.clone()));
+                }
+            }
+        }
+
+        if stale.is_empty() {
+            return Ok((vec![], to_block + 1));
+        }
+
+        let nonces = stale.iter().flat_map(|(_, _, nonces)| nonces.clone()).collect();
+        let tx_hash = cancel_nonces(&self.api, self.provider.clone(), self.signer, nonces).await?;
+
+        let delisted = stale
+            .into_iter()
+            .map(|(collection, token_id, nonces)| Delisted {
+                collection,
+                token_id,
+                nonces,
+                tx_hash,
+            })
+            .collect();
+
+        Ok((delisted, to_block + 1))
+    }
+
+    /// Polls every `interval`, starting from the current block, emitting each cancellation.
+    pub fn run(self) -> impl Stream<Item = Result<Delisted, ClientError>> {
+        let state = DelistState {
+            keeper: self,
+            from_block: None,
+            pending: VecDeque::new(),
+            polled: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(delisted) = state.pending.pop_front() {
+                    return Some((Ok(delisted), state));
+                }
+
+                if state.polled {
+                    tokio::time::sleep(state.keeper.interval).await;
+                }
+                state.polled = true;
+
+                let start = match state.from_block {
+                    Some(block) => block,
+                    None => match state.keeper.provider.get_block_number().await {
+                        Ok(block) => block.as_u64(),
+                        Err(e) => return Some((Err(ClientError::ProviderError(e.to_string())), state)),
+                    },
+                };
+
+                match state.keeper.poll(start).await {
+                    Ok((delisted, next)) => {
+                        state.from_block = Some(next);
+                        state.pending.extend(delisted);
+                    }
+                    Err(e) => {
+                        state.from_block = Some(start);
+                        return Some((Err(e), state));
+                    }
+                }
+            }
+        })
+    }
+}
+
+struct DelistState<M> {
+    keeper: AutoDelist<M>,
+    from_block: Option<u64>,
+    pending: VecDeque<Delisted>,
+    polled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StaleReason {
+    Expired,
+    /// Another bidder's best offer is above this bid by more than the sweeper's threshold.
+    Outbid { best: U256 },
+}
+
+#[derive(Clone, Debug)]
+pub struct StaleBid {
+    pub order: Order,
+    pub reason: StaleReason,
+}
+
+/// Cancels the signer's expired and outbid offers.
+///
+/// An expired or outbid offer that is never cancelled keeps its nonce open,
+/// and can become fillable again, e.g. after a WETH top-up restores the balance
+/// behind it. `provider` must be able to send transactions from `signer`.
+#[derive(Debug)]
+pub struct BidSweeper<M> {
+    pub api: Arc<LooksRareApi>,
+    pub provider: Arc<M>,
+    pub signer: Address,
+    /// Bids outbid by more than this many percent are swept.
+    pub outbid_threshold: u64,
+    /// Most nonces cancelled in one transaction.
+    pub batch_size: usize,
+    /// No new cancellation is sent after this instant.
+    pub deadline: Option<Instant>,
+}
+
+impl<M: Middleware> BidSweeper<M> {
+    /// The signer's bids that are expired or outbid beyond the threshold at `now`.
+    pub async fn find_stale(&self, now: u64) -> Result<Vec<StaleBid>, ClientError> {
+        let req = OrdersRequest {
+            is_order_ask: Some(false),
+            signer: Some(self.signer),
+            status: Some(vec![Status::Valid, Status::Expired]),
+            ..Default::default()
+        };
+        let bids = self.api.get_all_orders(req).await?;
+
+        let mut best_competing: HashMap<(Address, String), Option<U256>> = HashMap::new();
+        let mut stale = vec![];
+
+        for bid in bids {
+            let key = (bid.collection_address, bid.token_id.clone());
+            let best = match best_competing.get(&key) {
+                Some(best) => *best,
+                None => {
+                    let best = self.best_competing_bid(&bid, now).await?;
+                    best_competing.insert(key, best);
+                    best
+                }
+            };
+
+            if let Some(reason) = classify(&bid, best, now, self.outbid_threshold) {
+                stale.push(StaleBid { order: bid, reason });
+            }
+        }
+
+        Ok(stale)
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Cancels every stale bid, `batch_size` nonces per transaction, keyed by batch.
+    ///
+    /// Stops at the first failed transaction or once the deadline passes,
+    /// reporting the remaining batches as skipped. Fails before sending anything
+    /// if a stale bid's nonce doesn't parse.
+    pub async fn sweep(&self, now: u64) -> Result<BulkResult<H256, ClientError>, ClientError> {
+        let nonces = self
+            .find_stale(now)
+            .await?
+            .iter()
+            .map(|stale| order_nonce(&stale.order))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let options = BulkOptions {
+            mode: BulkMode::AbortOnFirstError,
+            deadline: self.deadline,
+        };
+        let batches = nonces.chunks(self.batch_size.max(1)).map(<[U256]>::to_vec);
+
+        Ok(bulk::execute_with(batches, options, |batch| {
+            cancel_nonces(&self.api, self.provider.clone(), self.signer, batch)
+        })
+        .await)
+    }
+
+    /// The best live bid from others that `bid` competes with. A token bid competes
+    /// with the token's bids and the collection's offers, which can fill any token.
+    async fn best_competing_bid(&self, bid: &Order, now: u64) -> Result<Option<U256>, ClientError> {
+        let mut bids = self.valid_bids(bid.collection_address, None, Strategy::CollectionOffer).await?;
+        if bid.strategy != self.api.network().strategy(Strategy::CollectionOffer) {
+            let token_id = bid.token_id.parse().ok();
+            bids.extend(self.valid_bids(bid.collection_address, token_id, Strategy::StandardSale).await?);
+        }
+
+        Ok(bids
+            .iter()
+            .filter(|other| other.signer != self.signer && book::is_live(other, now))
+            .filter_map(|other| U256::from_dec_str(&other.price).ok())
+            .max())
+    }
+
+    /// The highest valid bids on `collection`, or on `token_id` of it, under `strategy`.
+    async fn valid_bids(
+        &self,
+        collection: Address,
+        token_id: Option<u64>,
+        strategy: Strategy,
+    ) -> Result<Vec<Order>, ClientError> {
+        let req = OrdersRequest {
+            is_order_ask: Some(false),
+            collection: Some(collection),
+            token_id,
+            strategy: Some(strategy),
+            status: Some(vec![Status::Valid]),
+            pagination: Some(Pagination {
+                first: Some(20),
+                cursor: None,
+            }),
+            sort: Some(Sort::PriceDesc),
+            ..Default::default()
+        };
+
+        Ok(self.api.get_orders(req).await?)
+    }
+}
+
+fn classify(bid: &Order, best_competing: Option<U256>, now: u64, threshold: u64) -> Option<StaleReason> {
+    if bid.end_time <= now {
+        return Some(StaleReason::Expired);
+    }
+
+    let price = U256::from_dec_str(&bid.price).ok()?;
+    let best = best_competing?;
+    if best * U256::from(100) > price * U256::from(100 + threshold) {
+        return Some(StaleReason::Outbid { best });
+    }
+
+    None
+}
+
+/// The signer's valid asks, as nonces grouped by collection and token.
+async fn listings_by_token(
+    api: &LooksRareApi,
+    signer: Address,
+) -> Result<HashMap<(Address, U256), Vec<U256>>, ClientError> {
+    let req = OrdersRequest {
+        is_order_ask: Some(true),
+        signer: Some(signer),
+        status: Some(vec![Status::Valid]),
+        ..Default::default()
+    };
+    let asks = api.get_all_orders(req).await?;
+
+    group_nonces(&asks)
+}
+
+fn group_nonces(orders: &[Order]) -> Result<HashMap<(Address, U256), Vec<U256>>, ClientError> {
+    let mut grouped: HashMap<(Address, U256), Vec<U256>> = HashMap::new();
+
+    for order in orders {
+        let token_id = parse_field(order, "token id", &order.token_id)?;
+        let nonce = order_nonce(order)?;
+        grouped.entry((order.collection_address, token_id)).or_default().push(nonce);
+    }
+
+    Ok(grouped)
+}
+
+/// `order`'s nonce, or an error naming the order if the API sent one that doesn't parse.
+pub(crate) fn order_nonce(order: &Order) -> Result<U256, ClientError> {
+    parse_field(order, "nonce", &order.nonce)
+}
+
+fn parse_field(order: &Order, field: &'static str, value: &str) -> Result<U256, ClientError> {
+    U256::from_dec_str(value).map_err(|_| ClientError::UnparsableOrder {
+        hash: order.hash.clone(),
+        field,
+    })
+}
+
+/// Cancels `nonces` in one exchange transaction and waits for it to be mined.
+pub(crate) async fn cancel_nonces<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    signer: Address,
+    nonces: Vec<U256>,
+) -> Result<H256, ClientError> {
+    let cancelled = async {
+        let call = contracts::cancel_orders(api.network(), provider, nonces.clone(), signer);
+        let pending = call.send().await?;
+        let tx_hash = *pending;
+
+        pending
+            .await
+            .map_err(|e| ClientError::ProviderError(e.to_string()))?;
+
+        Ok::<_, ClientError>(tx_hash)
+    }
+    .await;
+
+    let outcome = cancelled.as_ref().map(|tx_hash| format!("{:?}", tx_hash)).map_err(|e| e.to_string());
+    let orders = nonces.iter().map(|nonce| OrderRef { signer, nonce: *nonce }).collect();
+    api.audit(AuditAction::OrderCancelled, orders, &nonces, outcome);
+
+    cancelled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn groups_nonces_by_token() {
+        let mut first = fixtures::order();
+        first.nonce = String::from("4");
+        let mut second = first.clone();
+        second.nonce = String::from("9");
+        let mut other = first.clone();
+        other.token_id = String::from("2");
+
+        let grouped = group_nonces(&[first.clone(), second, other]).unwrap();
+
+        assert_eq!(grouped[&(first.collection_address, U256::one())], vec![U256::from(4), U256::from(9)]);
+        assert_eq!(grouped[&(first.collection_address, U256::from(2))], vec![U256::from(4)]);
+
+        let mut garbled = first.clone();
+        garbled.nonce = String::from("0x4");
+        assert!(matches!(
+            group_nonces(&[first, garbled.clone()]),
+            Err(ClientError::UnparsableOrder { hash, field: "nonce" }) if hash == garbled.hash
+        ));
+    }
+
+    #[test]
+    fn classifies_stale_bids() {
+        let mut bid = fixtures::order();
+        bid.is_order_ask = false;
+        bid.price = String::from("1000");
+        let live = bid.start_time + 1;
+
+        assert_eq!(classify(&bid, None, bid.end_time, 10), Some(StaleReason::Expired));
+        assert_eq!(classify(&bid, Some(U256::from(1100)), live, 10), None);
+        assert_eq!(
+            classify(&bid, Some(U256::from(1101)), live, 10),
+            Some(StaleReason::Outbid { best: U256::from(1101) })
+        );
+        assert_eq!(classify(&bid, None, live, 10), None);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::types::{Event, Network, Order};
+use ethers::prelude::Address;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Tags known addresses with a human-readable name for logs and rendered output.
+pub trait Labeler: Send + Sync {
+    fn label(&self, address: Address) -> Option<String>;
+
+    /// The label followed by the full address, e.g. `weth (0xc02a…)`.
+    fn display(&self, address: Address) -> String {
+        match self.label(address) {
+            Some(label) => format!("{} ({:?})", label, address),
+            None => format!("{:?}", address),
+        }
+    }
+}
+
+impl<F> Labeler for F
+where
+    F: Fn(Address) -> Option<String> + Send + Sync,
+{
+    fn label(&self, address: Address) -> Option<String> {
+        self(address)
+    }
+}
+
+/// Labels from a fixed map.
+#[derive(Clone, Debug, Default)]
+pub struct StaticLabels {
+    labels: HashMap<Address, String>,
+}
+
+impl StaticLabels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The LooksRare contracts and currencies on `network`.
+    pub fn known(network: &Network) -> Self {
+        Self::new()
+            .with_label(network.exchange(), "looksrare exchange")
+            .with_label(network.transfer_manager_erc721(), "looksrare transfer manager erc721")
+            .with_label(network.royalty_fee_manager(), "looksrare royalty fee manager")
+            .with_label(network.weth(), "weth")
+            .with_label(network.looks(), "looks")
+    }
+
+    pub fn with_label(mut self, address: Address, label: impl Into<String>) -> Self {
+        self.labels.insert(address, label.into());
+        self
+    }
+
+    /// Reads `address,label` lines. Blank lines and lines starting with `#` are ignored.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Self::parse(&contents)
+    }
+
+    fn parse(contents: &str) -> io::Result<Self> {
+        let mut labels = Self::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("invalid label on line {}", number + 1));
+            let (address, label) = line.split_once(',').ok_or_else(invalid)?;
+            let address: Address = address.trim().parse().map_err(|_| invalid())?;
+            labels = labels.with_label(address, label.trim());
+        }
+
+        Ok(labels)
+    }
+
+    /// Adds every label from `other`, replacing labels already present for the same address.
+    pub fn merge(mut self, other: StaticLabels) -> Self {
+        self.labels.extend(other.labels);
+        self
+    }
+}
+
+impl Labeler for StaticLabels {
+    fn label(&self, address: Address) -> Option<String> {
+        self.labels.get(&address).cloned()
+    }
+}
+
+/// A one-line summary of an order with its addresses labeled.
+pub fn describe_order(order: &Order, labeler: &dyn Labeler) -> String {
+    format!(
+        "{} {} #{} for {} of {} by {}",
+        if order.is_order_ask { "ask" } else { "bid" },
+        labeler.display(order.collection_address),
+        order.token_id,
+        order.price,
+        labeler.display(order.currency_address),
+        labeler.display(order.signer),
+    )
+}
+
+/// A one-line summary of an event with its addresses labeled.
+pub fn describe_event(event: &Event, labeler: &dyn Labeler) -> String {
+    let mut summary = format!("{} {} from {}", event.type_.to_str(), event.id, labeler.display(event.from));
+    if let Some(to) = event.to {
+        summary.push_str(&format!(" to {}", labeler.display(to)));
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_label_file() {
+        let labels = StaticLabels::parse(
+            "# desks\n0x0000000000000000000000000000000000000001, whale\n\n0x0000000000000000000000000000000000000002,router\n",
+        )
+        .unwrap();
+
+        assert_eq!(labels.label(Address::from_low_u64_be(1)).as_deref(), Some("whale"));
+        assert_eq!(labels.label(Address::from_low_u64_be(2)).as_deref(), Some("router"));
+        assert!(StaticLabels::parse("not an address,x").is_err());
+    }
+
+    #[test]
+    fn displays_labeled_and_unlabeled_addresses() {
+        let labeler = |address: Address| (address == Address::from_low_u64_be(1)).then(|| String::from("whale"));
+
+        assert_eq!(
+            labeler.display(Address::from_low_u64_be(1)),
+            "whale (0x0000000000000000000000000000000000000001)"
+        );
+        assert_eq!(
+            labeler.display(Address::from_low_u64_be(2)),
+            "0x0000000000000000000000000000000000000002"
+        );
+    }
+}
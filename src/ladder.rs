@@ -0,0 +1,101 @@
+use crate::order::{MakerOrder, OrderTemplate};
+use ethers::prelude::U256;
+
+/// Prices spaced evenly between two offsets from a reference price, e.g. the floor.
+///
+/// Offsets are in basis points, so `from_bps: 200, to_bps: 2000` runs from
+/// floor+2% to floor+20%, and negative offsets give prices below the reference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ladder {
+    pub reference: U256,
+    pub from_bps: i64,
+    pub to_bps: i64,
+}
+
+impl Ladder {
+    pub fn new(reference: U256, from_bps: i64, to_bps: i64) -> Self {
+        Self {
+            reference,
+            from_bps,
+            to_bps,
+        }
+    }
+
+    /// `steps` prices from the first offset to the last, both included.
+    pub fn prices(&self, steps: usize) -> Vec<U256> {
+        (0..steps)
+            .map(|step| {
+                let offset = if steps == 1 {
+                    self.from_bps
+                } else {
+                    self.from_bps + (self.to_bps - self.from_bps) * step as i64 / (steps as i64 - 1)
+                };
+                let bps = U256::from((10_000 + offset).max(0) as u64);
+                self.reference * bps / U256::from(10_000)
+            })
+            .collect()
+    }
+
+    /// One ask per token, the first token at the bottom of the ladder.
+    ///
+    /// Orders take consecutive nonces starting at `first_nonce`.
+    pub fn asks(&self, template: &OrderTemplate, tokens: &[U256], first_nonce: U256, start_time: u64) -> Vec<MakerOrder> {
+        self.prices(tokens.len())
+            .into_iter()
+            .zip(tokens)
+            .enumerate()
+            .map(|(i, (price, token_id))| template.instantiate(*token_id, price, first_nonce + i, start_time))
+            .collect()
+    }
+
+    /// `steps` bids on one token, in ladder order. For collection offers pass a zero token id.
+    ///
+    /// Orders take consecutive nonces starting at `first_nonce`.
+    pub fn bids(
+        &self,
+        template: &OrderTemplate,
+        token_id: U256,
+        steps: usize,
+        first_nonce: U256,
+        start_time: u64,
+    ) -> Vec<MakerOrder> {
+        self.prices(steps)
+            .into_iter()
+            .enumerate()
+            .map(|(i, price)| template.instantiate(token_id, price, first_nonce + i, start_time))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Network;
+    use ethers::prelude::Address;
+
+    #[test]
+    fn spaces_prices_between_offsets() {
+        let ladder = Ladder::new(U256::from(10_000), 200, 2000);
+        let prices = ladder.prices(10);
+
+        assert_eq!(prices.len(), 10);
+        assert_eq!(prices[0], U256::from(10_200));
+        assert_eq!(prices[1], U256::from(10_400));
+        assert_eq!(prices[9], U256::from(12_000));
+
+        assert_eq!(Ladder::new(U256::from(100), -500, -2000).prices(1), vec![U256::from(95)]);
+    }
+
+    #[test]
+    fn builds_ask_ladder_with_consecutive_nonces() {
+        let template = OrderTemplate::ask(&Network::Mainnet, Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let tokens = [U256::from(5), U256::from(9)];
+
+        let orders = Ladder::new(U256::from(1000), 0, 1000).asks(&template, &tokens, U256::from(40), 0);
+
+        assert_eq!(orders[0].token_id, U256::from(5));
+        assert_eq!(orders[0].price, U256::from(1000));
+        assert_eq!(orders[1].price, U256::from(1100));
+        assert_eq!(orders[1].nonce, U256::from(41));
+    }
+}
@@ -1,10 +1,67 @@
 #![crate_type = "lib"]
 
+pub mod account;
+pub mod amount;
+pub mod analytics;
+pub mod annotations;
 pub mod api;
+pub mod audit;
+pub mod bait;
+pub mod book;
+pub mod bulk;
+pub mod channel;
+pub mod composite;
+pub mod confirm;
 pub mod constants;
+pub mod contracts;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+pub mod diagnostics;
+pub mod execution;
+pub mod format;
+pub mod gateways;
+pub mod gating;
+pub mod guards;
+mod health;
+#[cfg(feature = "images")]
+pub mod images;
+pub mod keepers;
+pub mod labels;
+pub mod ladder;
+pub mod limiter;
+pub mod links;
+pub mod metadata;
+pub mod order;
+pub mod otc;
+pub mod ownership;
+pub mod profile;
+pub mod rates;
+pub mod recording;
+#[cfg(feature = "remote-wallet")]
+pub mod remote_wallet;
+pub mod retry;
+pub mod rpc;
+pub mod scheduler;
+pub mod screener;
+pub mod secret;
+pub mod session;
+pub mod signer;
+pub mod strategies;
+pub mod sweep;
+pub mod tape;
+pub mod tick;
+pub mod transfer;
 pub mod types;
+pub mod v1;
+pub mod validity;
+pub mod watch;
+
+#[cfg(test)]
+mod fixtures;
 
 use api::{
+    CollectionSort,
+    CollectionsRequest,
     LooksRareApi, 
     LooksRareApiError, 
     OrdersRequest,
@@ -14,16 +71,42 @@ use api::{
     Status,
 };
 
+use audit::{AuditAction, OrderRef};
+
+use bulk::{BulkMode, BulkResult};
+
+use contracts::{LooksRareExchange, ERC1155, ERC20, ERC721};
+
+use guards::GuardViolation;
+
+use metadata::MetadataError;
+
+use session::PolicyViolation;
+
+use order::SignedOrder;
+
+use otc::ImportRejection;
+
+use transfer::{TokenStandard, VaultPurchase};
+
 use types::{
     Account, 
+    Collection,
     CollectionInformation,
     CollectionRewards,
     CollectionStats,
+    Currency,
+    Network,
     Order,
+    Rewards,
+    Strategy,
+    Token,
 };
 
 use thiserror::Error;
-use ethers::prelude::Address;
+use std::collections::HashMap;
+use std::sync::Arc;
+use ethers::prelude::{Address, ContractError, Middleware, H256, U256};
 
 pub async fn get_account(
     api: &LooksRareApi, 
@@ -38,6 +121,28 @@ pub async fn get_account(
     Ok(account)
 }
 
+pub async fn get_accounts(
+    api: &LooksRareApi,
+    addresses: Vec<Address>,
+) -> Result<HashMap<Address, Account>, ClientError> {
+    let accounts = api
+        .get_accounts(addresses)
+        .await?;
+
+    Ok(accounts)
+}
+
+pub async fn create_order(
+    api: &LooksRareApi,
+    order: &SignedOrder,
+) -> Result<Order, ClientError> {
+    let order = api
+        .create_order(order)
+        .await?;
+
+    Ok(order)
+}
+
 pub async fn get_orders(
     api: &LooksRareApi, 
     is_order_ask: Option<bool>,
@@ -45,8 +150,8 @@ pub async fn get_orders(
     token_id: Option<u64>,
     signer: Option<Address>,
     nonce: Option<u64>,
-    strategy: Option<Address>,
-    currency: Option<Address>,
+    strategy: Option<Strategy>,
+    currency: Option<Currency>,
     min_price: Option<u128>,
     max_price: Option<u128>,
     start_time: Option<u64>,
@@ -71,6 +176,7 @@ pub async fn get_orders(
         status: status,
         pagination: Some(Pagination { first: num_of_orders, cursor: start_after_hash}),
         sort: sort,
+        extra_params: vec![],
     };
 
     let orders = api
@@ -80,6 +186,245 @@ pub async fn get_orders(
     Ok(orders)
 }
 
+/// Buys `token_id` of `collection` at its cheapest fixed-price listing from `signer`,
+/// which `provider` must sign for. Returns the transaction hash once sent.
+///
+/// WETH listings are paid in ETH; see `SignedOrder::fill`. Use
+/// `confirm::confirm_execution` to wait for the purchase to stick.
+pub async fn buy_token<M: Middleware>(
+    api: &LooksRareApi,
+    collection: Address,
+    token_id: u64,
+    signer: Address,
+    provider: Arc<M>,
+) -> Result<H256, ClientError> {
+    let req = OrdersRequest {
+        is_order_ask: Some(true),
+        collection: Some(collection),
+        token_id: Some(token_id),
+        strategy: Some(Strategy::StandardSale),
+        status: Some(vec![Status::Valid]),
+        pagination: Some(Pagination { first: Some(constants::DEFAULT_ORDERS_PER_PAGE), cursor: None }),
+        sort: Some(Sort::PriceAsc),
+        ..Default::default()
+    };
+    let asks = api
+        .get_orders(req)
+        .await?;
+
+    let ask = order::fillable_ask(api.network(), &asks, watch::unix_now())
+        .ok_or(ClientError::NoListing { collection, token_id })?;
+    api.domain_separator(provider.clone()).await?;
+    let call = ask.fill(api.network(), provider, signer);
+    let sent = call.send().await.map(|pending| *pending).map_err(ClientError::from);
+
+    let outcome = sent.as_ref().map(|tx_hash| format!("{:?}", tx_hash)).map_err(|e| e.to_string());
+    api.audit(AuditAction::OrderExecuted, vec![OrderRef::from(&ask.order)], &ask, outcome);
+
+    sent
+}
+
+/// Buys `token_id` of `collection` like `buy_token`, then sends it on from
+/// `signer` to `vault`, e.g. a multisig, once the purchase is mined.
+///
+/// Errors only if the purchase couldn't be sent; what happened afterwards is
+/// in the returned `VaultPurchase`.
+pub async fn buy_token_to_vault<M: Middleware>(
+    api: &LooksRareApi,
+    collection: Address,
+    token_id: u64,
+    signer: Address,
+    vault: Address,
+    provider: Arc<M>,
+) -> Result<VaultPurchase, ClientError> {
+    let purchase_tx = buy_token(api, collection, token_id, signer, provider.clone()).await?;
+
+    Ok(transfer::deliver_to_vault(api, provider, purchase_tx, collection, U256::from(token_id), signer, vault).await)
+}
+
+/// Accepts the valid offer `order_hash` by selling the bid token from `signer`,
+/// which `provider` must sign for. Returns the transaction hash once sent.
+///
+/// Collection offers are filled with `token_id`; offers on a specific token
+/// ignore it. Fails before sending if the bidder's WETH balance or allowance no
+/// longer covers the price, if `signer` hasn't approved the transfer manager for
+/// the collection's standard, or holds fewer ERC-1155 tokens than the offer buys.
+pub async fn accept_offer<M: Middleware>(
+    api: &LooksRareApi,
+    order_hash: H256,
+    token_id: Option<U256>,
+    signer: Address,
+    provider: Arc<M>,
+) -> Result<H256, ClientError> {
+    let network = api.network();
+    let req = OrdersRequest {
+        is_order_ask: Some(false),
+        status: Some(vec![Status::Valid]),
+        ..Default::default()
+    }
+    .extra_params(vec![(String::from("hash"), format!("{:?}", order_hash))]);
+    let offers = api
+        .get_orders(req)
+        .await?;
+
+    let offer = offers
+        .iter()
+        .find(|order| !order.is_order_ask && order.hash.eq_ignore_ascii_case(&format!("{:?}", order_hash)))
+        .ok_or(ClientError::NoOffer(order_hash))?;
+    let offer = SignedOrder::try_from(offer)?;
+    let bid = &offer.order;
+    api.domain_separator(provider.clone()).await?;
+
+    let weth = ERC20::new(bid.currency, provider.clone());
+    let balance_of = weth.balance_of(bid.signer);
+    let allowance = weth.allowance(bid.signer, network.exchange());
+    let (balance, allowance) = futures::try_join!(balance_of.call(), allowance.call())?;
+    let available = balance.min(allowance);
+    if available < bid.price {
+        return Err(ClientError::OfferUnfunded { bidder: bid.signer, price: bid.price, available });
+    }
+
+    let mut taker = offer.taker_order(signer);
+    if bid.strategy == network.strategy(Strategy::CollectionOffer) {
+        taker.token_id = token_id.ok_or(ClientError::IncompleteOrder("token id"))?;
+    }
+
+    let standard = transfer::detect_standard(provider.clone(), bid.collection).await?;
+    let operator = standard.transfer_manager(network);
+    let approved = match standard {
+        TokenStandard::Erc721 => {
+            ERC721::new(bid.collection, provider.clone())
+                .is_approved_for_all(signer, operator)
+                .call()
+                .await?
+        }
+        TokenStandard::Erc1155 => {
+            let collection = ERC1155::new(bid.collection, provider.clone());
+            let held = collection.balance_of(signer, taker.token_id);
+            let approved = collection.is_approved_for_all(signer, operator);
+            let (held, approved) = futures::try_join!(held.call(), approved.call())?;
+            if held < bid.amount {
+                return Err(ClientError::NotEnoughTokens { token_id: taker.token_id, held, amount: bid.amount });
+            }
+            approved
+        }
+    };
+    if !approved {
+        return Err(ClientError::NotApproved { collection: bid.collection, operator });
+    }
+    let call = LooksRareExchange::new(network.exchange(), provider)
+        .match_bid_with_taker_ask(taker, offer.to_exchange())
+        .from(signer);
+    let sent = call.send().await.map(|pending| *pending).map_err(ClientError::from);
+
+    let outcome = sent.as_ref().map(|tx_hash| format!("{:?}", tx_hash)).map_err(|e| e.to_string());
+    api.audit(AuditAction::OrderExecuted, vec![OrderRef::from(&offer.order)], &offer, outcome);
+
+    sent
+}
+
+/// Cancels every valid order of `signer` on chain, `batch_size` nonces per
+/// transaction sent from `signer`, which `provider` must sign for, waiting for
+/// each to be mined before sending the next.
+///
+/// Stops at the first failed transaction, reporting the remaining batches as
+/// skipped. Fails before sending anything if an order's nonce doesn't parse.
+pub async fn cancel_open_orders<M: Middleware>(
+    api: &LooksRareApi,
+    signer: Address,
+    provider: Arc<M>,
+    batch_size: usize,
+) -> Result<BulkResult<H256, ClientError>, ClientError> {
+    let req = OrdersRequest {
+        signer: Some(signer),
+        status: Some(vec![Status::Valid]),
+        ..Default::default()
+    };
+    let orders = api
+        .get_all_orders(req)
+        .await?;
+
+    let mut nonces = orders
+        .iter()
+        .map(keepers::order_nonce)
+        .collect::<Result<Vec<_>, _>>()?;
+    nonces.sort();
+    nonces.dedup();
+
+    let batches = nonces.chunks(batch_size.max(1)).map(<[U256]>::to_vec);
+
+    Ok(bulk::execute(batches, BulkMode::AbortOnFirstError, |batch| {
+        keepers::cancel_nonces(api, provider.clone(), signer, batch)
+    })
+    .await)
+}
+
+/// Cancels every order of `signer` with a nonce below `min_nonce` on chain, in
+/// one transaction sent from `signer`, which `provider` must sign for, and
+/// waits for it to be mined. `min_nonce` must be above the signer's current
+/// minimum nonce.
+pub async fn cancel_all_orders<M: Middleware>(
+    api: &LooksRareApi,
+    signer: Address,
+    provider: Arc<M>,
+    min_nonce: U256,
+) -> Result<H256, ClientError> {
+    let cancelled = async {
+        let call = contracts::cancel_all_orders_for_sender(api.network(), provider, min_nonce, signer);
+        let pending = call.send().await?;
+        let tx_hash = *pending;
+
+        pending
+            .await
+            .map_err(|e| ClientError::ProviderError(e.to_string()))?;
+
+        Ok::<_, ClientError>(tx_hash)
+    }
+    .await;
+
+    let outcome = cancelled.as_ref().map(|tx_hash| format!("{:?}", tx_hash)).map_err(|e| e.to_string());
+    api.audit(AuditAction::OrderCancelled, vec![], &min_nonce, outcome);
+
+    cancelled
+}
+
+/// Cancels `signer`'s orders with `nonces` on chain, in one transaction sent
+/// from `signer`, which `provider` must sign for, and waits for it to be mined.
+///
+/// The cancellation is audited in `api`'s audit log, if it has one. See
+/// `cancel_open_orders` to cancel every valid order in batches.
+pub async fn cancel_orders<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    nonces: Vec<U256>,
+    signer: Address,
+) -> Result<H256, ClientError> {
+    keepers::cancel_nonces(api, provider, signer, nonces).await
+}
+
+pub async fn get_collection_bids(
+    api: &LooksRareApi,
+    collection: Address,
+) -> Result<Vec<Order>, ClientError> {
+    let bids = api
+        .get_collection_bids(collection)
+        .await?;
+
+    Ok(bids)
+}
+
+pub async fn get_token_bids(
+    api: &LooksRareApi,
+    collection: Address,
+    token_id: u64,
+) -> Result<Vec<Order>, ClientError> {
+    let bids = api
+        .get_token_bids(collection, token_id)
+        .await?;
+
+    Ok(bids)
+}
+
 pub async fn get_nonce(
     api: &LooksRareApi, 
     address: Address,
@@ -93,6 +438,30 @@ pub async fn get_nonce(
     Ok(nonce)
 }
 
+pub async fn get_order_nonce(
+    api: &LooksRareApi,
+    address: Address,
+) -> Result<U256, ClientError> {
+    let nonce = api
+        .get_order_nonce(address)
+        .await?;
+
+    Ok(nonce)
+}
+
+pub async fn get_collections(
+    api: &LooksRareApi,
+    pagination: Option<Pagination>,
+    sort: Option<CollectionSort>,
+) -> Result<Vec<Collection>, ClientError> {
+    let req = CollectionsRequest { pagination, sort };
+    let collections = api
+        .get_collections(req)
+        .await?;
+
+    Ok(collections)
+}
+
 pub async fn get_collection_information(
     api: &LooksRareApi,
     address: Address,
@@ -115,6 +484,29 @@ pub async fn get_collection_stats(
     Ok(collection_stats)
 }
 
+pub async fn get_token(
+    api: &LooksRareApi,
+    collection: Address,
+    token_id: U256,
+) -> Result<Token, ClientError> {
+    let token = api
+        .get_token(collection, token_id)
+        .await?;
+
+    Ok(token)
+}
+
+pub async fn get_rewards(
+    api: &LooksRareApi,
+    address: Address,
+) -> Result<Rewards, ClientError> {
+    let rewards = api
+        .get_rewards(address)
+        .await?;
+
+    Ok(rewards)
+}
+
 pub async fn get_top_5_listing_rewards_collections(
     api: &LooksRareApi,
 ) -> Result<Vec<CollectionRewards>, ClientError> {
@@ -129,10 +521,50 @@ pub async fn get_top_5_listing_rewards_collections(
 pub enum ClientError {
     #[error(transparent)]
     LooksRareApiError(#[from] LooksRareApiError),
+    #[error(transparent)]
+    GuardViolation(#[from] GuardViolation),
+    #[error(transparent)]
+    Metadata(#[from] MetadataError),
+    #[error(transparent)]
+    PolicyViolation(#[from] PolicyViolation),
+    #[error(transparent)]
+    ImportRejected(#[from] ImportRejection),
+    #[error("Contract call failed: {0}")]
+    ContractError(String),
+    #[error("Provider request failed: {0}")]
+    ProviderError(String),
+    #[error("Signing failed: {0}")]
+    SignerError(String),
+    #[error("Order is missing a valid {0}")]
+    IncompleteOrder(&'static str),
+    #[error("Order {hash} has an unparsable {field}")]
+    UnparsableOrder { hash: String, field: &'static str },
+    #[error("No fillable listing for token {token_id} of {collection:?}")]
+    NoListing { collection: Address, token_id: u64 },
+    #[error("Order hash {claimed} does not match its contents, which hash to {computed:?}")]
+    HashMismatch { claimed: String, computed: H256 },
+    #[error("{0:?} implements neither ERC-721 nor ERC-1155")]
+    UnsupportedToken(Address),
+    #[error("No valid offer with hash {0:?}")]
+    NoOffer(H256),
+    #[error("Bidder {bidder:?} can pay {available} WETH of the {price} offered")]
+    OfferUnfunded { bidder: Address, price: U256, available: U256 },
+    #[error("{operator:?} is not approved to transfer {collection:?} tokens")]
+    NotApproved { collection: Address, operator: Address },
+    #[error("Holding {held} of token {token_id}, fewer than the {amount} offered for")]
+    NotEnoughTokens { token_id: U256, held: U256, amount: U256 },
+    #[error("EIP-712 domain mismatch on {network:?} (local: {local:?}, on-chain: {onchain:?})")]
+    DomainMismatch { network: Network, local: H256, onchain: H256 },
 }
 
-#[cfg(test)]
-mod tests {
+impl<M: Middleware> From<ContractError<M>> for ClientError {
+    fn from(err: ContractError<M>) -> Self {
+        ClientError::ContractError(err.to_string())
+    }
+}
+
+#[cfg(all(test, feature = "live-tests"))]
+mod live_tests {
     //use crate::types::Account;
 
     use super::*;
@@ -154,8 +586,8 @@ mod tests {
         let input_token_id: Option<u64> = Some(62962);
         let input_signer: Option<Address> = Some("0x9E69b59b8d2A094CB1117f92Ff7DCf51Ed467B41".parse().unwrap());
         let input_nonce: Option<u64> = Some(17832);
-        let input_strategy: Option<Address> = Some("0x579af6fd30bf83a5ac0d636bc619f98dbdeb930c".parse().unwrap());
-        let input_currency: Option<Address> = Some("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2".parse().unwrap());
+        let input_strategy: Option<Strategy> = Some(Strategy::StandardSale);
+        let input_currency: Option<Currency> = Some(Currency::Weth);
         let input_min_price: Option<u128> = Some(12000000000000000000000000000000000000);
         let input_max_price: Option<u128> = Some(13000000000000000000000000000000000000);                
         let input_start_time: Option<u64> = Some(1667747434);
@@ -203,8 +635,8 @@ mod tests {
         assert_eq!(input_token_id.unwrap(), output_token_id);
         assert_eq!(input_signer.unwrap(), output_signer);
         assert_eq!(input_nonce.unwrap(), output_nonce);
-        assert_eq!(input_strategy.unwrap(), output_strategy);
-        assert_eq!(input_currency.unwrap(), output_currency);
+        assert_eq!(api.network().strategy(input_strategy.unwrap()), output_strategy);
+        assert_eq!(api.network().currency(input_currency.unwrap()), output_currency);
         let greater_than_min_price: bool = input_min_price.unwrap() <= output_price;
         let less_than_max_price: bool = input_max_price.unwrap() >= output_price;
         assert!(greater_than_min_price && less_than_max_price);
@@ -0,0 +1,264 @@
+use reqwest::header::HeaderMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A token bucket shared by every request a client sends.
+///
+/// The bucket holds up to `burst` requests and refills one every
+/// `period / requests`, so bursts are allowed but the long-run rate is capped.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    burst: u32,
+    refill: Duration,
+    bucket: Mutex<Bucket>,
+    queued: AtomicUsize,
+    policies: Vec<ShedPolicy>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    available: u32,
+    updated: Instant,
+}
+
+/// How important a request is when the rate limiter is saturated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Background polling that can skip a round, e.g. watchers.
+    Low,
+    #[default]
+    Normal,
+    /// Requests that change state, like posting orders. Never shed by default.
+    High,
+}
+
+/// Rejects requests of `priority` or lower instead of queueing them once
+/// `max_queued` requests are already waiting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShedPolicy {
+    pub priority: Priority,
+    pub max_queued: usize,
+}
+
+impl ShedPolicy {
+    pub fn new(priority: Priority, max_queued: usize) -> Self {
+        Self { priority, max_queued }
+    }
+}
+
+/// The rate limit the API reported in its most recent response headers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ServerRateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Seconds until the server's window resets, as sent in `x-ratelimit-reset`.
+    pub reset: Option<u64>,
+}
+
+/// A snapshot of a client's request budget, for deciding whether to defer work.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Requests that can be sent right now without waiting, or `None` without a client-side limit.
+    pub remaining: Option<u32>,
+    /// Requests currently waiting for a slot.
+    pub queued: usize,
+    /// When the next request can be sent; now if there is budget left.
+    pub next_available: Instant,
+    pub server: ServerRateLimit,
+}
+
+impl RateLimiter {
+    /// Allows `requests` per `period`, all of which may be sent in a burst.
+    pub(crate) fn new(requests: u32, period: Duration) -> Self {
+        let requests = requests.max(1);
+        Self {
+            burst: requests,
+            refill: period / requests,
+            bucket: Mutex::new(Bucket {
+                available: requests,
+                updated: Instant::now(),
+            }),
+            queued: AtomicUsize::new(0),
+            policies: vec![],
+        }
+    }
+
+    pub(crate) fn shed(&mut self, policy: ShedPolicy) {
+        self.policies.push(policy);
+    }
+
+    /// Waits until a request may be sent and takes its slot.
+    ///
+    /// Cancellation safe: a dropped call takes no slot and leaves the queue.
+    ///
+    /// Fails without waiting when the request would have to queue and a shed
+    /// policy covers its priority at the current queue depth.
+    pub(crate) async fn acquire(&self, priority: Priority) -> Result<(), Priority> {
+        if self.try_acquire(Instant::now()).is_ok() {
+            return Ok(());
+        }
+
+        if self.sheds(priority, self.queued.load(Ordering::SeqCst)) {
+            return Err(priority);
+        }
+
+        // the guard leaves the queue even if this future is dropped while waiting
+        let _queued = Queued::join(&self.queued);
+        while let Err(wait) = self.try_acquire(Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+
+        Ok(())
+    }
+
+    fn sheds(&self, priority: Priority, queued: usize) -> bool {
+        self.policies
+            .iter()
+            .any(|policy| priority <= policy.priority && queued >= policy.max_queued)
+    }
+
+    /// Takes a slot, or returns how long until one frees up.
+    fn try_acquire(&self, now: Instant) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket, now);
+
+        if bucket.available == 0 {
+            return Err((bucket.updated + self.refill).saturating_duration_since(now));
+        }
+
+        bucket.available -= 1;
+        Ok(())
+    }
+
+    fn refill(&self, bucket: &mut Bucket, now: Instant) {
+        if bucket.available >= self.burst {
+            bucket.updated = now;
+            return;
+        }
+
+        let elapsed = now.saturating_duration_since(bucket.updated);
+        let tokens = (elapsed.as_nanos() / self.refill.as_nanos().max(1)) as u32;
+        if tokens == 0 {
+            return;
+        }
+
+        bucket.available = bucket.available.saturating_add(tokens).min(self.burst);
+        bucket.updated = if bucket.available == self.burst {
+            now
+        } else {
+            bucket.updated + self.refill * tokens
+        };
+    }
+
+    /// The client-side part of the status; `server` is left for the client to fill in.
+    pub(crate) fn status(&self) -> RateLimitStatus {
+        let now = Instant::now();
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket, now);
+
+        RateLimitStatus {
+            remaining: Some(bucket.available),
+            queued: self.queued.load(Ordering::SeqCst),
+            next_available: if bucket.available > 0 {
+                now
+            } else {
+                bucket.updated + self.refill
+            },
+            server: ServerRateLimit::default(),
+        }
+    }
+}
+
+/// Counts one waiting request for as long as it lives.
+struct Queued<'a>(&'a AtomicUsize);
+
+impl<'a> Queued<'a> {
+    fn join(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::SeqCst);
+        Self(queued)
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ServerRateLimit {
+    /// The rate limit headers of a response, if it sent any.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+        };
+
+        let observed = Self {
+            limit: header("x-ratelimit-limit"),
+            remaining: header("x-ratelimit-remaining"),
+            reset: header("x-ratelimit-reset"),
+        };
+
+        (observed != Self::default()).then_some(observed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn refills_one_slot_per_interval() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(1));
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire(start).is_ok());
+        assert!(limiter.try_acquire(start).is_ok());
+        assert_eq!(limiter.try_acquire(start), Err(Duration::from_millis(500)));
+
+        assert!(limiter.try_acquire(start + Duration::from_millis(500)).is_ok());
+        assert!(limiter.try_acquire(start + Duration::from_millis(500)).is_err());
+    }
+
+    #[test]
+    fn sheds_low_priority_first() {
+        let mut limiter = RateLimiter::new(1, Duration::from_secs(1));
+        limiter.shed(ShedPolicy::new(Priority::Low, 0));
+        limiter.shed(ShedPolicy::new(Priority::Normal, 10));
+
+        assert!(limiter.sheds(Priority::Low, 0));
+        assert!(!limiter.sheds(Priority::Normal, 9));
+        assert!(limiter.sheds(Priority::Normal, 10));
+        assert!(!limiter.sheds(Priority::High, 100));
+    }
+
+    #[tokio::test]
+    async fn dropped_waiter_leaves_the_queue() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        limiter.acquire(Priority::Normal).await.unwrap();
+
+        let waited = tokio::time::timeout(Duration::from_millis(10), limiter.acquire(Priority::Normal)).await;
+
+        assert!(waited.is_err());
+        assert_eq!(limiter.status().queued, 0);
+        assert_eq!(limiter.status().remaining, Some(0));
+    }
+
+    #[test]
+    fn reads_server_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("120"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("7"));
+
+        assert_eq!(ServerRateLimit::from_headers(&headers), Some(ServerRateLimit {
+            limit: Some(120),
+            remaining: Some(7),
+            reset: None,
+        }));
+        assert_eq!(ServerRateLimit::from_headers(&HeaderMap::new()), None);
+    }
+}
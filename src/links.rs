@@ -0,0 +1,55 @@
+use crate::types::{Network, Order, Strategy};
+use ethers::prelude::Address;
+
+/// The marketplace page an order can be filled from: its token's page, or the
+/// collection's for a collection offer.
+pub fn order_url(network: &Network, order: &Order) -> String {
+    if order.strategy == network.strategy(Strategy::CollectionOffer) {
+        return collection_url(network, order.collection_address);
+    }
+
+    token_url(network, order.collection_address, &order.token_id)
+}
+
+pub fn collection_url(network: &Network, collection: Address) -> String {
+    format!("{}/collections/{:?}", network.app_url(), collection)
+}
+
+pub fn token_url(network: &Network, collection: Address, token_id: &str) -> String {
+    format!("{}/{}", collection_url(network, collection), token_id)
+}
+
+pub fn tx_url(network: &Network, hash: &str) -> String {
+    format!("{}/tx/{}", network.explorer_url(), hash)
+}
+
+pub fn address_url(network: &Network, address: Address) -> String {
+    format!("{}/address/{:?}", network.explorer_url(), address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_orders_to_token_or_collection() {
+        let network = Network::Mainnet;
+        let mut order = crate::fixtures::order();
+
+        assert_eq!(
+            order_url(&network, &order),
+            "https://looksrare.org/collections/0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258/1"
+        );
+
+        order.strategy = network.strategy(Strategy::CollectionOffer);
+        assert_eq!(
+            order_url(&network, &order),
+            "https://looksrare.org/collections/0x34d85c9cdeb23fa97cb08333b511ac86e1c4e258"
+        );
+
+        assert_eq!(
+            address_url(&network, Address::from_low_u64_be(1)),
+            "https://etherscan.io/address/0x0000000000000000000000000000000000000001"
+        );
+    }
+}
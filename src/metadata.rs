@@ -0,0 +1,379 @@
+use crate::contracts::ERC721;
+use crate::gateways::{self, ipfs_path, GatewayPool};
+use crate::ClientError;
+use base64::Engine;
+use ethers::prelude::{Address, Middleware, H256, U256};
+use reqwest::{Client, ClientBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// A token's metadata document, normalized from the shapes collections actually publish.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// The image as an HTTP(S) or data URL, with `ipfs://` resolved through the first gateway.
+    pub image: Option<String>,
+    pub attributes: Vec<Attribute>,
+    /// keccak256 of the document exactly as fetched.
+    pub content_hash: H256,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attribute {
+    pub trait_type: String,
+    pub value: Value,
+    pub display_type: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum MetadataError {
+    #[error("Unsupported token URI: {0}")]
+    UnsupportedUri(String),
+    #[error("Malformed data URI")]
+    InvalidDataUri,
+    #[error("Could not fetch {uri}: {reason}")]
+    Unreachable { uri: String, reason: String },
+    #[error("{uri} is larger than {max_bytes} bytes")]
+    TooLarge { uri: String, max_bytes: usize },
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Fetches and normalizes token metadata, optionally caching documents on disk.
+#[derive(Clone, Debug)]
+pub struct MetadataFetcher {
+    client: Client,
+    pool: Arc<GatewayPool>,
+    cache_dir: Option<PathBuf>,
+}
+
+impl Default for MetadataFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataFetcher {
+    pub fn new() -> Self {
+        let client = ClientBuilder::new().timeout(Duration::from_secs(10)).build().unwrap();
+
+        Self {
+            client,
+            pool: Arc::new(GatewayPool::default()),
+            cache_dir: None,
+        }
+    }
+
+    /// Replaces the IPFS gateways, each a URL prefix the CID path is appended to.
+    pub fn gateways(mut self, gateways: Vec<String>) -> Self {
+        self.pool = Arc::new(GatewayPool::new(gateways));
+        self
+    }
+
+    /// Fetches IPFS documents through `pool`, sharing its health and rate limits.
+    pub fn gateway_pool(mut self, pool: Arc<GatewayPool>) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Keeps every fetched document in `dir`, keyed by its URI.
+    ///
+    /// A cached document is never refetched, which suits IPFS and data URIs;
+    /// metadata served over HTTP may change after it was cached.
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Reads the token's URI from the collection contract and fetches its metadata.
+    pub async fn fetch<M: Middleware>(
+        &self,
+        provider: Arc<M>,
+        collection: Address,
+        token_id: U256,
+    ) -> Result<TokenMetadata, ClientError> {
+        let contract = ERC721::new(collection, provider);
+        let uri = contract.token_uri(token_id).call().await?;
+
+        Ok(self.fetch_uri(&expand_id(&uri, token_id)).await?)
+    }
+
+    /// Fetches and normalizes the metadata document at `uri`.
+    ///
+    /// Only documents that normalize are cached. An IPFS gateway serving one
+    /// that doesn't, e.g. an error page, is skipped for the next gateway.
+    pub async fn fetch_uri(&self, uri: &str) -> Result<TokenMetadata, MetadataError> {
+        let gateway = self.pool.urls().first().copied();
+        if let Some(document) = self.cached(uri)? {
+            return normalize(&document, gateway);
+        }
+
+        let parse = |document: &[u8]| Ok::<_, MetadataError>((document.to_vec(), normalize(document, gateway)?));
+        let (document, metadata) = download_parsed(&self.client, &self.pool, uri, usize::MAX, parse).await?;
+        self.store(uri, &document)?;
+
+        Ok(metadata)
+    }
+
+    fn cache_path(&self, uri: &str) -> Option<PathBuf> {
+        let key = ethers::utils::hex::encode(looksrare_core::keccak256(uri.as_bytes()));
+        self.cache_dir.as_ref().map(|dir| dir.join(format!("{}.json", key)))
+    }
+
+    fn cached(&self, uri: &str) -> Result<Option<Vec<u8>>, MetadataError> {
+        match self.cache_path(uri).map(fs::read) {
+            Some(Ok(document)) => Ok(Some(document)),
+            Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(None),
+        }
+    }
+
+    fn store(&self, uri: &str, document: &[u8]) -> Result<(), MetadataError> {
+        if let (Some(dir), Some(path)) = (&self.cache_dir, self.cache_path(uri)) {
+            fs::create_dir_all(dir)?;
+            fs::write(path, document)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fetches a token's metadata with the default gateways and no cache.
+pub async fn fetch<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+    token_id: U256,
+) -> Result<TokenMetadata, ClientError> {
+    MetadataFetcher::new().fetch(provider, collection, token_id).await
+}
+
+/// Reads the document at a data, IPFS or HTTP(S) URI, failing over between the pool's gateways.
+pub(crate) async fn download(client: &Client, pool: &GatewayPool, uri: &str) -> Result<Vec<u8>, MetadataError> {
+    download_parsed(client, pool, uri, usize::MAX, |document| Ok(document.to_vec())).await
+}
+
+/// Like `download`, but only accepts a document `parse` succeeds on, moving on
+/// to the next gateway when one serves a document that fails to parse.
+///
+/// Documents larger than `max_bytes` fail with `MetadataError::TooLarge`, and
+/// are not read any further.
+pub(crate) async fn download_parsed<T>(
+    client: &Client,
+    pool: &GatewayPool,
+    uri: &str,
+    max_bytes: usize,
+    parse: impl Fn(&[u8]) -> Result<T, MetadataError>,
+) -> Result<T, MetadataError> {
+    let parse = |document: &[u8]| match document.len() > max_bytes {
+        true => Err(MetadataError::TooLarge {
+            uri: uri.to_string(),
+            max_bytes,
+        }),
+        false => parse(document),
+    };
+
+    if let Some(data) = uri.strip_prefix("data:") {
+        return parse(&decode_data_uri(data)?);
+    }
+
+    if let Some(path) = ipfs_path(uri) {
+        return match pool.get_parsed(client, path, max_bytes, parse).await {
+            Some(Ok(parsed)) => Ok(parsed),
+            Some(Err(MetadataError::Http(e))) => Err(fetch_failed(uri, e.to_string())),
+            Some(Err(e)) => Err(e),
+            None => Err(fetch_failed(uri, String::from("no IPFS gateways configured"))),
+        };
+    }
+
+    if !uri.starts_with("https://") && !uri.starts_with("http://") {
+        return Err(MetadataError::UnsupportedUri(uri.to_string()));
+    }
+
+    match gateways::fetch(client, uri, max_bytes).await {
+        Ok(document) => parse(&document),
+        Err(e) => Err(fetch_failed(uri, e.to_string())),
+    }
+}
+
+/// Substitutes the ERC-1155 `{id}` placeholder, which some ERC-721 collections also use.
+fn expand_id(uri: &str, token_id: U256) -> String {
+    uri.replace("{id}", &format!("{:0>64}", format!("{:x}", token_id)))
+}
+
+fn fetch_failed(uri: &str, reason: String) -> MetadataError {
+    MetadataError::Unreachable {
+        uri: uri.to_string(),
+        reason,
+    }
+}
+
+/// Decodes the part of a data URI after `data:`, base64 or percent-encoded.
+fn decode_data_uri(data: &str) -> Result<Vec<u8>, MetadataError> {
+    let (media_type, payload) = data.split_once(',').ok_or(MetadataError::InvalidDataUri)?;
+
+    if media_type.ends_with(";base64") {
+        return base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .map_err(|_| MetadataError::InvalidDataUri);
+    }
+
+    percent_decode(payload)
+}
+
+fn percent_decode(payload: &str) -> Result<Vec<u8>, MetadataError> {
+    let bytes = payload.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            decoded.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+
+        let hex = bytes.get(i + 1..i + 3).filter(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+        let hex = hex.ok_or(MetadataError::InvalidDataUri)?;
+        decoded.push(u8::from_str_radix(std::str::from_utf8(hex).unwrap(), 16).unwrap());
+        i += 3;
+    }
+
+    Ok(decoded)
+}
+
+fn normalize(document: &[u8], gateway: Option<&str>) -> Result<TokenMetadata, MetadataError> {
+    let value: Value = serde_json::from_slice(document)?;
+    let text = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+
+    let image = text("image").or_else(|| text("image_url")).map(|image| resolve_image(image, gateway));
+
+    let attributes = match value.get("attributes").or_else(|| value.get("traits")) {
+        Some(Value::Array(items)) => items.iter().filter_map(attribute).collect(),
+        // some collections publish traits as a plain `{"Background": "Blue"}` map
+        Some(Value::Object(map)) => map
+            .iter()
+            .map(|(trait_type, value)| Attribute {
+                trait_type: trait_type.clone(),
+                value: value.clone(),
+                display_type: None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    Ok(TokenMetadata {
+        name: text("name"),
+        description: text("description"),
+        image,
+        attributes,
+        content_hash: H256(looksrare_core::keccak256(document)),
+    })
+}
+
+fn resolve_image(image: String, gateway: Option<&str>) -> String {
+    if let (Some(path), Some(gateway)) = (ipfs_path(&image), gateway) {
+        return format!("{}{}", gateway, path);
+    }
+
+    image
+}
+
+fn attribute(item: &Value) -> Option<Attribute> {
+    let value = item.get("value")?.clone();
+    let trait_type = item
+        .get("trait_type")
+        .or_else(|| item.get("traitType"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    Some(Attribute {
+        trait_type,
+        value,
+        display_type: item.get("display_type").and_then(Value::as_str).map(str::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const GATEWAY: Option<&str> = Some("https://a.example/ipfs/");
+
+    #[test]
+    fn resolves_ipfs_paths_and_ids() {
+        assert_eq!(ipfs_path("ipfs://ipfs/QmHash/1.json"), Some("QmHash/1.json"));
+        assert_eq!(ipfs_path("ipfs://QmHash"), Some("QmHash"));
+        assert_eq!(ipfs_path("https://x.example/1"), None);
+
+        let fetcher = MetadataFetcher::new();
+        let res = futures::executor::block_on(fetcher.fetch_uri("ar://tx"));
+        assert!(matches!(res, Err(MetadataError::UnsupportedUri(_))));
+        assert_eq!(expand_id("https://x.example/{id}.json", U256::from(255)), format!("https://x.example/{:0>64}.json", "ff"));
+    }
+
+    #[test]
+    fn decodes_data_uris() {
+        assert_eq!(decode_data_uri("application/json;base64,eyJhIjoxfQ==").unwrap(), br#"{"a":1}"#.to_vec());
+        assert_eq!(decode_data_uri(r#"application/json;utf8,{"a":1}"#).unwrap(), br#"{"a":1}"#.to_vec());
+        assert_eq!(decode_data_uri("application/json,%7B%22a%22%3A%201%7D").unwrap(), br#"{"a": 1}"#.to_vec());
+        assert!(decode_data_uri("application/json").is_err());
+        assert!(decode_data_uri("application/json,100%").is_err());
+    }
+
+    #[test]
+    fn normalizes_common_shapes() {
+        let document = serde_json::to_vec(&json!({
+            "name": "Token #1",
+            "image": "ipfs://QmImage",
+            "attributes": [
+                {"trait_type": "Background", "value": "Blue"},
+                {"trait_type": "Level", "value": 3, "display_type": "number"},
+                {"value": "Untyped"}
+            ]
+        }))
+        .unwrap();
+        let metadata = normalize(&document, GATEWAY).unwrap();
+
+        assert_eq!(metadata.name.as_deref(), Some("Token #1"));
+        assert_eq!(metadata.image.as_deref(), Some("https://a.example/ipfs/QmImage"));
+        assert_eq!(metadata.attributes.len(), 3);
+        assert_eq!(metadata.attributes[1].display_type.as_deref(), Some("number"));
+        assert_eq!(metadata.content_hash, H256(looksrare_core::keccak256(&document)));
+
+        let document = br#"{"image_url":"https://x.example/1.png","traits":{"Eyes":"Red"}}"#;
+        let metadata = normalize(document, GATEWAY).unwrap();
+        assert_eq!(metadata.image.as_deref(), Some("https://x.example/1.png"));
+        assert_eq!(metadata.attributes[0].trait_type, "Eyes");
+    }
+
+    #[test]
+    fn serves_cached_documents() {
+        let dir = std::env::temp_dir().join(format!("looksrare-metadata-{}", std::process::id()));
+        let fetcher = MetadataFetcher::new().gateways(vec![]).cache_dir(&dir);
+        fetcher.store("ipfs://QmCached", br#"{"name":"Cached"}"#).unwrap();
+
+        let metadata = futures::executor::block_on(fetcher.fetch_uri("ipfs://QmCached")).unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("Cached"));
+
+        let uri = "data:application/json,not json";
+        assert!(futures::executor::block_on(fetcher.fetch_uri(uri)).is_err());
+        assert_eq!(fetcher.cached(uri).unwrap(), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,751 @@
+use crate::amount::{Amount, ETHER_DECIMALS};
+use crate::api::LooksRareApi;
+use crate::book;
+use crate::contracts::{ExchangeMakerOrder, ExecutionStrategy, LooksRareExchange, TakerOrder};
+use crate::profile::CollectionProfile;
+use crate::secret::Redacted;
+use crate::signer;
+use crate::tick;
+use crate::types::{Currency, Network, Order, Strategy};
+use crate::watch::unix_now;
+use crate::ClientError;
+use ethers::abi::{InvalidOutputType, Token, Tokenizable};
+use ethers::prelude::{Address, Bytes, ContractCall, Middleware, Signature, H256, U256};
+use ethers::utils::hex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long orders stay valid unless a template or profile says otherwise.
+pub const DEFAULT_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The share of the price, in basis points, a seller accepts to receive at minimum
+/// after protocol and royalty fees unless a template says otherwise.
+pub const DEFAULT_MIN_PERCENTAGE_TO_ASK: u64 = 8500;
+
+/// A maker order in the shape the exchange hashes and verifies it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MakerOrder {
+    pub is_order_ask: bool,
+    pub signer: Address,
+    pub collection: Address,
+    pub price: U256,
+    pub token_id: U256,
+    pub amount: U256,
+    pub strategy: Address,
+    pub currency: Address,
+    pub nonce: U256,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub min_percentage_to_ask: u64,
+    pub params: Bytes,
+}
+
+impl MakerOrder {
+    /// The order's EIP-712 struct hash, the same one the exchange computes.
+    pub fn hash(&self) -> H256 {
+        H256(self.to_core().struct_hash())
+    }
+
+    /// The order as compact JSON with keys sorted, addresses and params as
+    /// lowercase `0x` hex and integers as decimal strings.
+    ///
+    /// The same order always gives the same bytes, unlike its `Serialize`
+    /// output, which follows the struct's field order and ethers' formatting.
+    pub fn canonical_json(&self) -> String {
+        let fields: BTreeMap<&str, Value> = BTreeMap::from([
+            ("amount", Value::from(self.amount.to_string())),
+            ("collection", Value::from(format!("{:?}", self.collection))),
+            ("currency", Value::from(format!("{:?}", self.currency))),
+            ("endTime", Value::from(self.end_time.to_string())),
+            ("isOrderAsk", Value::from(self.is_order_ask)),
+            ("minPercentageToAsk", Value::from(self.min_percentage_to_ask.to_string())),
+            ("nonce", Value::from(self.nonce.to_string())),
+            ("params", Value::from(format!("0x{}", hex::encode(&self.params)))),
+            ("price", Value::from(self.price.to_string())),
+            ("signer", Value::from(format!("{:?}", self.signer))),
+            ("startTime", Value::from(self.start_time.to_string())),
+            ("strategy", Value::from(format!("{:?}", self.strategy))),
+            ("tokenId", Value::from(self.token_id.to_string())),
+        ]);
+
+        serde_json::to_string(&fields).expect("string keys always serialize")
+    }
+
+    /// Keccak-256 of `canonical_json`, for spotting changed or duplicate orders.
+    pub fn content_hash(&self) -> H256 {
+        H256(looksrare_core::keccak256(self.canonical_json().as_bytes()))
+    }
+
+    pub(crate) fn to_core(&self) -> looksrare_core::MakerOrder {
+        looksrare_core::MakerOrder {
+            is_order_ask: self.is_order_ask,
+            signer: self.signer.0,
+            collection: self.collection.0,
+            price: word(self.price),
+            token_id: word(self.token_id),
+            amount: word(self.amount),
+            strategy: self.strategy.0,
+            currency: self.currency.0,
+            nonce: word(self.nonce),
+            start_time: self.start_time,
+            end_time: self.end_time,
+            min_percentage_to_ask: self.min_percentage_to_ask,
+            params: self.params.to_vec(),
+        }
+    }
+}
+
+fn word(value: U256) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    value.to_big_endian(&mut word);
+    word
+}
+
+/// A maker order with the signer's signature over it.
+///
+/// `Debug` redacts the signature; see `secret::reveal_secrets`.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedOrder {
+    pub order: MakerOrder,
+    pub signature: Signature,
+}
+
+impl fmt::Debug for SignedOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignedOrder")
+            .field("order", &self.order)
+            .field("signature", &Redacted(&self.signature))
+            .finish()
+    }
+}
+
+impl SignedOrder {
+    /// The order and signature in the tuple the exchange's match functions take.
+    pub fn to_exchange(&self) -> ExchangeMakerOrder {
+        let order = &self.order;
+        ExchangeMakerOrder {
+            is_order_ask: order.is_order_ask,
+            signer: order.signer,
+            collection: order.collection,
+            price: order.price,
+            token_id: order.token_id,
+            amount: order.amount,
+            strategy: order.strategy,
+            currency: order.currency,
+            nonce: order.nonce,
+            start_time: order.start_time.into(),
+            end_time: order.end_time.into(),
+            min_percentage_to_ask: order.min_percentage_to_ask.into(),
+            params: order.params.clone(),
+            v: self.signature.v as u8,
+            r: word(self.signature.r),
+            s: word(self.signature.s),
+        }
+    }
+
+    /// The taker side filling this order as is: the opposite side, at the
+    /// maker's price and token, accepting the maker's minimum percentage.
+    pub fn taker_order(&self, taker: Address) -> TakerOrder {
+        TakerOrder {
+            is_order_ask: !self.order.is_order_ask,
+            taker,
+            price: self.order.price,
+            token_id: self.order.token_id,
+            min_percentage_to_ask: self.order.min_percentage_to_ask.into(),
+            params: Bytes::default(),
+        }
+    }
+
+    /// The exchange call filling this order from `taker`, who must be the
+    /// sender of the transaction.
+    ///
+    /// Listings in WETH are bought with `matchAskWithTakerBidUsingETHAndWETH`
+    /// and the price attached as ETH; other listings with `matchAskWithTakerBid`.
+    /// Offers are accepted with `matchBidWithTakerAsk`, which needs the collection
+    /// approved for the strategy's transfer manager.
+    pub fn fill<M: Middleware>(&self, network: &Network, provider: Arc<M>, taker: Address) -> ContractCall<M, ()> {
+        let exchange = LooksRareExchange::new(network.exchange(), provider);
+        let taker_order = self.taker_order(taker);
+        let maker_order = self.to_exchange();
+
+        let call = if !self.order.is_order_ask {
+            exchange.match_bid_with_taker_ask(taker_order, maker_order)
+        } else if self.order.currency == network.weth() {
+            exchange
+                .match_ask_with_taker_bid_using_eth_and_weth(taker_order, maker_order)
+                .value(self.order.price)
+        } else {
+            exchange.match_ask_with_taker_bid(taker_order, maker_order)
+        };
+
+        call.from(taker)
+    }
+}
+
+/// The first of `asks` that can be filled at `now`. Pass them sorted cheapest first.
+pub(crate) fn fillable_ask(network: &Network, asks: &[Order], now: u64) -> Option<SignedOrder> {
+    asks.iter().find_map(|order| fillable(network, order, now))
+}
+
+/// The signed order behind `order` if it is a signed fixed-price listing that
+/// `book::is_executable` at `now`.
+///
+/// Other strategies are skipped: their price isn't what the taker pays, e.g. a
+/// dutch auction's price falls over time, and a private sale only fills for its buyer.
+pub(crate) fn fillable(network: &Network, order: &Order, now: u64) -> Option<SignedOrder> {
+    let standard = order.strategy == network.strategy(Strategy::StandardSale);
+    (standard && book::is_executable(order, now)).then(|| SignedOrder::try_from(order).ok()).flatten()
+}
+
+impl TryFrom<&Order> for MakerOrder {
+    type Error = ClientError;
+
+    /// The maker order behind an order returned by the API, without its signature.
+    fn try_from(order: &Order) -> Result<Self, Self::Error> {
+        let number = |value: &str, field| U256::from_dec_str(value).map_err(|_| ClientError::IncompleteOrder(field));
+        let params = match order.params.as_str() {
+            "" | "0x" => Bytes::default(),
+            params => params.parse().map_err(|_| ClientError::IncompleteOrder("params"))?,
+        };
+
+        Ok(MakerOrder {
+            is_order_ask: order.is_order_ask,
+            signer: order.signer,
+            collection: order.collection_address,
+            price: number(&order.price, "price")?,
+            token_id: number(&order.token_id, "token id")?,
+            amount: number(&order.amount, "amount")?,
+            strategy: order.strategy,
+            currency: order.currency_address,
+            nonce: number(&order.nonce, "nonce")?,
+            start_time: order.start_time,
+            end_time: order.end_time,
+            min_percentage_to_ask: order.min_percentage_to_ask,
+            params,
+        })
+    }
+}
+
+impl TryFrom<&Order> for SignedOrder {
+    type Error = ClientError;
+
+    /// The signed maker order behind an order returned by the API, ready to fill on chain.
+    fn try_from(order: &Order) -> Result<Self, Self::Error> {
+        let signature = match (&order.signature, order.v, order.r, order.s) {
+            (Some(signature), ..) => signature.parse().map_err(|_| ClientError::IncompleteOrder("signature"))?,
+            (None, Some(v), Some(r), Some(s)) => Signature {
+                r: U256::from_big_endian(r.as_bytes()),
+                s: U256::from_big_endian(s.as_bytes()),
+                v: v.into(),
+            },
+            _ => return Err(ClientError::IncompleteOrder("signature")),
+        };
+
+        Ok(SignedOrder {
+            order: MakerOrder::try_from(order)?,
+            signature,
+        })
+    }
+}
+
+impl Order {
+    /// The EIP-712 struct hash of the order's contents, computed locally.
+    pub fn computed_hash(&self) -> Result<H256, ClientError> {
+        Ok(MakerOrder::try_from(self)?.hash())
+    }
+
+    /// Checks that the `hash` the API returned matches the order's contents,
+    /// returning it if so.
+    pub fn verify_hash(&self) -> Result<H256, ClientError> {
+        let computed = self.computed_hash()?;
+        match self.hash.parse::<H256>() {
+            Ok(claimed) if claimed == computed => Ok(computed),
+            _ => Err(ClientError::HashMismatch {
+                claimed: self.hash.clone(),
+                computed,
+            }),
+        }
+    }
+
+    /// Checks that the order's signature recovers to its `signer` over the
+    /// digest of its contents on `network`, returning the signed order if so.
+    pub fn verify_signature(&self, network: &Network) -> Result<SignedOrder, ClientError> {
+        let signed = SignedOrder::try_from(self)?;
+        signer::verified(network, signed.order, signed.signature)
+    }
+}
+
+/// Encodes as the exchange's `MakerOrder` tuple, signature included, so signed
+/// orders can be passed to and decoded from contract calls directly.
+impl Tokenizable for SignedOrder {
+    fn from_token(token: Token) -> Result<Self, InvalidOutputType> {
+        let maker = ExchangeMakerOrder::from_token(token)?;
+        let seconds = |value: U256, field: &str| {
+            u64::try_from(value).map_err(|_| InvalidOutputType(format!("{} does not fit in u64", field)))
+        };
+
+        Ok(SignedOrder {
+            order: MakerOrder {
+                is_order_ask: maker.is_order_ask,
+                signer: maker.signer,
+                collection: maker.collection,
+                price: maker.price,
+                token_id: maker.token_id,
+                amount: maker.amount,
+                strategy: maker.strategy,
+                currency: maker.currency,
+                nonce: maker.nonce,
+                start_time: seconds(maker.start_time, "startTime")?,
+                end_time: seconds(maker.end_time, "endTime")?,
+                min_percentage_to_ask: seconds(maker.min_percentage_to_ask, "minPercentageToAsk")?,
+                params: maker.params,
+            },
+            signature: Signature {
+                r: U256::from_big_endian(&maker.r),
+                s: U256::from_big_endian(&maker.s),
+                v: maker.v.into(),
+            },
+        })
+    }
+
+    fn into_token(self) -> Token {
+        self.to_exchange().into_token()
+    }
+}
+
+/// A vetted definition of the orders a signer places on a collection.
+///
+/// Everything but the token, price and nonce is fixed by the template, so
+/// every order built from it is consistent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderTemplate {
+    pub is_order_ask: bool,
+    pub signer: Address,
+    pub collection: Address,
+    pub strategy: Address,
+    pub currency: Address,
+    pub duration: Duration,
+    pub min_percentage_to_ask: u64,
+    pub params: Bytes,
+    /// Prices are rounded to this tick, asks and bids both rounding down.
+    pub tick: Option<U256>,
+}
+
+impl OrderTemplate {
+    /// A fixed price listing in WETH.
+    pub fn ask(network: &Network, signer: Address, collection: Address) -> Self {
+        Self::new(network, true, signer, collection, Strategy::StandardSale)
+    }
+
+    /// An offer on a specific token in WETH.
+    pub fn bid(network: &Network, signer: Address, collection: Address) -> Self {
+        Self::new(network, false, signer, collection, Strategy::StandardSale)
+    }
+
+    /// An offer any token in the collection can fill, in WETH.
+    pub fn collection_bid(network: &Network, signer: Address, collection: Address) -> Self {
+        Self::new(network, false, signer, collection, Strategy::CollectionOffer)
+    }
+
+    fn new(network: &Network, is_order_ask: bool, signer: Address, collection: Address, strategy: Strategy) -> Self {
+        Self {
+            is_order_ask,
+            signer,
+            collection,
+            strategy: network.strategy(strategy),
+            currency: network.weth(),
+            duration: DEFAULT_DURATION,
+            min_percentage_to_ask: DEFAULT_MIN_PERCENTAGE_TO_ASK,
+            params: Bytes::default(),
+            tick: None,
+        }
+    }
+
+    pub fn currency(mut self, currency: Address) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn min_percentage_to_ask(mut self, bps: u64) -> Self {
+        self.min_percentage_to_ask = bps;
+        self
+    }
+
+    /// Sets the minimum the seller receives to the price less the given protocol and royalty fees.
+    pub fn max_fees(mut self, protocol_fee_bps: u64, royalty_fee_bps: u64) -> Self {
+        self.min_percentage_to_ask = 10_000u64.saturating_sub(protocol_fee_bps + royalty_fee_bps);
+        self
+    }
+
+    pub fn params(mut self, params: Bytes) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn tick(mut self, tick: U256) -> Self {
+        self.tick = Some(tick);
+        self
+    }
+
+    /// Applies the collection's listing duration and tick size.
+    pub fn with_profile(mut self, profile: &CollectionProfile) -> Self {
+        if let Some(duration) = profile.listing_duration {
+            self.duration = duration;
+        }
+        self.tick = Some(profile.tick());
+        self
+    }
+
+    /// Builds an order for one token, valid for the template's duration from `start_time`.
+    pub fn instantiate(&self, token_id: U256, price: U256, nonce: U256, start_time: u64) -> MakerOrder {
+        let price = match self.tick {
+            Some(tick) if self.is_order_ask => tick::round_ask(price, tick),
+            Some(tick) => tick::round_bid(price, tick),
+            None => price,
+        };
+
+        MakerOrder {
+            is_order_ask: self.is_order_ask,
+            signer: self.signer,
+            collection: self.collection,
+            price,
+            token_id,
+            amount: U256::one(),
+            strategy: self.strategy,
+            currency: self.currency,
+            nonce,
+            start_time,
+            end_time: start_time + self.duration.as_secs(),
+            min_percentage_to_ask: self.min_percentage_to_ask,
+            params: self.params.clone(),
+        }
+    }
+}
+
+/// Builds a single ready-to-sign order step by step, filling in the rest.
+///
+/// Unless set, the nonce is fetched from the API, the strategy's protocol fee
+/// from its contract, the order starts now and lasts `DEFAULT_DURATION`, and
+/// the seller's minimum is the price less the protocol fee and any royalty
+/// given with `royalty_fee_bps`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderBuilder {
+    network: Network,
+    signer: Address,
+    is_order_ask: bool,
+    collection: Option<Address>,
+    token_id: Option<U256>,
+    price: Option<U256>,
+    strategy: Strategy,
+    currency: Currency,
+    duration: Duration,
+    royalty_fee_bps: u64,
+    protocol_fee_bps: Option<u64>,
+    nonce: Option<U256>,
+    start_time: Option<u64>,
+}
+
+impl OrderBuilder {
+    /// An ask from `signer` with the standard sale strategy.
+    pub fn new(network: &Network, signer: Address) -> Self {
+        Self {
+            network: *network,
+            signer,
+            is_order_ask: true,
+            collection: None,
+            token_id: None,
+            price: None,
+            strategy: Strategy::StandardSale,
+            currency: Currency::Weth,
+            duration: DEFAULT_DURATION,
+            royalty_fee_bps: 0,
+            protocol_fee_bps: None,
+            nonce: None,
+            start_time: None,
+        }
+    }
+
+    pub fn ask(mut self) -> Self {
+        self.is_order_ask = true;
+        self
+    }
+
+    pub fn bid(mut self) -> Self {
+        self.is_order_ask = false;
+        self
+    }
+
+    pub fn collection(mut self, collection: Address) -> Self {
+        self.collection = Some(collection);
+        self
+    }
+
+    pub fn token_id(mut self, token_id: impl Into<U256>) -> Self {
+        self.token_id = Some(token_id.into());
+        self
+    }
+
+    /// The price in wei of the order's currency.
+    pub fn price(mut self, price: U256) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// The price as a decimal amount of an 18-decimal currency, e.g. `"1.25"`.
+    pub fn price_eth(mut self, price: &str) -> Self {
+        self.price = Amount::parse(price, ETHER_DECIMALS).map(|amount| amount.value);
+        self
+    }
+
+    pub fn valid_for(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Defaults to WETH.
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn royalty_fee_bps(mut self, bps: u64) -> Self {
+        self.royalty_fee_bps = bps;
+        self
+    }
+
+    /// The strategy's protocol fee, as its `viewProtocolFee` returns it.
+    pub fn protocol_fee_bps(mut self, bps: u64) -> Self {
+        self.protocol_fee_bps = Some(bps);
+        self
+    }
+
+    pub fn nonce(mut self, nonce: U256) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    pub fn start_time(mut self, start_time: u64) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Builds the order, fetching the signer's next nonce from the API and the
+    /// strategy's protocol fee through `provider` if they weren't set.
+    pub async fn build<M: Middleware>(self, api: &LooksRareApi, provider: Arc<M>) -> Result<MakerOrder, ClientError> {
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => api.get_order_nonce(self.signer).await?,
+        };
+        let protocol_fee_bps = match self.protocol_fee_bps {
+            Some(bps) => bps,
+            None => ExecutionStrategy::new(self.network.strategy(self.strategy), provider)
+                .view_protocol_fee()
+                .call()
+                .await?
+                .low_u64(),
+        };
+
+        self.nonce(nonce).protocol_fee_bps(protocol_fee_bps).build_offline()
+    }
+
+    /// Builds the order without the API or chain; the nonce and protocol fee
+    /// must have been set.
+    pub fn build_offline(self) -> Result<MakerOrder, ClientError> {
+        let collection = self.collection.ok_or(ClientError::IncompleteOrder("collection"))?;
+        let price = self.price.ok_or(ClientError::IncompleteOrder("price"))?;
+        let nonce = self.nonce.ok_or(ClientError::IncompleteOrder("nonce"))?;
+        let protocol_fee_bps = self.protocol_fee_bps.ok_or(ClientError::IncompleteOrder("protocol fee"))?;
+        let token_id = match (self.token_id, self.strategy) {
+            (Some(token_id), _) => token_id,
+            (None, Strategy::CollectionOffer) => U256::zero(),
+            (None, _) => return Err(ClientError::IncompleteOrder("token id")),
+        };
+
+        let template = OrderTemplate::new(&self.network, self.is_order_ask, self.signer, collection, self.strategy)
+            .currency(self.network.currency(self.currency))
+            .duration(self.duration)
+            .max_fees(protocol_fee_bps, self.royalty_fee_bps);
+
+        Ok(template.instantiate(token_id, price, nonce, self.start_time.unwrap_or_else(unix_now)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instantiates_consistent_orders() {
+        let network = Network::Mainnet;
+        let profile = CollectionProfile::new().listing_duration(Duration::from_secs(3600));
+        let template = OrderTemplate::ask(&network, Address::from_low_u64_be(1), Address::from_low_u64_be(2))
+            .max_fees(200, 500)
+            .with_profile(&profile);
+
+        let price = U256::from(tick::DEFAULT_TICK) * 1500 + 42;
+        let order = template.instantiate(U256::from(7), price, U256::from(3), 1_000);
+
+        assert!(order.is_order_ask);
+        assert_eq!(order.strategy, network.strategy(Strategy::StandardSale));
+        assert_eq!(order.currency, network.weth());
+        assert_eq!(order.price, U256::from(tick::DEFAULT_TICK) * 1500);
+        assert_eq!(order.end_time, 4_600);
+        assert_eq!(order.min_percentage_to_ask, 9300);
+        assert_eq!(order.nonce, U256::from(3));
+    }
+
+    #[test]
+    fn builder_fills_defaults() {
+        let network = Network::Mainnet;
+        let builder = OrderBuilder::new(&network, Address::from_low_u64_be(1))
+            .collection(Address::from_low_u64_be(2))
+            .price_eth("1.5")
+            .valid_for(Duration::from_secs(600))
+            .royalty_fee_bps(500)
+            .start_time(1_000);
+
+        assert!(matches!(
+            builder.clone().token_id(7u64).nonce(U256::zero()).build_offline(),
+            Err(ClientError::IncompleteOrder("protocol fee"))
+        ));
+        let builder = builder.protocol_fee_bps(150);
+
+        assert!(matches!(
+            builder.clone().token_id(7u64).build_offline(),
+            Err(ClientError::IncompleteOrder("nonce"))
+        ));
+        assert!(matches!(
+            builder.clone().nonce(U256::zero()).build_offline(),
+            Err(ClientError::IncompleteOrder("token id"))
+        ));
+
+        let looks = builder.clone().currency(Currency::Looks).token_id(7u64).nonce(U256::from(4));
+        assert_eq!(looks.build_offline().unwrap().currency, network.looks());
+
+        let order = builder.token_id(7u64).nonce(U256::from(4)).build_offline().unwrap();
+        assert_eq!(order.price, U256::from(15) * U256::exp10(17));
+        assert_eq!(order.currency, network.weth());
+        assert_eq!(order.strategy, network.strategy(Strategy::StandardSale));
+        assert_eq!(order.end_time, 1_600);
+        assert_eq!(order.min_percentage_to_ask, 9350);
+    }
+
+    #[test]
+    fn canonical_json_is_sorted_and_compact() {
+        let template = OrderTemplate::ask(&Network::Mainnet, Address::from_low_u64_be(0xab), Address::from_low_u64_be(2))
+            .params(Bytes::from(vec![0xCA, 0xFE]));
+        let order = template.instantiate(U256::from(7), U256::from(tick::DEFAULT_TICK), U256::from(3), 1_000);
+
+        let json = order.canonical_json();
+        assert!(json.starts_with(r#"{"amount":"1","collection":"0x0000000000000000000000000000000000000002","#));
+        assert!(json.contains(r#""params":"0xcafe","#));
+        assert!(json.contains(r#""signer":"0x00000000000000000000000000000000000000ab","#));
+        assert!(!json.contains(' '));
+
+        let reparsed: MakerOrder = serde_json::from_str(&serde_json::to_string(&order).unwrap()).unwrap();
+        assert_eq!(reparsed.content_hash(), order.content_hash());
+    }
+
+    #[test]
+    fn converts_api_orders_for_the_exchange() {
+        let mut api_order = crate::fixtures::order();
+        assert!(matches!(
+            SignedOrder::try_from(&api_order),
+            Err(ClientError::IncompleteOrder("signature"))
+        ));
+
+        api_order.v = Some(28);
+        api_order.r = Some(H256::repeat_byte(1));
+        api_order.s = Some(H256::repeat_byte(2));
+        let signed = SignedOrder::try_from(&api_order).unwrap();
+        assert_eq!(signed.order.price, U256::exp10(18));
+        assert!(signed.order.params.is_empty());
+
+        let maker = signed.to_exchange();
+        assert_eq!((maker.v, maker.r, maker.s), (28, [1; 32], [2; 32]));
+        assert_eq!(maker.end_time, U256::from(1667754634u64));
+
+        let taker = signed.taker_order(Address::from_low_u64_be(9));
+        assert!(!taker.is_order_ask);
+        assert_eq!(taker.price, signed.order.price);
+        assert_eq!(taker.min_percentage_to_ask, U256::from(8500));
+
+        api_order.signature = Some(format!("0x{}", hex::encode(signed.signature.to_vec())));
+        assert_eq!(SignedOrder::try_from(&api_order).unwrap(), signed);
+
+        let token = signed.clone().into_token();
+        assert_eq!(token, maker.into_token());
+        assert_eq!(SignedOrder::from_token(token).unwrap(), signed);
+    }
+
+    #[test]
+    fn picks_the_first_fillable_ask() {
+        let signed = |price: &str| {
+            let mut order = crate::fixtures::order();
+            order.price = price.to_string();
+            order.v = Some(27);
+            order.r = Some(H256::repeat_byte(1));
+            order.s = Some(H256::repeat_byte(2));
+            order
+        };
+        let now = 1667750000;
+
+        let mut expired = signed("1");
+        expired.end_time = now - 1;
+        let unsigned = crate::fixtures::order();
+        let mut executed = signed("2");
+        executed.status = String::from("EXECUTED");
+        let mut auction = signed("2");
+        auction.strategy = Network::Mainnet.strategy(Strategy::DutchAuction);
+        let mut ending = signed("3");
+        ending.end_time = now;
+        let asks = vec![expired, unsigned, executed, auction, ending, signed("4")];
+
+        let ask = fillable_ask(&Network::Mainnet, &asks, now).unwrap();
+        assert_eq!(ask.order.price, U256::from(3));
+        assert!(fillable_ask(&Network::Mainnet, &asks[..4], now).is_none());
+    }
+
+    #[test]
+    fn verifies_api_hashes_against_contents() {
+        let mut order = crate::fixtures::order();
+        let maker = MakerOrder::try_from(&order).unwrap();
+        order.hash = format!("{:?}", maker.hash());
+
+        assert_eq!(order.verify_hash().unwrap(), maker.hash());
+
+        order.price = String::from("1");
+        assert!(matches!(order.verify_hash(), Err(ClientError::HashMismatch { .. })));
+    }
+
+    #[test]
+    fn verifies_api_signatures_against_contents() {
+        let wallet = crate::fixtures::wallet();
+        let network = Network::Mainnet;
+        let mut order = crate::fixtures::order();
+        order.signer = ethers::signers::Signer::address(&wallet);
+
+        let maker = MakerOrder::try_from(&order).unwrap();
+        let signed = futures::executor::block_on(signer::sign_maker_order(&network, maker, &wallet)).unwrap();
+        order.signature = Some(format!("0x{}", hex::encode(signed.signature.to_vec())));
+        assert_eq!(order.verify_signature(&network).unwrap(), signed);
+
+        order.price = String::from("1");
+        assert!(matches!(order.verify_signature(&network), Err(ClientError::SignerError(_))));
+    }
+}
@@ -0,0 +1,184 @@
+//! Signed orders shared directly between counterparties instead of through the API.
+
+use crate::api::LooksRareApi;
+use crate::audit::{AuditAction, AuditRecord, OrderRef};
+use crate::contracts::LooksRareExchange;
+use crate::order::SignedOrder;
+use crate::signer;
+use crate::watch::unix_now;
+use crate::ClientError;
+use ethers::prelude::{Address, Middleware, U256};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Why a shared order was refused at import.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ImportRejection {
+    #[error("Shared order is malformed: {0}")]
+    Malformed(String),
+    #[error("Shared order ended at {end_time}")]
+    Expired { end_time: u64 },
+    #[error("Nonce {nonce} of {signer:?} was already executed or cancelled")]
+    NonceUsed { signer: Address, nonce: U256 },
+    #[error("Nonce {nonce} of {signer:?} is below their minimum nonce {min_nonce}")]
+    BelowMinNonce { signer: Address, nonce: U256, min_nonce: U256 },
+    #[error("Nonce {nonce} of {signer:?} was already executed through this client")]
+    AlreadyExecuted { signer: Address, nonce: U256 },
+    #[error("Nonce {nonce} of {signer:?} was already imported")]
+    AlreadyImported { signer: Address, nonce: U256 },
+    #[error("Audit log can't be checked: {0}")]
+    AuditUnavailable(String),
+}
+
+/// Takes in a signed order shared as JSON, e.g. over OTC chat, and checks it
+/// can still be filled before anything is sent.
+///
+/// The signature must recover to the order's signer, the order must not have
+/// ended, its nonce must be neither used nor cancelled on chain, and the audit
+/// log (see `LooksRareApi::audit_log`) must have no execution or earlier
+/// import of its signer and nonce, mined or not. An audit log that can't be
+/// read or verified refuses every import. Every import, accepted or not, is
+/// audited.
+pub async fn import_shared_order<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    shared: &str,
+) -> Result<SignedOrder, ClientError> {
+    let signed: SignedOrder = match serde_json::from_str(shared) {
+        Ok(signed) => signed,
+        Err(e) => {
+            let rejection = ImportRejection::Malformed(e.to_string());
+            api.audit(AuditAction::OrderImported, vec![], &shared, Err(rejection.to_string()));
+            return Err(rejection.into());
+        }
+    };
+
+    let checked = check(api, provider, &signed).await;
+    let outcome = checked
+        .as_ref()
+        .map(|_| format!("{:?}", signed.order.hash()))
+        .map_err(|e| e.to_string());
+    api.audit(AuditAction::OrderImported, vec![OrderRef::from(&signed.order)], &signed, outcome);
+
+    checked.map(|_| signed)
+}
+
+async fn check<M: Middleware>(api: &LooksRareApi, provider: Arc<M>, signed: &SignedOrder) -> Result<(), ClientError> {
+    let order = &signed.order;
+    signer::verified(api.network(), order.clone(), signed.signature)?;
+    if order.end_time <= unix_now() {
+        return Err(ImportRejection::Expired { end_time: order.end_time }.into());
+    }
+
+    let exchange = LooksRareExchange::new(api.network().exchange(), provider);
+    let used = exchange.is_user_order_nonce_executed_or_cancelled(order.signer, order.nonce);
+    let min_nonce = exchange.user_min_order_nonce(order.signer);
+    let (used, min_nonce) = futures::try_join!(used.call(), min_nonce.call())?;
+    check_nonce(signed, used, min_nonce)?;
+
+    let records = api
+        .audit_records()
+        .map_err(|e| ImportRejection::AuditUnavailable(e.to_string()))?;
+    check_history(&records, signed)?;
+
+    Ok(())
+}
+
+fn check_nonce(signed: &SignedOrder, used: bool, min_nonce: U256) -> Result<(), ImportRejection> {
+    let (signer, nonce) = (signed.order.signer, signed.order.nonce);
+
+    if used {
+        return Err(ImportRejection::NonceUsed { signer, nonce });
+    }
+    if nonce < min_nonce {
+        return Err(ImportRejection::BelowMinNonce { signer, nonce, min_nonce });
+    }
+
+    Ok(())
+}
+
+/// Refuses orders whose signer and nonce the audit log shows were already
+/// filled, even if the fill isn't mined yet, or already imported.
+///
+/// Matching on the nonce rather than the order's encoding also catches the
+/// same order with its signature encoded differently, or re-signed.
+fn check_history(records: &[AuditRecord], signed: &SignedOrder) -> Result<(), ImportRejection> {
+    let key = OrderRef::from(&signed.order);
+    let (signer, nonce) = (key.signer, key.nonce);
+
+    let earlier = records
+        .iter()
+        .filter(|record| record.ok && record.orders.contains(&key))
+        .find_map(|record| match record.action {
+            AuditAction::OrderExecuted => Some(ImportRejection::AlreadyExecuted { signer, nonce }),
+            AuditAction::OrderImported => Some(ImportRejection::AlreadyImported { signer, nonce }),
+            _ => None,
+        });
+
+    match earlier {
+        Some(rejection) => Err(rejection),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditLog;
+    use ethers::prelude::H256;
+
+    fn signed() -> SignedOrder {
+        let mut order = crate::fixtures::order();
+        order.v = Some(27);
+        order.r = Some(H256::repeat_byte(1));
+        order.s = Some(H256::repeat_byte(2));
+        SignedOrder::try_from(&order).unwrap()
+    }
+
+    #[test]
+    fn refuses_used_and_cancelled_nonces() {
+        let signed = signed();
+
+        assert_eq!(check_nonce(&signed, false, U256::zero()), Ok(()));
+        assert!(matches!(
+            check_nonce(&signed, true, U256::zero()),
+            Err(ImportRejection::NonceUsed { .. })
+        ));
+        assert!(matches!(
+            check_nonce(&signed, false, U256::one()),
+            Err(ImportRejection::BelowMinNonce { .. })
+        ));
+    }
+
+    #[test]
+    fn refuses_nonces_executed_or_imported_locally() {
+        let path = std::env::temp_dir().join(format!("looksrare-otc-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let log = AuditLog::open(&path).unwrap();
+        let signed = signed();
+        let key = vec![OrderRef::from(&signed.order)];
+        let (signer, nonce) = (signed.order.signer, signed.order.nonce);
+
+        log.append(AuditAction::OrderExecuted, key.clone(), &signed, false, None).unwrap();
+        assert_eq!(check_history(&log.records().unwrap(), &signed), Ok(()));
+
+        // the same nonce under another signature encoding
+        let mut reencoded = signed.clone();
+        reencoded.signature.v -= 27;
+        log.append(AuditAction::OrderExecuted, key.clone(), &signed, true, None).unwrap();
+        assert_eq!(
+            check_history(&log.records().unwrap(), &reencoded),
+            Err(ImportRejection::AlreadyExecuted { signer, nonce })
+        );
+
+        let _ = std::fs::remove_file(&path);
+        let log = AuditLog::open(&path).unwrap();
+        log.append(AuditAction::OrderImported, key, &signed, true, None).unwrap();
+        assert_eq!(
+            check_history(&log.records().unwrap(), &signed),
+            Err(ImportRejection::AlreadyImported { signer, nonce })
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::api::{EventType, EventsRequest, LooksRareApi, Pagination};
+use crate::constants;
+use crate::contracts::ERC721;
+use crate::ClientError;
+use ethers::prelude::{Address, ContractCall, Middleware, ValueOrArray, H256, U256};
+use futures::{stream, StreamExt, TryStreamExt};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+const CONCURRENT_CALLS: usize = 32;
+
+/// Lists every token id in a collection.
+///
+/// Uses ERC721Enumerable when the collection supports it and falls back to
+/// replaying mint `Transfer` logs otherwise. See `get_collection_tokens_from_api`
+/// to page through the API instead, e.g. without an archive provider.
+pub async fn get_collection_tokens<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+) -> Result<Vec<U256>, ClientError> {
+    collection_tokens(provider, collection, None).await
+}
+
+/// Like `get_collection_tokens`, as of `block`. Needs an archive provider for older blocks.
+pub async fn get_collection_tokens_at<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+    block: u64,
+) -> Result<Vec<U256>, ClientError> {
+    collection_tokens(provider, collection, Some(block)).await
+}
+
+/// Lists every token id the API has seen minted in a collection, a page of mint
+/// events at a time. Tokens minted before LooksRare indexed the collection are missed.
+pub async fn get_collection_tokens_from_api(api: &LooksRareApi, collection: Address) -> Result<Vec<U256>, ClientError> {
+    let mut tokens = BTreeSet::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let req = EventsRequest {
+            collection: Some(collection),
+            event_type: Some(EventType::Mint),
+            pagination: Some(Pagination {
+                first: Some(constants::MAX_ORDERS_PER_PAGE),
+                cursor: cursor.take(),
+            }),
+            ..Default::default()
+        };
+
+        let page = api.get_events(req).await?;
+        let page_len = page.len() as u64;
+        cursor = page.last().map(|event| event.id.to_string());
+
+        for token in page.iter().filter_map(|event| event.token.as_ref()) {
+            let token_id = U256::from_dec_str(&token.token_id).map_err(|_| ClientError::IncompleteOrder("token id"))?;
+            tokens.insert(token_id);
+        }
+
+        if page_len < constants::MAX_ORDERS_PER_PAGE {
+            break;
+        }
+    }
+
+    Ok(tokens.into_iter().collect())
+}
+
+/// Maps every current owner in a collection to the token ids they hold.
+///
+/// Tokens whose `ownerOf` reverts, e.g. burned ones, are left out.
+pub async fn ownership_snapshot<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+) -> Result<HashMap<Address, Vec<U256>>, ClientError> {
+    snapshot(provider, collection, None).await
+}
+
+/// Like `ownership_snapshot`, as of `block`, e.g. for an airdrop snapshot block.
+/// Needs an archive provider for older blocks.
+pub async fn ownership_snapshot_at<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+    block: u64,
+) -> Result<HashMap<Address, Vec<U256>>, ClientError> {
+    snapshot(provider, collection, Some(block)).await
+}
+
+/// Like `ownership_snapshot`, with the token ids listed by `get_collection_tokens_from_api`.
+pub async fn ownership_snapshot_from_api<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    collection: Address,
+) -> Result<HashMap<Address, Vec<U256>>, ClientError> {
+    let tokens = get_collection_tokens_from_api(api, collection).await?;
+
+    owners(provider, collection, tokens, None).await
+}
+
+/// Whether `owner` held `token_id` as of `block`, or currently when `block` is `None`.
+pub async fn owns<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+    token_id: U256,
+    owner: Address,
+    block: Option<u64>,
+) -> Result<bool, ClientError> {
+    let contract = ERC721::new(collection, provider);
+    let holder = at(contract.owner_of(token_id), block).call().await?;
+
+    Ok(holder == owner)
+}
+
+async fn collection_tokens<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+    block: Option<u64>,
+) -> Result<Vec<U256>, ClientError> {
+    let contract = ERC721::new(collection, provider);
+
+    match enumerate_tokens(&contract, block).await {
+        Ok(tokens) => Ok(tokens),
+        Err(_) => minted_tokens(&contract, block).await,
+    }
+}
+
+async fn snapshot<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+    block: Option<u64>,
+) -> Result<HashMap<Address, Vec<U256>>, ClientError> {
+    let tokens = collection_tokens(provider.clone(), collection, block).await?;
+
+    owners(provider, collection, tokens, block).await
+}
+
+/// Maps the owners of `tokens` to the ones they hold, skipping tokens without one.
+async fn owners<M: Middleware>(
+    provider: Arc<M>,
+    collection: Address,
+    tokens: Vec<U256>,
+    block: Option<u64>,
+) -> Result<HashMap<Address, Vec<U256>>, ClientError> {
+    let contract = ERC721::new(collection, provider);
+
+    let owners: Vec<Option<(U256, Address)>> = stream::iter(tokens)
+        .map(|token_id| {
+            let call = at(contract.owner_of(token_id), block);
+            async move {
+                match call.call().await {
+                    Ok(owner) => Ok(Some((token_id, owner))),
+                    Err(e) if e.is_revert() => {
+                        log::debug!("skipping token {} of {:?} without an owner: {}", token_id, collection, e);
+                        Ok(None)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        })
+        .buffered(CONCURRENT_CALLS)
+        .try_collect()
+        .await?;
+
+    let mut snapshot: HashMap<Address, Vec<U256>> = HashMap::new();
+    for (token_id, owner) in owners.into_iter().flatten() {
+        snapshot.entry(owner).or_default().push(token_id);
+    }
+
+    Ok(snapshot)
+}
+
+async fn enumerate_tokens<M: Middleware>(contract: &ERC721<M>, block: Option<u64>) -> Result<Vec<U256>, ClientError> {
+    let total_supply = at(contract.total_supply(), block).call().await?;
+
+    let tokens: Vec<U256> = stream::iter(0..total_supply.low_u64())
+        .map(|index| {
+            let call = at(contract.token_by_index(U256::from(index)), block);
+            async move { call.call().await }
+        })
+        .buffered(CONCURRENT_CALLS)
+        .try_collect()
+        .await?;
+
+    Ok(tokens)
+}
+
+async fn minted_tokens<M: Middleware>(contract: &ERC721<M>, block: Option<u64>) -> Result<Vec<U256>, ClientError> {
+    let mut mints = contract
+        .transfer_filter()
+        .from_block(0u64)
+        .topic1(ValueOrArray::Value(Some(H256::zero())));
+    if let Some(block) = block {
+        mints = mints.to_block(block);
+    }
+    let mints = mints.query().await?;
+
+    let mut tokens: Vec<U256> = mints.into_iter().map(|mint| mint.token_id).collect();
+    tokens.sort();
+    tokens.dedup();
+
+    Ok(tokens)
+}
+
+/// Pins a call to `block` when one is given.
+fn at<M: Middleware, D>(call: ContractCall<M, D>, block: Option<u64>) -> ContractCall<M, D> {
+    match block {
+        Some(block) => call.block(block),
+        None => call,
+    }
+}
@@ -0,0 +1,128 @@
+use crate::tick;
+use ethers::prelude::{Address, U256};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A trait value worth pricing differently from the rest of a collection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraitPreference {
+    pub trait_type: String,
+    pub value: String,
+    /// Basis points added to (or, when negative, taken off) the price of matching tokens.
+    pub premium_bps: i64,
+}
+
+/// Per-collection settings for the listing and bidding helpers.
+///
+/// Unset fields fall back to the registry's default profile, then to the helper's own default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CollectionProfile {
+    /// Royalty fee in basis points to assume instead of the on-chain value.
+    pub royalty_fee_bps: Option<u64>,
+    pub listing_duration: Option<Duration>,
+    /// Price increment in wei that listing and bidding prices are aligned to.
+    pub tick_size: Option<U256>,
+    pub trait_preferences: Vec<TraitPreference>,
+}
+
+impl CollectionProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn royalty_fee_bps(mut self, bps: u64) -> Self {
+        self.royalty_fee_bps = Some(bps);
+        self
+    }
+
+    pub fn listing_duration(mut self, duration: Duration) -> Self {
+        self.listing_duration = Some(duration);
+        self
+    }
+
+    pub fn tick_size(mut self, tick: U256) -> Self {
+        self.tick_size = Some(tick);
+        self
+    }
+
+    /// The tick prices for this collection are rounded to, defaulting to 0.001 ETH.
+    pub fn tick(&self) -> U256 {
+        self.tick_size.unwrap_or_else(|| U256::from(tick::DEFAULT_TICK))
+    }
+
+    pub fn trait_preference(mut self, preference: TraitPreference) -> Self {
+        self.trait_preferences.push(preference);
+        self
+    }
+
+    /// Fills every unset field from `fallback`. Trait preferences are only
+    /// inherited when this profile has none of its own.
+    fn or(self, fallback: &CollectionProfile) -> CollectionProfile {
+        CollectionProfile {
+            royalty_fee_bps: self.royalty_fee_bps.or(fallback.royalty_fee_bps),
+            listing_duration: self.listing_duration.or(fallback.listing_duration),
+            tick_size: self.tick_size.or(fallback.tick_size),
+            trait_preferences: if self.trait_preferences.is_empty() {
+                fallback.trait_preferences.clone()
+            } else {
+                self.trait_preferences
+            },
+        }
+    }
+}
+
+/// Profiles keyed by collection, with a default applied to every collection.
+#[derive(Clone, Debug, Default)]
+pub struct ProfileRegistry {
+    default: CollectionProfile,
+    profiles: HashMap<Address, CollectionProfile>,
+}
+
+impl ProfileRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_default(mut self, profile: CollectionProfile) -> Self {
+        self.default = profile;
+        self
+    }
+
+    pub fn with_profile(mut self, collection: Address, profile: CollectionProfile) -> Self {
+        self.profiles.insert(collection, profile);
+        self
+    }
+
+    /// The effective profile for `collection`, merged over the default.
+    pub fn profile(&self, collection: Address) -> CollectionProfile {
+        match self.profiles.get(&collection) {
+            Some(profile) => profile.clone().or(&self.default),
+            None => self.default.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collection_profile_overrides_default() {
+        let collection = Address::from_low_u64_be(1);
+        let registry = ProfileRegistry::new()
+            .with_default(
+                CollectionProfile::new()
+                    .royalty_fee_bps(200)
+                    .listing_duration(Duration::from_secs(3600)),
+            )
+            .with_profile(collection, CollectionProfile::new().royalty_fee_bps(50));
+
+        let profile = registry.profile(collection);
+        assert_eq!(profile.royalty_fee_bps, Some(50));
+        assert_eq!(profile.listing_duration, Some(Duration::from_secs(3600)));
+        assert_eq!(profile.tick(), U256::from(tick::DEFAULT_TICK));
+
+        let other = registry.profile(Address::from_low_u64_be(2));
+        assert_eq!(other.royalty_fee_bps, Some(200));
+    }
+}
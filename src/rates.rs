@@ -0,0 +1,116 @@
+use crate::types::Order;
+use ethers::prelude::{Address, U256};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Fixed-point scale of every rate: a rate of `RATE_SCALE` means one-to-one.
+pub const RATE_SCALE: u64 = 1_000_000_000_000_000_000;
+
+/// Supplies exchange rates between currencies.
+pub trait RateSource: Send + Sync {
+    /// Units of `to` received for `RATE_SCALE` units of `from`, or `None` when unknown.
+    fn rate(&self, from: Address, to: Address) -> Option<U256>;
+}
+
+/// A fixed table of rates, e.g. refreshed from a price feed by the caller.
+#[derive(Clone, Debug, Default)]
+pub struct StaticRates {
+    rates: HashMap<(Address, Address), U256>,
+}
+
+impl StaticRates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rate(mut self, from: Address, to: Address, rate: U256) -> Self {
+        self.rates.insert((from, to), rate);
+        self
+    }
+}
+
+impl RateSource for StaticRates {
+    fn rate(&self, from: Address, to: Address) -> Option<U256> {
+        self.rates.get(&(from, to)).copied()
+    }
+}
+
+/// A price expressed in the converter's base currency, along with what it was converted from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConvertedPrice {
+    pub price: U256,
+    pub currency: Address,
+    pub converted: U256,
+    pub base: Address,
+    pub rate: U256,
+}
+
+/// Converts order prices into a single base currency so they can be compared.
+#[derive(Clone)]
+pub struct Converter {
+    base: Address,
+    source: Arc<dyn RateSource>,
+}
+
+impl Converter {
+    pub fn new(base: Address, source: impl RateSource + 'static) -> Self {
+        Self {
+            base,
+            source: Arc::new(source),
+        }
+    }
+
+    pub fn base(&self) -> Address {
+        self.base
+    }
+
+    pub fn convert(&self, price: U256, currency: Address) -> Option<ConvertedPrice> {
+        let rate = if currency == self.base {
+            U256::from(RATE_SCALE)
+        } else {
+            self.source.rate(currency, self.base)?
+        };
+
+        Some(ConvertedPrice {
+            price,
+            currency,
+            converted: price.checked_mul(rate)? / U256::from(RATE_SCALE),
+            base: self.base,
+            rate,
+        })
+    }
+
+    pub fn convert_order(&self, order: &Order) -> Option<ConvertedPrice> {
+        let price = U256::from_dec_str(&order.price).ok()?;
+        self.convert(price, order.currency_address)
+    }
+}
+
+impl fmt::Debug for Converter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Converter").field("base", &self.base).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_into_base_currency() {
+        let weth = Address::from_low_u64_be(1);
+        let looks = Address::from_low_u64_be(2);
+        let rates = StaticRates::new().with_rate(looks, weth, U256::from(RATE_SCALE / 1000));
+        let converter = Converter::new(weth, rates);
+
+        let converted = converter.convert(U256::from(5000), looks).unwrap();
+        assert_eq!(converted.converted, U256::from(5));
+        assert_eq!(converted.rate, U256::from(RATE_SCALE / 1000));
+
+        let same = converter.convert(U256::from(5000), weth).unwrap();
+        assert_eq!(same.converted, U256::from(5000));
+
+        assert!(converter.convert(U256::from(1), Address::from_low_u64_be(3)).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+use crate::api::{LooksRareApiError, ParseMode, Replay};
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// One API response as written by a recording client.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording {
+    pub recorded_at: DateTime<Utc>,
+    /// The path under the API base, e.g. `/orders`.
+    pub endpoint: String,
+    pub url: String,
+    pub status: u16,
+    /// The body exactly as received, so it can be fed back to the parsers.
+    pub body: String,
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Parses the recorded response as the client did, e.g.
+    /// `recording.parse::<Vec<Order>>(ParseMode::Strict)` for one from `/orders`.
+    pub fn parse<T: Replay>(&self, mode: ParseMode) -> Result<T, LooksRareApiError> {
+        T::replay(self, mode)
+    }
+
+    /// The first value of `key` in the recorded URL's query.
+    pub fn query_param(&self, key: &str) -> Option<String> {
+        let url = Url::parse(&self.url).ok()?;
+        let value = url.query_pairs().find(|(name, _)| name == key)?.1;
+        Some(value.into_owned())
+    }
+}
+
+/// Writes every response a client reads to its own file in `dir`, named
+/// `<timestamp>-<endpoint>-<params hash>-<seq>.json`.
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    dir: PathBuf,
+    seq: AtomicU64,
+}
+
+impl Recorder {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            seq: AtomicU64::new(0),
+        }
+    }
+
+    /// Records one response. Failing to write is reported but never fails the request.
+    pub(crate) fn record(&self, base: &str, url: &Url, status: u16, body: &str) -> io::Result<PathBuf> {
+        let recording = Recording {
+            recorded_at: Utc::now(),
+            endpoint: endpoint(base, url),
+            url: url.to_string(),
+            status,
+            body: body.to_string(),
+        };
+
+        let params_hash = looksrare_core::keccak256(url.query().unwrap_or_default().as_bytes());
+        let name = format!(
+            "{}-{}-{}-{}.json",
+            recording.recorded_at.format("%Y%m%dT%H%M%S%.3fZ"),
+            recording.endpoint.trim_matches('/').replace('/', "_"),
+            ethers::utils::hex::encode(&params_hash[..4]),
+            self.seq.fetch_add(1, Ordering::Relaxed),
+        );
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(name);
+        fs::write(&path, serde_json::to_string_pretty(&recording)?)?;
+
+        Ok(path)
+    }
+}
+
+fn endpoint(base: &str, url: &Url) -> String {
+    let path = url.path();
+    let base_path = Url::parse(base).map(|base| base.path().trim_end_matches('/').to_string()).unwrap_or_default();
+
+    path.strip_prefix(&base_path).unwrap_or(path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_and_loads_responses() {
+        let dir = std::env::temp_dir().join(format!("looksrare-recordings-{}", std::process::id()));
+        let recorder = Recorder::new(dir.clone());
+        let url = Url::parse("https://api.looksrare.org/api/v1/collections/stats?address=0xabc").unwrap();
+
+        let path = recorder
+            .record("https://api.looksrare.org/api/v1", &url, 200, r#"{"success":true}"#)
+            .unwrap();
+        assert!(path.file_name().unwrap().to_str().unwrap().contains("-collections_stats-"));
+
+        let recording = Recording::load(&path).unwrap();
+        assert_eq!(recording.endpoint, "/collections/stats");
+        assert_eq!(recording.status, 200);
+        assert_eq!(recording.body, r#"{"success":true}"#);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+//! Signing and sending through a wallet the caller has already connected to,
+//! so desktop tools never hold a key.
+//!
+//! This is a signer adapter, not a wallet connection: pairing, relays and
+//! session encryption belong to whatever client holds the session, e.g. a
+//! WalletConnect v2 client or an EIP-1193 provider. Implement `WalletSession`
+//! over that session and `RemoteWalletSigner` routes order signatures and fills
+//! through it as `eth_signTypedData_v4` and `eth_sendTransaction` requests.
+
+use crate::order::{MakerOrder, SignedOrder};
+use crate::signer::{self, DomainCache};
+use crate::types::Network;
+use crate::ClientError;
+use async_trait::async_trait;
+use ethers::prelude::{Address, Middleware, Signature, H256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use serde_json::{json, Value};
+use std::fmt::Debug;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// A connected wallet session with `eip155` accounts, e.g. an approved
+/// WalletConnect v2 session.
+#[async_trait]
+pub trait WalletSession: Debug + Send + Sync {
+    /// The session's accounts as CAIP-10 ids, e.g. `eip155:1:0xab…`.
+    fn accounts(&self) -> Vec<String>;
+
+    /// Sends a JSON-RPC request to the wallet for `chain_id` and waits for the user's answer.
+    async fn request(&self, chain_id: u64, method: &str, params: Value) -> Result<Value, RemoteWalletError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RemoteWalletError {
+    #[error("Session has no account on chain {chain_id}")]
+    NoAccount { chain_id: u64 },
+    #[error("Wallet rejected the request ({code}): {message}")]
+    Rejected { code: i64, message: String },
+    #[error("Wallet session failed: {0}")]
+    Transport(String),
+    #[error("Unexpected wallet response: {0}")]
+    InvalidResponse(String),
+}
+
+impl From<RemoteWalletError> for ClientError {
+    fn from(err: RemoteWalletError) -> Self {
+        ClientError::SignerError(err.to_string())
+    }
+}
+
+/// A connected wallet's account on one network, signing orders and sending fills.
+#[derive(Debug)]
+pub struct RemoteWalletSigner<S> {
+    session: S,
+    network: Network,
+    account: Address,
+    domains: DomainCache,
+}
+
+impl<S: WalletSession> RemoteWalletSigner<S> {
+    /// Uses the session's first account on `network`.
+    pub fn new(session: S, network: Network) -> Result<Self, RemoteWalletError> {
+        let chain_id = network.chain_id();
+        let account = session
+            .accounts()
+            .iter()
+            .find_map(|id| account_on(id, chain_id))
+            .ok_or(RemoteWalletError::NoAccount { chain_id })?;
+
+        Ok(Self {
+            session,
+            network,
+            account,
+            domains: DomainCache::new(),
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.account
+    }
+
+    /// Asks the wallet to sign `order` with `eth_signTypedData_v4`.
+    ///
+    /// Fails if `provider`'s exchange domain does not match this network's, or
+    /// if the signature does not recover to `order.signer`.
+    pub async fn sign_maker_order<M: Middleware>(
+        &self,
+        order: MakerOrder,
+        provider: Arc<M>,
+    ) -> Result<SignedOrder, ClientError> {
+        self.domains.domain_separator(self.network, provider).await?;
+        let typed_data = signer::to_typed_data_json(&self.network, &order).to_string();
+        let response = self
+            .request("eth_signTypedData_v4", json!([self.account, typed_data]))
+            .await?;
+
+        let signature: Signature = response
+            .as_str()
+            .and_then(|hex| hex.parse().ok())
+            .ok_or_else(|| RemoteWalletError::InvalidResponse(response.to_string()))?;
+
+        signer::verified(&self.network, order, signature)
+    }
+
+    /// Asks the wallet to fill `order` from the session account, leaving gas to
+    /// the wallet. Returns the transaction hash.
+    ///
+    /// `provider` is only used to check the exchange domain and build the call,
+    /// not to send it.
+    pub async fn fill<M: Middleware>(&self, order: &SignedOrder, provider: Arc<M>) -> Result<H256, ClientError> {
+        self.domains.domain_separator(self.network, provider.clone()).await?;
+        let call = order.fill(&self.network, provider, self.account);
+        self.send_transaction(&call.tx).await
+    }
+
+    /// Asks the wallet to send `tx` with `eth_sendTransaction`. Returns the transaction hash.
+    pub async fn send_transaction(&self, tx: &TypedTransaction) -> Result<H256, ClientError> {
+        let mut params = json!({ "from": self.account });
+        if let Some(to) = tx.to_addr() {
+            params["to"] = json!(to);
+        }
+        if let Some(data) = tx.data() {
+            params["data"] = json!(data);
+        }
+        if let Some(value) = tx.value() {
+            params["value"] = json!(value);
+        }
+
+        let response = self.request("eth_sendTransaction", json!([params])).await?;
+        let hash = serde_json::from_value(response.clone())
+            .map_err(|_| RemoteWalletError::InvalidResponse(response.to_string()))?;
+
+        Ok(hash)
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, RemoteWalletError> {
+        log::debug!("{} via the wallet session for {:?}", method, self.account);
+        self.session.request(self.network.chain_id(), method, params).await
+    }
+}
+
+/// The address in a CAIP-10 account id if it is on `chain_id`.
+fn account_on(id: &str, chain_id: u64) -> Option<Address> {
+    let mut parts = id.split(':');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some("eip155"), Some(chain), Some(address), None) if chain.parse() == Ok(chain_id) => address.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderTemplate;
+    use ethers::prelude::U256;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::transaction::eip712::TypedData;
+
+    #[derive(Debug)]
+    struct LocalSession(LocalWallet);
+
+    #[async_trait]
+    impl WalletSession for LocalSession {
+        fn accounts(&self) -> Vec<String> {
+            vec![
+                String::from("eip155:5:0x0000000000000000000000000000000000000001"),
+                format!("eip155:1:{:?}", self.0.address()),
+            ]
+        }
+
+        async fn request(&self, _chain_id: u64, method: &str, params: Value) -> Result<Value, RemoteWalletError> {
+            assert_eq!(method, "eth_signTypedData_v4");
+            let typed: TypedData = serde_json::from_str(params[1].as_str().unwrap()).unwrap();
+            let signature = self.0.sign_typed_data(&typed).await.unwrap();
+            Ok(json!(format!("0x{}", signature)))
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_orders_through_the_session() {
+        let wallet = crate::fixtures::wallet();
+        let bridge = RemoteWalletSigner::new(LocalSession(wallet.clone()), Network::Mainnet).unwrap();
+        assert_eq!(bridge.address(), wallet.address());
+
+        let template = OrderTemplate::ask(&Network::Mainnet, wallet.address(), Address::from_low_u64_be(2));
+        let order = template.instantiate(U256::from(7), U256::exp10(18), U256::zero(), 1_000);
+        let provider = crate::fixtures::exchange_provider(&Network::Mainnet);
+        let signed = bridge.sign_maker_order(order.clone(), provider.clone()).await.unwrap();
+        assert_eq!(signed.order, order);
+
+        let mut other = order;
+        other.signer = Address::from_low_u64_be(3);
+        assert!(bridge.sign_maker_order(other, provider).await.is_err());
+    }
+
+    #[test]
+    fn picks_accounts_on_the_network() {
+        assert_eq!(
+            account_on("eip155:1:0x0000000000000000000000000000000000000001", 1),
+            Some(Address::from_low_u64_be(1))
+        );
+        assert_eq!(account_on("eip155:5:0x0000000000000000000000000000000000000001", 1), None);
+        assert_eq!(account_on("solana:1:abc", 1), None);
+    }
+}
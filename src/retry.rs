@@ -0,0 +1,125 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+/// How the client retries requests that failed for transient reasons:
+/// 429, 5xx, timeouts and connection failures.
+///
+/// POSTs are retried only when the API is known not to have acted on them
+/// (429 or a failed connection), so an order is never submitted twice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first. 1 disables retrying.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each one after.
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Waits a random time between zero and the backoff, so clients that
+    /// failed together don't retry together.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Sends every request once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn new(max_attempts: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            max_delay,
+            jitter: true,
+        }
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// The delay before retry number `retry` (0 for the first), before jitter.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+        self.base_delay.checked_mul(factor).unwrap_or(self.max_delay).min(self.max_delay)
+    }
+
+    /// The delay before retry number `retry`, at least what the server asked for in `Retry-After`.
+    pub(crate) fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = self.backoff(retry);
+        let backoff = if self.jitter {
+            backoff.mul_f64(ethers::core::rand::random::<f64>())
+        } else {
+            backoff
+        };
+
+        retry_after.map_or(backoff, |after| after.max(backoff)).min(self.max_delay)
+    }
+}
+
+/// Whether a response with `status` to a `method` request is worth retrying.
+pub(crate) fn retryable_status(method: &Method, status: StatusCode) -> bool {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => true,
+        status if status.is_server_error() => method != Method::POST,
+        _ => false,
+    }
+}
+
+/// Whether a request that failed with `err` is worth retrying.
+pub(crate) fn retryable_error(method: &Method, err: &reqwest::Error) -> bool {
+    err.is_connect() || (method != Method::POST && (err.is_timeout() || err.is_request()))
+}
+
+/// The `Retry-After` header in seconds; HTTP dates are ignored.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn backs_off_exponentially_up_to_the_cap() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100), Duration::from_secs(1)).jitter(false);
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff(40), Duration::from_secs(1));
+
+        assert_eq!(policy.delay(0, Some(Duration::from_millis(700))), Duration::from_millis(700));
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(30))), Duration::from_secs(1));
+        assert!(RetryPolicy::default().delay(1, None) <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn retries_posts_only_when_not_acted_on() {
+        assert!(retryable_status(&Method::GET, StatusCode::BAD_GATEWAY));
+        assert!(retryable_status(&Method::POST, StatusCode::TOO_MANY_REQUESTS));
+        assert!(!retryable_status(&Method::POST, StatusCode::BAD_GATEWAY));
+        assert!(!retryable_status(&Method::GET, StatusCode::NOT_FOUND));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+    }
+}
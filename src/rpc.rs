@@ -0,0 +1,211 @@
+//! A JSON-RPC transport over several endpoints, for on-chain reads that
+//! shouldn't depend on one provider staying up.
+//!
+//! Wrap it in a `Provider` and pass that anywhere a `Middleware` is taken.
+
+use crate::health::Health;
+use async_trait::async_trait;
+use ethers::providers::{Http, HttpClientError, JsonRpcClient, JsonRpcError, Provider, ProviderError, RpcError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// RPC endpoints used in rotation, failing over to the next on transport errors.
+///
+/// Errors the node returned, such as a reverted call, are returned as-is: every
+/// endpoint would give the same answer. An endpoint that keeps failing is left
+/// out for a while; see `cooldown`.
+#[derive(Debug)]
+pub struct RpcPool {
+    endpoints: Vec<(String, Http)>,
+    health: Health,
+}
+
+/// An endpoint's health as last observed by the pool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointStatus {
+    pub url: String,
+    /// Consecutive failures since the last success.
+    pub failures: u32,
+    pub healthy: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum RpcPoolError {
+    #[error("Invalid RPC URL: {0}")]
+    InvalidUrl(String),
+    #[error("No RPC endpoints configured")]
+    NoEndpoints,
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    Http(#[from] HttpClientError),
+}
+
+impl RpcError for RpcPoolError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            RpcPoolError::Http(e) => e.as_error_response(),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            RpcPoolError::SerdeJson(e) => Some(e),
+            RpcPoolError::Http(e) => e.as_serde_error(),
+            _ => None,
+        }
+    }
+}
+
+impl From<RpcPoolError> for ProviderError {
+    fn from(err: RpcPoolError) -> Self {
+        match err {
+            RpcPoolError::Http(e) => e.into(),
+            err => ProviderError::CustomError(err.to_string()),
+        }
+    }
+}
+
+impl RpcPool {
+    pub fn new<I>(urls: I) -> Result<Self, RpcPoolError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                let url = url.into();
+                let http = Http::from_str(&url).map_err(|_| RpcPoolError::InvalidUrl(url.clone()))?;
+                Ok((url, http))
+            })
+            .collect::<Result<Vec<_>, RpcPoolError>>()?;
+
+        if endpoints.is_empty() {
+            return Err(RpcPoolError::NoEndpoints);
+        }
+
+        Ok(Self {
+            health: Health::new(endpoints.len()),
+            endpoints,
+        })
+    }
+
+    /// Leaves an endpoint out for `cooldown` after `max_failures` failures in a row.
+    /// Defaults to 3 failures and a minute.
+    pub fn cooldown(mut self, max_failures: u32, cooldown: Duration) -> Self {
+        self.health.set_cooldown(max_failures, cooldown);
+        self
+    }
+
+    /// A provider reading through the pool.
+    pub fn provider(self) -> Provider<RpcPool> {
+        Provider::new(self)
+    }
+
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        let now = Instant::now();
+
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(|(index, (url, _))| {
+                let (failures, healthy) = self.health.status(index, now);
+                EndpointStatus {
+                    url: url.clone(),
+                    failures,
+                    healthy,
+                }
+            })
+            .collect()
+    }
+
+    /// Asks every endpoint for the block number and records which answered.
+    pub async fn health_check(&self) -> Vec<EndpointStatus> {
+        for (index, (_, http)) in self.endpoints.iter().enumerate() {
+            let ok = http.request::<_, Value>("eth_blockNumber", ()).await.is_ok();
+            self.health.report(index, ok, Instant::now());
+        }
+
+        self.status()
+    }
+}
+
+/// Whether another endpoint might succeed where this one failed.
+fn should_fail_over(err: &HttpClientError) -> bool {
+    !matches!(err, HttpClientError::JsonRpcError(_))
+}
+
+#[async_trait]
+impl JsonRpcClient for RpcPool {
+    type Error = RpcPoolError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        // serialized once, since each endpoint needs its own copy
+        let params = serde_json::to_value(params)?;
+        let mut last = RpcPoolError::NoEndpoints;
+
+        for index in self.health.order(Instant::now()) {
+            let (url, http) = &self.endpoints[index];
+
+            match http.request(method, &params).await {
+                Ok(result) => {
+                    self.health.report(index, true, Instant::now());
+                    return Ok(result);
+                }
+                Err(e) if should_fail_over(&e) => {
+                    log::debug!("{} via {} failed: {}", method, url, e);
+                    self.health.report(index, false, Instant::now());
+                    last = e.into();
+                }
+                Err(e) => {
+                    self.health.report(index, true, Instant::now());
+                    return Err(e.into());
+                }
+            }
+        }
+
+        Err(last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_and_invalid_pools() {
+        assert!(matches!(RpcPool::new(Vec::<String>::new()), Err(RpcPoolError::NoEndpoints)));
+        assert!(matches!(RpcPool::new(["not a url"]), Err(RpcPoolError::InvalidUrl(_))));
+
+        let pool = RpcPool::new(["http://localhost:8545", "http://localhost:8546"]).unwrap();
+        assert_eq!(pool.status().len(), 2);
+        assert!(pool.status().iter().all(|status| status.healthy));
+    }
+
+    #[test]
+    fn fails_over_on_transport_errors_only() {
+        let revert = HttpClientError::JsonRpcError(JsonRpcError {
+            code: 3,
+            message: String::from("execution reverted"),
+            data: None,
+        });
+        assert!(!should_fail_over(&revert));
+
+        let malformed = serde_json::from_str::<Value>("not json").unwrap_err();
+        assert!(should_fail_over(&HttpClientError::SerdeJson {
+            err: malformed,
+            text: String::from("not json"),
+        }));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::bulk::BulkResult;
+use crate::order::SignedOrder;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledOrder {
+    pub id: u64,
+    pub submit_at: DateTime<Utc>,
+    pub order: SignedOrder,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    next_id: u64,
+    pending: Vec<ScheduledOrder>,
+}
+
+/// Holds signed orders until their submission time.
+///
+/// When opened on a file, every change is written through, so scheduled
+/// orders survive a restart. An order leaves the schedule only once its
+/// submission has been attempted; an order due while the process was down is
+/// submitted on the next run.
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    path: Option<PathBuf>,
+    state: State,
+}
+
+impl Scheduler {
+    /// A scheduler that does not persist anything.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Opens the schedule stored at `path`, starting empty if the file does not exist.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let state = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => State::default(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            state,
+        })
+    }
+
+    /// Schedules `order` for submission at `submit_at`, returning its id.
+    pub fn schedule(&mut self, order: SignedOrder, submit_at: DateTime<Utc>) -> io::Result<u64> {
+        let id = self.state.next_id;
+        self.state.next_id += 1;
+        self.state.pending.push(ScheduledOrder {
+            id,
+            submit_at,
+            order,
+        });
+        self.save()?;
+
+        Ok(id)
+    }
+
+    /// Removes a scheduled order before it is submitted.
+    pub fn cancel(&mut self, id: u64) -> io::Result<Option<ScheduledOrder>> {
+        let index = match self.state.pending.iter().position(|scheduled| scheduled.id == id) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+
+        let scheduled = self.state.pending.remove(index);
+        self.save()?;
+
+        Ok(Some(scheduled))
+    }
+
+    pub fn pending(&self) -> &[ScheduledOrder] {
+        &self.state.pending
+    }
+
+    /// When the next order is due, if any are scheduled.
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.state.pending.iter().map(|scheduled| scheduled.submit_at).min()
+    }
+
+    /// Submits every order due at `now`, oldest first, and removes them from the schedule.
+    ///
+    /// Results are keyed by scheduled order id. Failed orders are not retried.
+    ///
+    /// Dropping this future mid-submission leaves the order being submitted on
+    /// the schedule, so it is submitted again next time: delivery is at least
+    /// once, never lost. Orders already attempted are removed and saved.
+    pub async fn submit_due<F, Fut, T, E>(&mut self, now: DateTime<Utc>, mut submit: F) -> io::Result<BulkResult<T, E>>
+    where
+        F: FnMut(SignedOrder) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut due: Vec<ScheduledOrder> = self
+            .state
+            .pending
+            .iter()
+            .filter(|scheduled| scheduled.submit_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|scheduled| (scheduled.submit_at, scheduled.id));
+
+        let mut result = BulkResult::default();
+        for scheduled in due {
+            let id = scheduled.id as usize;
+            match submit(scheduled.order).await {
+                Ok(value) => result.succeeded.push((id, value)),
+                Err(e) => result.failed.push((id, e)),
+            }
+
+            self.state.pending.retain(|pending| pending.id != scheduled.id);
+            self.save()?;
+        }
+
+        Ok(result)
+    }
+
+    /// Sleeps until each order is due and submits it, returning once the schedule is empty.
+    pub async fn run<F, Fut, T, E>(&mut self, mut submit: F) -> io::Result<BulkResult<T, E>>
+    where
+        F: FnMut(SignedOrder) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut result = BulkResult::default();
+
+        while let Some(next) = self.next_due() {
+            if let Ok(wait) = (next - Utc::now()).to_std() {
+                tokio::time::sleep(wait).await;
+            }
+
+            let batch = self.submit_due(Utc::now(), &mut submit).await?;
+            result.succeeded.extend(batch.succeeded);
+            result.failed.extend(batch.failed);
+        }
+
+        Ok(result)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        // write to a sibling file and rename it over, so a crash never leaves a truncated schedule
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string(&self.state)?)?;
+        fs::rename(tmp, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderTemplate;
+    use crate::types::Network;
+    use ethers::prelude::{Address, Signature, U256};
+    use futures::executor::block_on;
+
+    fn signed(nonce: u64) -> SignedOrder {
+        let template = OrderTemplate::ask(&Network::Mainnet, Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        SignedOrder {
+            order: template.instantiate(U256::one(), U256::from(1000), U256::from(nonce), 0),
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
+        }
+    }
+
+    #[test]
+    fn submits_only_due_orders_and_persists() {
+        let path = std::env::temp_dir().join(format!("looksrare-schedule-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let now: DateTime<Utc> = "2022-11-06T14:00:00Z".parse().unwrap();
+
+        let mut scheduler = Scheduler::open(&path).unwrap();
+        scheduler.schedule(signed(1), now - chrono::Duration::minutes(1)).unwrap();
+        let later = scheduler.schedule(signed(2), now + chrono::Duration::hours(1)).unwrap();
+
+        let result = block_on(scheduler.submit_due(now, |order| async move {
+            Ok::<_, String>(order.order.nonce)
+        }))
+        .unwrap();
+        assert_eq!(result.succeeded, vec![(0, U256::from(1))]);
+
+        let reopened = Scheduler::open(&path).unwrap();
+        assert_eq!(reopened.pending().len(), 1);
+        assert_eq!(reopened.pending()[0].id, later);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn stored_schedule_round_trips_byte_for_byte() {
+        let path = std::env::temp_dir().join(format!("looksrare-schedule-stable-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let now: DateTime<Utc> = "2022-11-06T14:00:00Z".parse().unwrap();
+
+        let mut scheduler = Scheduler::open(&path).unwrap();
+        scheduler.schedule(signed(1), now).unwrap();
+        scheduler.schedule(signed(2), now + chrono::Duration::hours(1)).unwrap();
+        let stored = fs::read(&path).unwrap();
+
+        let reopened = Scheduler::open(&path).unwrap();
+        assert_eq!(reopened.pending(), scheduler.pending());
+        for (original, restored) in scheduler.pending().iter().zip(reopened.pending()) {
+            assert_eq!(restored.order.order.content_hash(), original.order.order.content_hash());
+        }
+
+        reopened.save().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), stored);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,158 @@
+use crate::api::{EventType, EventsRequest, LooksRareApi, Pagination};
+use crate::constants;
+use crate::types::{CollectionStats, Event};
+use crate::ClientError;
+use chrono::{Duration, Utc};
+use ethers::prelude::Address;
+use futures::future::try_join_all;
+
+/// Filters and ranking applied by `run`. Unset filters let every collection through.
+#[derive(Clone, Debug, Default)]
+pub struct ScreenerCriteria {
+    /// Collections to screen. When empty, the current listing-rewards collections are used.
+    pub collections: Vec<Address>,
+    pub min_volume_change_24h: Option<f64>,
+    pub min_floor_change_24h: Option<f64>,
+    pub min_listings_per_hour: Option<f64>,
+    pub rank_by: RankBy,
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RankBy {
+    #[default]
+    VolumeChange,
+    FloorChange,
+    ListingRate,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScreenerResult {
+    pub collection: Address,
+    /// Percentage change in 24h volume.
+    pub volume_change_24h: f64,
+    /// Percentage change in floor price over 24h.
+    pub floor_change_24h: f64,
+    /// New listings per hour over the last 24h.
+    pub listings_per_hour: f64,
+}
+
+/// Pulls stats and listing activity for each collection, filters by `criteria` and returns ranked results.
+pub async fn run(
+    api: &LooksRareApi,
+    criteria: &ScreenerCriteria,
+) -> Result<Vec<ScreenerResult>, ClientError> {
+    let collections = if criteria.collections.is_empty() {
+        api.get_top_5_listing_rewards_collections()
+            .await?
+            .into_iter()
+            .map(|rewards| rewards.collection.address)
+            .collect()
+    } else {
+        criteria.collections.clone()
+    };
+
+    let results = try_join_all(collections.into_iter().map(|collection| async move {
+        let listings = EventsRequest {
+            collection: Some(collection),
+            event_type: Some(EventType::List),
+            pagination: Some(Pagination {
+                first: Some(constants::MAX_ORDERS_PER_PAGE),
+                cursor: None,
+            }),
+            ..Default::default()
+        };
+
+        let (stats, listings) = futures::try_join!(
+            api.get_collection_stats(collection),
+            api.get_events(listings),
+        )?;
+
+        Ok::<_, ClientError>(screen(&stats, &listings))
+    }))
+    .await?;
+
+    Ok(rank(results, criteria))
+}
+
+fn screen(stats: &CollectionStats, listings: &[Event]) -> ScreenerResult {
+    let since = Utc::now() - Duration::hours(24);
+    let recent = listings
+        .iter()
+        .filter(|event| event.created_at > since)
+        .count();
+
+    ScreenerResult {
+        collection: stats.address,
+        volume_change_24h: stats.change_24h,
+        floor_change_24h: stats.floor_change_24h,
+        listings_per_hour: recent as f64 / 24.0,
+    }
+}
+
+fn rank(results: Vec<ScreenerResult>, criteria: &ScreenerCriteria) -> Vec<ScreenerResult> {
+    let passes = |value: f64, min: Option<f64>| min.is_none_or(|min| value >= min);
+
+    let mut results: Vec<ScreenerResult> = results
+        .into_iter()
+        .filter(|result| {
+            passes(result.volume_change_24h, criteria.min_volume_change_24h)
+                && passes(result.floor_change_24h, criteria.min_floor_change_24h)
+                && passes(result.listings_per_hour, criteria.min_listings_per_hour)
+        })
+        .collect();
+
+    let key = |result: &ScreenerResult| match criteria.rank_by {
+        RankBy::VolumeChange => result.volume_change_24h,
+        RankBy::FloorChange => result.floor_change_24h,
+        RankBy::ListingRate => result.listings_per_hour,
+    };
+    results.sort_by(|a, b| key(b).total_cmp(&key(a)));
+
+    if let Some(limit) = criteria.limit {
+        results.truncate(limit);
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: u64, volume_change_24h: f64, floor_change_24h: f64) -> ScreenerResult {
+        ScreenerResult {
+            collection: Address::from_low_u64_be(id),
+            volume_change_24h,
+            floor_change_24h,
+            listings_per_hour: 1.0,
+        }
+    }
+
+    #[test]
+    fn filters_and_ranks_results() {
+        let results = vec![result(1, 10.0, -5.0), result(2, 50.0, 2.0), result(3, 30.0, 8.0)];
+        let criteria = ScreenerCriteria {
+            min_floor_change_24h: Some(0.0),
+            ..Default::default()
+        };
+
+        let ranked = rank(results, &criteria);
+
+        assert_eq!(ranked, vec![result(2, 50.0, 2.0), result(3, 30.0, 8.0)]);
+    }
+
+    #[test]
+    fn ranks_by_selected_metric_and_limits() {
+        let results = vec![result(1, 10.0, -5.0), result(2, 50.0, 2.0), result(3, 30.0, 8.0)];
+        let criteria = ScreenerCriteria {
+            rank_by: RankBy::FloorChange,
+            limit: Some(1),
+            ..Default::default()
+        };
+
+        let ranked = rank(results, &criteria);
+
+        assert_eq!(ranked, vec![result(3, 30.0, 8.0)]);
+    }
+}
@@ -0,0 +1,124 @@
+use std::env;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+static REVEAL: AtomicBool = AtomicBool::new(false);
+
+/// Shows secrets and signatures in `Debug` output, which redacts them by default,
+/// for local debugging. Has no effect in release builds.
+pub fn reveal_secrets(reveal: bool) {
+    if cfg!(debug_assertions) {
+        REVEAL.store(reveal, Ordering::Relaxed);
+    }
+}
+
+fn revealed() -> bool {
+    cfg!(debug_assertions) && REVEAL.load(Ordering::Relaxed)
+}
+
+/// Debug-formats the value as `<redacted>` unless `reveal_secrets` is on.
+pub(crate) struct Redacted<'a, T>(pub(crate) &'a T);
+
+impl<T: fmt::Debug> fmt::Debug for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match revealed() {
+            true => self.0.fmt(f),
+            false => f.write_str("<redacted>"),
+        }
+    }
+}
+
+/// An API key or auth token, wiped from memory when dropped and redacted by `Debug`.
+///
+/// Read it with `expose` only where it is sent.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(Zeroizing<String>);
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("Environment variable {0} is not set")]
+    MissingEnv(String),
+    #[cfg(feature = "keychain")]
+    #[error(transparent)]
+    Keychain(#[from] keyring::Error),
+}
+
+impl Secret {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(Zeroizing::new(secret.into()))
+    }
+
+    /// Reads the secret from the environment variable `name`.
+    pub fn from_env(name: &str) -> Result<Self, SecretError> {
+        env::var(name)
+            .map(Self::new)
+            .map_err(|_| SecretError::MissingEnv(name.to_string()))
+    }
+
+    /// Reads the secret stored for `account` under `service` in the OS keychain:
+    /// the macOS Keychain, Windows Credential Manager or the Secret Service on Linux.
+    #[cfg(feature = "keychain")]
+    pub fn from_keychain(service: &str, account: &str) -> Result<Self, SecretError> {
+        let password = keyring::Entry::new(service, account)?.get_password()?;
+        Ok(Self::new(password))
+    }
+
+    /// Stores the secret for `account` under `service` in the OS keychain.
+    #[cfg(feature = "keychain")]
+    pub fn save_to_keychain(&self, service: &str, account: &str) -> Result<(), SecretError> {
+        keyring::Entry::new(service, account)?.set_password(self.expose())?;
+        Ok(())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Secret").field(&Redacted(&self.expose())).finish()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(secret: &str) -> Self {
+        Self::new(secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_debug_output() {
+        let secret = Secret::new("hunter2");
+        assert_eq!(secret.expose(), "hunter2");
+        assert_eq!(format!("{:?}", Some(secret)), "Some(Secret(<redacted>))");
+
+        assert!(matches!(
+            Secret::from_env("LOOKSRARE_TEST_UNSET_SECRET"),
+            Err(SecretError::MissingEnv(_))
+        ));
+    }
+
+    #[test]
+    fn redacts_signatures_in_orders() {
+        let mut order = crate::fixtures::order();
+        order.signature = Some(String::from("0xdeadbeef"));
+
+        let debug = format!("{:?}", order);
+        assert!(debug.contains("signature: <redacted>"));
+        assert!(!debug.contains("deadbeef"));
+        assert!(debug.contains("price: \"1000000000000000000\""));
+    }
+}
@@ -0,0 +1,428 @@
+//! Delegated signing: a hot session key signs orders within a policy, while the
+//! cold key only grants it allowances.
+//!
+//! The exchange settles from the order signer's own balances, so the session key
+//! places orders as its own account. The cold key approves it to pull a capped
+//! amount of WETH with `transferFrom`, and never signs orders.
+
+use crate::api::LooksRareApi;
+use crate::audit::{AuditAction, OrderRef};
+use crate::contracts::ERC20;
+use crate::guards::OrderGuards;
+use crate::order::{MakerOrder, SignedOrder};
+use crate::signer;
+use crate::types::Strategy;
+use crate::watch::unix_now;
+use crate::ClientError;
+use ethers::prelude::{Address, Middleware, H256, U256};
+use ethers::signers::Signer;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+/// Which key an audited action was taken with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyRole {
+    /// The long-lived key holding the funds, used only for approvals.
+    Cold,
+    /// The short-lived key signing orders under a `SessionPolicy`.
+    Session,
+}
+
+/// What a session key may sign. Every limit is off unless set, except the expiry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionPolicy {
+    /// Unix time after which nothing is signed. Orders must also end by then.
+    pub expires_at: u64,
+    /// Only orders on these collections, when set.
+    pub collections: Option<HashSet<Address>>,
+    pub allow_asks: bool,
+    pub allow_bids: bool,
+    /// Reject asks below this price.
+    pub min_ask_price: Option<U256>,
+    /// Reject bids above this price.
+    pub max_bid_price: Option<U256>,
+    /// The most all bids signed in the session may add up to.
+    pub bid_budget: Option<U256>,
+    pub max_orders: Option<u32>,
+}
+
+impl SessionPolicy {
+    /// Asks and bids on any collection until `expires_at`.
+    pub fn new(expires_at: u64) -> Self {
+        Self {
+            expires_at,
+            collections: None,
+            allow_asks: true,
+            allow_bids: true,
+            min_ask_price: None,
+            max_bid_price: None,
+            bid_budget: None,
+            max_orders: None,
+        }
+    }
+
+    pub fn collections(mut self, collections: impl IntoIterator<Item = Address>) -> Self {
+        self.collections = Some(collections.into_iter().collect());
+        self
+    }
+
+    pub fn asks_only(mut self) -> Self {
+        self.allow_bids = false;
+        self
+    }
+
+    pub fn bids_only(mut self) -> Self {
+        self.allow_asks = false;
+        self
+    }
+
+    pub fn min_ask_price(mut self, price: U256) -> Self {
+        self.min_ask_price = Some(price);
+        self
+    }
+
+    pub fn max_bid_price(mut self, price: U256) -> Self {
+        self.max_bid_price = Some(price);
+        self
+    }
+
+    pub fn bid_budget(mut self, budget: U256) -> Self {
+        self.bid_budget = Some(budget);
+        self
+    }
+
+    pub fn max_orders(mut self, max_orders: u32) -> Self {
+        self.max_orders = Some(max_orders);
+        self
+    }
+
+    /// Checks `order` against the policy at `now`, given what the session signed so far.
+    pub fn check(&self, order: &MakerOrder, usage: &SessionUsage, now: u64) -> Result<(), PolicyViolation> {
+        if now >= self.expires_at {
+            return Err(PolicyViolation::Expired { expires_at: self.expires_at });
+        }
+        if order.end_time > self.expires_at {
+            return Err(PolicyViolation::OutlivesSession {
+                end_time: order.end_time,
+                expires_at: self.expires_at,
+            });
+        }
+        if self.collections.as_ref().is_some_and(|collections| !collections.contains(&order.collection)) {
+            return Err(PolicyViolation::Collection(order.collection));
+        }
+        if let Some(max) = self.max_orders.filter(|max| usage.orders >= *max) {
+            return Err(PolicyViolation::TooManyOrders { max });
+        }
+
+        if order.is_order_ask {
+            if !self.allow_asks {
+                return Err(PolicyViolation::Side { is_order_ask: true });
+            }
+            if let Some(min) = self.min_ask_price.filter(|min| order.price < *min) {
+                return Err(PolicyViolation::AskBelowMin { price: order.price, min });
+            }
+        } else {
+            if !self.allow_bids {
+                return Err(PolicyViolation::Side { is_order_ask: false });
+            }
+            if let Some(max) = self.max_bid_price.filter(|max| order.price > *max) {
+                return Err(PolicyViolation::BidAboveMax { price: order.price, max });
+            }
+            if let Some(budget) = self.bid_budget.filter(|budget| usage.bid_total + order.price > *budget) {
+                return Err(PolicyViolation::OverBudget {
+                    price: order.price,
+                    spent: usage.bid_total,
+                    budget,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What a session key has signed so far.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionUsage {
+    pub orders: u32,
+    /// The summed prices of the bids signed.
+    pub bid_total: U256,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum PolicyViolation {
+    #[error("Session expired at {expires_at}")]
+    Expired { expires_at: u64 },
+    #[error("Order ends at {end_time}, after the session expires at {expires_at}")]
+    OutlivesSession { end_time: u64, expires_at: u64 },
+    #[error("Collection {0:?} is not allowed in this session")]
+    Collection(Address),
+    #[error("Session already signed {max} orders")]
+    TooManyOrders { max: u32 },
+    #[error("{} are not allowed in this session", if *is_order_ask { "Asks" } else { "Bids" })]
+    Side { is_order_ask: bool },
+    #[error("Ask price {price} is below the session minimum {min}")]
+    AskBelowMin { price: U256, min: U256 },
+    #[error("Bid price {price} is above the session maximum {max}")]
+    BidAboveMax { price: U256, max: U256 },
+    #[error("Bid of {price} exceeds the session budget {budget} ({spent} spent)")]
+    OverBudget { price: U256, spent: U256, budget: U256 },
+    #[error("Order signer {signer:?} is not the session key {session:?}")]
+    WrongSigner { signer: Address, session: Address },
+}
+
+/// The payload audited for an action taken through a `SessionKey`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KeyAction<'a, P> {
+    role: KeyRole,
+    key: Address,
+    #[serde(flatten)]
+    payload: &'a P,
+}
+
+#[derive(Serialize)]
+struct Allowance {
+    spender: Address,
+    amount: U256,
+}
+
+/// A hot key signing orders only within its `SessionPolicy` and `OrderGuards`.
+///
+/// Every attempt, allowed or not, is recorded in `api`'s audit log, if it has one.
+#[derive(Debug)]
+pub struct SessionKey<S> {
+    api: LooksRareApi,
+    signer: S,
+    policy: SessionPolicy,
+    guards: OrderGuards,
+    usage: Mutex<SessionUsage>,
+}
+
+impl<S: Signer> SessionKey<S> {
+    /// A session key signing for `api`'s network, with every guard disabled.
+    pub fn new(api: LooksRareApi, signer: S, policy: SessionPolicy) -> Self {
+        Self {
+            api,
+            signer,
+            policy,
+            guards: OrderGuards::default(),
+            usage: Mutex::new(SessionUsage::default()),
+        }
+    }
+
+    /// Checks every order against `guards` before the policy, e.g. to refuse
+    /// asks far off the floor.
+    pub fn guards(mut self, guards: OrderGuards) -> Self {
+        self.guards = guards;
+        self
+    }
+
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    pub fn policy(&self) -> &SessionPolicy {
+        &self.policy
+    }
+
+    pub fn usage(&self) -> SessionUsage {
+        self.usage.lock().unwrap().clone()
+    }
+
+    /// Signs `order` if the guards and the policy allow it, counting it against
+    /// the session's limits. The domain is checked on chain through `provider`
+    /// first; see `LooksRareApi::domain_separator`.
+    ///
+    /// The order is counted before it is signed, so concurrent calls can't
+    /// overrun the limits together, and uncounted again if signing fails.
+    pub async fn sign_maker_order<M: Middleware>(
+        &self,
+        order: MakerOrder,
+        provider: Arc<M>,
+    ) -> Result<SignedOrder, ClientError> {
+        if order.signer != self.address() {
+            let violation = PolicyViolation::WrongSigner {
+                signer: order.signer,
+                session: self.address(),
+            };
+            return Err(self.refuse(&order, violation.into()));
+        }
+
+        let guarded = match order.is_order_ask {
+            true => self.guards.validate_ask(&self.api, order.collection, order.price).await,
+            false => {
+                let collection_offer = order.strategy == self.api.network().strategy(Strategy::CollectionOffer);
+                let token_id = Some(order.token_id).filter(|_| !collection_offer).and_then(|id| u64::try_from(id).ok());
+                self.guards.validate_bid(&self.api, order.collection, token_id, order.price).await
+            }
+        };
+        if let Err(e) = guarded {
+            return Err(self.refuse(&order, e));
+        }
+        if let Err(e) = self.api.domain_separator(provider).await {
+            return Err(self.refuse(&order, e));
+        }
+
+        if let Err(violation) = self.reserve(&order) {
+            return Err(self.refuse(&order, violation.into()));
+        }
+
+        let orders = vec![OrderRef::from(&order)];
+        let payload = KeyAction { role: KeyRole::Session, key: self.address(), payload: &order };
+        let signed = signer::sign_maker_order(self.api.network(), order.clone(), &self.signer).await;
+        if signed.is_err() {
+            self.release(&order);
+        }
+
+        let outcome = signed.as_ref().map(|signed| format!("{:?}", signed.order.hash())).map_err(|e| e.to_string());
+        self.api.audit(AuditAction::OrderSigned, orders, &payload, outcome);
+
+        signed
+    }
+
+    /// Sends the WETH approval from the cold key, through `cold_provider`, letting
+    /// the session key pull up to `amount` for its bids.
+    pub async fn grant_weth<M: Middleware>(&self, cold_provider: Arc<M>, amount: U256) -> Result<H256, ClientError> {
+        let cold = cold_provider.default_sender().unwrap_or_default();
+        let call = ERC20::new(self.api.network().weth(), cold_provider).approve(self.address(), amount);
+        let sent = call.send().await.map(|pending| *pending).map_err(ClientError::from);
+
+        let allowance = Allowance { spender: self.address(), amount };
+        let payload = KeyAction { role: KeyRole::Cold, key: cold, payload: &allowance };
+        let outcome = sent.as_ref().map(|tx_hash| format!("{:?}", tx_hash)).map_err(|e| e.to_string());
+        self.api.audit(AuditAction::AllowanceGranted, vec![], &payload, outcome);
+
+        sent
+    }
+
+    /// Checks `order` against the policy and counts it, under one lock.
+    fn reserve(&self, order: &MakerOrder) -> Result<(), PolicyViolation> {
+        let mut usage = self.usage.lock().unwrap();
+        self.policy.check(order, &usage, unix_now())?;
+
+        usage.orders += 1;
+        if !order.is_order_ask {
+            usage.bid_total += order.price;
+        }
+        Ok(())
+    }
+
+    /// Uncounts an order `reserve` counted but that was never signed.
+    fn release(&self, order: &MakerOrder) {
+        let mut usage = self.usage.lock().unwrap();
+        usage.orders -= 1;
+        if !order.is_order_ask {
+            usage.bid_total -= order.price;
+        }
+    }
+
+    /// Audits `order` as refused for `reason` and hands the error back.
+    fn refuse(&self, order: &MakerOrder, reason: ClientError) -> ClientError {
+        log::warn!("session key {:?} refused to sign order {:?}: {}", self.address(), order.hash(), reason);
+
+        let payload = KeyAction { role: KeyRole::Session, key: self.address(), payload: order };
+        self.api
+            .audit(AuditAction::OrderSigned, vec![OrderRef::from(order)], &payload, Err(reason.to_string()));
+        reason
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditLog;
+    use crate::order::OrderTemplate;
+    use crate::types::Network;
+    use futures::executor::block_on;
+
+    fn bid(signer: Address, price: u64, end_time: u64) -> MakerOrder {
+        let mut order = OrderTemplate::bid(&Network::Mainnet, signer, Address::from_low_u64_be(2))
+            .instantiate(U256::from(7), U256::from(price), U256::zero(), 1_000);
+        order.end_time = end_time;
+        order
+    }
+
+    #[test]
+    fn enforces_policy_limits() {
+        let policy = SessionPolicy::new(2_000)
+            .collections([Address::from_low_u64_be(2)])
+            .max_bid_price(U256::from(100))
+            .bid_budget(U256::from(150));
+        let signer = Address::from_low_u64_be(1);
+        let mut usage = SessionUsage::default();
+
+        assert_eq!(policy.check(&bid(signer, 100, 1_500), &usage, 1_000), Ok(()));
+        assert!(matches!(
+            policy.check(&bid(signer, 101, 1_500), &usage, 1_000),
+            Err(PolicyViolation::BidAboveMax { .. })
+        ));
+        assert!(matches!(
+            policy.check(&bid(signer, 10, 2_500), &usage, 1_000),
+            Err(PolicyViolation::OutlivesSession { .. })
+        ));
+        assert!(matches!(
+            policy.check(&bid(signer, 10, 1_500), &usage, 2_000),
+            Err(PolicyViolation::Expired { .. })
+        ));
+
+        usage.bid_total = U256::from(100);
+        assert!(matches!(
+            policy.check(&bid(signer, 60, 1_500), &usage, 1_000),
+            Err(PolicyViolation::OverBudget { .. })
+        ));
+
+        let mut other = bid(signer, 10, 1_500);
+        other.collection = Address::from_low_u64_be(3);
+        assert_eq!(
+            policy.check(&other, &usage, 1_000),
+            Err(PolicyViolation::Collection(Address::from_low_u64_be(3)))
+        );
+        assert!(matches!(
+            policy.clone().asks_only().check(&bid(signer, 10, 1_500), &usage, 1_000),
+            Err(PolicyViolation::Side { is_order_ask: false })
+        ));
+    }
+
+    #[test]
+    fn reports_the_configured_order_limit() {
+        let policy = SessionPolicy::new(2_000).max_orders(2);
+        let usage = SessionUsage { orders: 3, bid_total: U256::zero() };
+
+        assert_eq!(
+            policy.check(&bid(Address::from_low_u64_be(1), 10, 1_500), &usage, 1_000),
+            Err(PolicyViolation::TooManyOrders { max: 2 })
+        );
+    }
+
+    #[test]
+    fn signs_counts_and_audits_orders() {
+        let wallet = crate::fixtures::wallet();
+        let path = std::env::temp_dir().join(format!("looksrare-session-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let api = LooksRareApi::new().audit_log(Arc::new(AuditLog::open(&path).unwrap()));
+
+        let now = unix_now();
+        let session = SessionKey::new(api, wallet.clone(), SessionPolicy::new(now + 3_600).bid_budget(U256::from(150)));
+        let provider = crate::fixtures::exchange_provider(&Network::Mainnet);
+        let sign = |order| block_on(session.sign_maker_order(order, provider.clone()));
+
+        sign(bid(wallet.address(), 100, now + 60)).unwrap();
+        assert_eq!(session.usage().bid_total, U256::from(100));
+
+        assert!(sign(bid(wallet.address(), 100, now + 60)).is_err());
+        assert!(sign(bid(Address::from_low_u64_be(9), 1, now + 60)).is_err());
+
+        let records = AuditLog::open(&path).unwrap().records().unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|record| record.action == AuditAction::OrderSigned));
+        assert!(records[0].ok);
+        assert!(records[1].detail.as_deref().unwrap().contains("budget"));
+        assert_eq!(session.usage().orders, 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
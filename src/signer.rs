@@ -0,0 +1,259 @@
+use crate::constants;
+use crate::contracts::LooksRareExchange;
+use crate::api::LooksRareApi;
+use crate::order::{MakerOrder, SignedOrder};
+use crate::types::Network;
+use crate::ClientError;
+use ethers::prelude::{Middleware, Signature, H256, U256};
+use ethers::signers::Signer;
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The EIP-712 domain LooksRare maker orders are signed under on `network`.
+pub fn domain(network: &Network) -> EIP712Domain {
+    EIP712Domain {
+        name: Some(String::from(constants::EXCHANGE_DOMAIN_NAME)),
+        version: Some(String::from(constants::EXCHANGE_DOMAIN_VERSION)),
+        chain_id: Some(U256::from(network.chain_id())),
+        verifying_contract: Some(network.exchange()),
+        salt: None,
+    }
+}
+
+/// The digest `order` is signed as on `network`.
+pub fn order_digest(network: &Network, order: &MakerOrder) -> H256 {
+    let separator = core_domain(network).separator();
+    H256(order.to_core().digest(&separator))
+}
+
+/// Signs `order` as EIP-712 typed data with any ethers signer, e.g. a `LocalWallet` or `Ledger`.
+///
+/// Fails if the signature does not recover to `order.signer`, e.g. when a
+/// hardware wallet signed with a different account. The domain isn't checked
+/// on chain, as no provider is given; `LooksRareApi::sign_maker_order` does.
+pub async fn sign_maker_order<S: Signer>(
+    network: &Network,
+    order: MakerOrder,
+    signer: &S,
+) -> Result<SignedOrder, ClientError> {
+    let typed = TypedOrder { network: *network, order: &order };
+    let signature = signer
+        .sign_typed_data(&typed)
+        .await
+        .map_err(|e| ClientError::SignerError(e.to_string()))?;
+
+    verified(network, order, signature)
+}
+
+/// Pairs `order` with `signature` once it recovers to `order.signer`.
+pub(crate) fn verified(network: &Network, order: MakerOrder, signature: Signature) -> Result<SignedOrder, ClientError> {
+    let recovered = signature
+        .recover(order_digest(network, &order))
+        .map_err(|e| ClientError::SignerError(e.to_string()))?;
+    if recovered != order.signer {
+        return Err(ClientError::SignerError(format!(
+            "order signer is {:?} but the signature recovers to {:?}",
+            order.signer, recovered
+        )));
+    }
+
+    Ok(SignedOrder { order, signature })
+}
+
+/// The `eth_signTypedData_v4` payload for `order` on `network`, for wallets that
+/// only sign typed-data JSON, e.g. through `remote_wallet`. The signature they return
+/// is the one `sign_maker_order` would produce.
+pub fn to_typed_data_json(network: &Network, order: &MakerOrder) -> Value {
+    let domain = domain(network);
+
+    json!({
+        "types": {
+            "EIP712Domain": type_fields(looksrare_core::DOMAIN_TYPE),
+            "MakerOrder": type_fields(looksrare_core::MAKER_ORDER_TYPE),
+        },
+        "primaryType": "MakerOrder",
+        "domain": {
+            "name": domain.name,
+            "version": domain.version,
+            "chainId": network.chain_id(),
+            "verifyingContract": domain.verifying_contract,
+        },
+        "message": {
+            "isOrderAsk": order.is_order_ask,
+            "signer": order.signer,
+            "collection": order.collection,
+            "price": order.price.to_string(),
+            "tokenId": order.token_id.to_string(),
+            "amount": order.amount.to_string(),
+            "strategy": order.strategy,
+            "currency": order.currency,
+            "nonce": order.nonce.to_string(),
+            "startTime": order.start_time.to_string(),
+            "endTime": order.end_time.to_string(),
+            "minPercentageToAsk": order.min_percentage_to_ask.to_string(),
+            "params": order.params,
+        },
+    })
+}
+
+/// The `[{name, type}]` member list of an encoded EIP-712 type such as `Name(uint256 a,bool b)`.
+fn type_fields(encoded: &str) -> Value {
+    let members = encoded
+        .split_once('(')
+        .and_then(|(_, rest)| rest.strip_suffix(')'))
+        .unwrap_or_default();
+
+    members
+        .split(',')
+        .filter_map(|member| member.split_once(' '))
+        .map(|(type_, name)| json!({ "name": name, "type": type_ }))
+        .collect()
+}
+
+impl LooksRareApi {
+    /// `sign_maker_order` on this client's network, once its domain separator
+    /// has been checked against the exchange through `provider`; see
+    /// `LooksRareApi::domain_separator`.
+    pub async fn sign_maker_order<S: Signer, M: Middleware>(
+        &self,
+        order: MakerOrder,
+        signer: &S,
+        provider: Arc<M>,
+    ) -> Result<SignedOrder, ClientError> {
+        self.domain_separator(provider).await?;
+        sign_maker_order(self.network(), order, signer).await
+    }
+}
+
+/// A maker order paired with the network it is signed for, as ethers signers expect.
+struct TypedOrder<'a> {
+    network: Network,
+    order: &'a MakerOrder,
+}
+
+impl Eip712 for TypedOrder<'_> {
+    type Error = Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(domain(&self.network))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(looksrare_core::keccak256(looksrare_core::MAKER_ORDER_TYPE.as_bytes()))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(self.order.hash().0)
+    }
+}
+
+fn core_domain(network: &Network) -> looksrare_core::Domain<'static> {
+    looksrare_core::Domain {
+        name: constants::EXCHANGE_DOMAIN_NAME,
+        version: constants::EXCHANGE_DOMAIN_VERSION,
+        chain_id: network.chain_id(),
+        verifying_contract: network.exchange().0,
+    }
+}
+
+/// Domain separators that have been checked against the exchange contract.
+///
+/// A separator built from a wrong chain id or exchange address still produces
+/// signatures, just ones the exchange rejects, so each network is verified
+/// on-chain once before its separator is handed out.
+#[derive(Debug, Default)]
+pub struct DomainCache {
+    verified: Mutex<HashMap<Network, H256>>,
+}
+
+impl DomainCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn domain_separator<M: Middleware>(
+        &self,
+        network: Network,
+        provider: Arc<M>,
+    ) -> Result<H256, ClientError> {
+        if let Some(separator) = self.verified.lock().unwrap().get(&network) {
+            return Ok(*separator);
+        }
+
+        let local = H256::from(domain(&network).separator());
+        let exchange = LooksRareExchange::new(network.exchange(), provider);
+        let onchain = H256::from(exchange.domain_separator().call().await?);
+
+        if local != onchain {
+            return Err(ClientError::DomainMismatch {
+                network,
+                local,
+                onchain,
+            });
+        }
+
+        self.verified.lock().unwrap().insert(network, local);
+
+        Ok(local)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderTemplate;
+    use ethers::prelude::Address;
+    use ethers::types::transaction::eip712::TypedData;
+    use futures::executor::block_on;
+
+    #[test]
+    fn mainnet_domain() {
+        let domain = domain(&Network::Mainnet);
+        assert_eq!(domain.name.as_deref(), Some("LooksRareExchange"));
+        assert_eq!(domain.version.as_deref(), Some("1"));
+        assert_eq!(domain.chain_id, Some(U256::from(1)));
+        assert_eq!(domain.verifying_contract, Some(Network::Mainnet.exchange()));
+    }
+
+    #[test]
+    fn signs_recoverable_orders() {
+        let wallet = crate::fixtures::wallet();
+        let network = Network::Mainnet;
+        let template = OrderTemplate::ask(&network, wallet.address(), Address::from_low_u64_be(2));
+        let order = template.instantiate(U256::from(7), U256::exp10(18), U256::zero(), 1_000);
+
+        let typed = TypedOrder { network, order: &order };
+        assert_eq!(H256(typed.encode_eip712().unwrap()), order_digest(&network, &order));
+
+        let signed = block_on(sign_maker_order(&network, order.clone(), &wallet)).unwrap();
+        assert_eq!(signed.signature.recover(order_digest(&network, &order)).unwrap(), wallet.address());
+
+        let mut foreign = order;
+        foreign.signer = Address::from_low_u64_be(3);
+        assert!(block_on(sign_maker_order(&network, foreign, &wallet)).is_err());
+    }
+
+    #[test]
+    fn typed_data_json_encodes_to_the_order_digest() {
+        let network = Network::Mainnet;
+        let template = OrderTemplate::ask(&network, Address::from_low_u64_be(1), Address::from_low_u64_be(2))
+            .params(vec![0xca, 0xfe].into());
+        let order = template.instantiate(U256::from(7), U256::exp10(18), U256::from(3), 1_000);
+
+        let json = to_typed_data_json(&network, &order);
+        assert_eq!(json["types"]["MakerOrder"][12], json!({ "name": "params", "type": "bytes" }));
+        assert_eq!(json["message"]["params"], "0xcafe");
+
+        let typed: TypedData = serde_json::from_value(json).unwrap();
+        assert_eq!(H256(typed.encode_eip712().unwrap()), order_digest(&network, &order));
+    }
+
+    #[test]
+    fn core_separator_matches_ethers() {
+        let network = Network::Mainnet;
+        assert_eq!(core_domain(&network).separator(), domain(&network).separator());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::api::{LooksRareApi, LooksRareApiError};
+use crate::guards::{GuardViolation, OrderGuards};
+use crate::order::{MakerOrder, OrderTemplate};
+use crate::tick::{self, Rounding};
+use crate::watch::{unix_now, watch_competing_bids, Outbid, WatchConfig};
+use crate::ClientError;
+use ethers::prelude::U256;
+use futures::future::{self, Either};
+use futures::{pin_mut, stream, Stream, StreamExt};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Outbids competitors on one token by `increment`, never going above `ceiling`
+/// and waiting at least `cooldown` between offers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AutoRebid {
+    pub ceiling: U256,
+    pub increment: U256,
+    pub cooldown: Duration,
+}
+
+/// What `AutoRebid` did about one competing offer.
+#[derive(Debug)]
+pub enum RebidOutcome<T> {
+    /// A new offer was made at `price`; `made` is what `make_offer` returned.
+    Rebid { price: U256, made: T },
+    /// Beating the competing offer would take the price above the ceiling.
+    AtCeiling { competing: Outbid },
+    /// The last offer was made less than `cooldown` ago. The competing offer
+    /// is answered once the cooldown ends, unless a newer one comes first.
+    CoolingDown { competing: Outbid },
+    /// The guards rejected the new price.
+    Blocked(GuardViolation),
+}
+
+impl AutoRebid {
+    pub fn new(ceiling: U256, increment: U256, cooldown: Duration) -> Self {
+        Self {
+            ceiling,
+            increment,
+            cooldown,
+        }
+    }
+
+    /// The price that beats `competing`, or `None` if it would exceed the ceiling.
+    pub fn next_price(&self, competing: U256) -> Option<U256> {
+        let price = competing.checked_add(self.increment)?;
+        (price <= self.ceiling).then_some(price)
+    }
+
+    /// `next_price` rounded up to `tick`, or `None` if the rounded price no
+    /// longer beats `competing` or exceeds the ceiling.
+    pub fn next_price_on(&self, competing: U256, tick: Option<U256>) -> Option<U256> {
+        let price = tick::round(self.next_price(competing)?, tick.unwrap_or_default(), Rounding::Up);
+        (price > competing && price <= self.ceiling).then_some(price)
+    }
+
+    /// Watches competing offers on `token_id` and answers each with a new offer
+    /// built from `template`, passed to `make_offer` to sign and post.
+    ///
+    /// Every new price is rounded up to the template's tick and checked against
+    /// `guards` first. The stream yields an outcome per competing offer, and
+    /// another once the cooldown ends for an offer that came in during it. It
+    /// ends when the watcher does.
+    pub fn run<F, Fut, T>(
+        self,
+        api: Arc<LooksRareApi>,
+        template: OrderTemplate,
+        token_id: u64,
+        guards: OrderGuards,
+        config: WatchConfig,
+        make_offer: F,
+    ) -> impl Stream<Item = Result<RebidOutcome<T>, ClientError>>
+    where
+        F: FnMut(MakerOrder) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let competing = watch_competing_bids(api.clone(), template.signer, template.collection, token_id, config);
+        let state = RebidState {
+            strategy: self,
+            api,
+            template,
+            token_id,
+            guards,
+            make_offer,
+            competing: Box::pin(competing),
+            last_offer: None,
+            pending: None,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            let competing = match state.next_competing().await? {
+                Ok(competing) => competing,
+                Err(e) => return Some((Err(e.into()), state)),
+            };
+
+            let outcome = state.respond(competing).await;
+            Some((outcome, state))
+        })
+    }
+}
+
+struct RebidState<F, S> {
+    strategy: AutoRebid,
+    api: Arc<LooksRareApi>,
+    template: OrderTemplate,
+    token_id: u64,
+    guards: OrderGuards,
+    make_offer: F,
+    competing: Pin<Box<S>>,
+    last_offer: Option<Instant>,
+    /// The latest competing offer that came in during the cooldown.
+    pending: Option<Outbid>,
+}
+
+impl<F, S, Fut, T> RebidState<F, S>
+where
+    F: FnMut(MakerOrder) -> Fut,
+    Fut: Future<Output = Result<T, ClientError>>,
+    S: Stream<Item = Result<Outbid, LooksRareApiError>>,
+{
+    fn cooldown_left(&self) -> Duration {
+        self.last_offer
+            .map(|last| self.strategy.cooldown.saturating_sub(last.elapsed()))
+            .unwrap_or_default()
+    }
+
+    /// The next competing offer from the watcher, or the pending one once the
+    /// cooldown ends if the watcher has nothing newer by then.
+    async fn next_competing(&mut self) -> Option<Result<Outbid, LooksRareApiError>> {
+        if self.pending.is_none() {
+            return self.competing.next().await;
+        }
+
+        let cooldown = tokio::time::sleep(self.cooldown_left());
+        pin_mut!(cooldown);
+        match future::select(self.competing.next(), cooldown).await {
+            Either::Left((next, _)) => next,
+            Either::Right(((), _)) => self.pending.take().map(Ok),
+        }
+    }
+
+    async fn respond(&mut self, competing: Outbid) -> Result<RebidOutcome<T>, ClientError> {
+        // a newer offer supersedes one waiting out the cooldown
+        self.pending = None;
+        if !self.cooldown_left().is_zero() {
+            self.pending = Some(competing.clone());
+            return Ok(RebidOutcome::CoolingDown { competing });
+        }
+
+        let price = match self.strategy.next_price_on(competing.price, self.template.tick) {
+            Some(price) => price,
+            None => return Ok(RebidOutcome::AtCeiling { competing }),
+        };
+
+        match self
+            .guards
+            .validate_bid(&self.api, self.template.collection, Some(self.token_id), price)
+            .await
+        {
+            Ok(()) => {}
+            Err(ClientError::GuardViolation(violation)) => return Ok(RebidOutcome::Blocked(violation)),
+            Err(e) => return Err(e),
+        }
+
+        let nonce = self.api.get_order_nonce(self.template.signer).await?;
+        let order = self
+            .template
+            .instantiate(U256::from(self.token_id), price, nonce, unix_now());
+        let price = order.price;
+
+        let made = (self.make_offer)(order).await?;
+        self.last_offer = Some(Instant::now());
+
+        Ok(RebidOutcome::Rebid { price, made })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_price_respects_ceiling() {
+        let strategy = AutoRebid::new(U256::from(1000), U256::from(10), Duration::from_secs(60));
+
+        assert_eq!(strategy.next_price(U256::from(900)), Some(U256::from(910)));
+        assert_eq!(strategy.next_price(U256::from(990)), Some(U256::from(1000)));
+        assert_eq!(strategy.next_price(U256::from(991)), None);
+    }
+
+    #[test]
+    fn rebids_round_up_and_stay_above_the_competition() {
+        let strategy = AutoRebid::new(U256::from(1000), U256::from(1), Duration::from_secs(60));
+        let tick = Some(U256::from(100));
+
+        assert_eq!(strategy.next_price_on(U256::from(850), tick), Some(U256::from(900)));
+        assert_eq!(strategy.next_price_on(U256::from(900), tick), Some(U256::from(1000)));
+        // rounding up would pass the ceiling
+        assert_eq!(strategy.next_price_on(U256::from(950), Some(U256::from(300))), None);
+        assert_eq!(strategy.next_price_on(U256::from(850), None), Some(U256::from(851)));
+    }
+}
@@ -0,0 +1,189 @@
+use crate::api::{LooksRareApi, OrdersRequest, Pagination, Sort, Status};
+use crate::composite::{partial, subtask, SubtaskError};
+use crate::constants;
+use crate::contracts::{ExecutionStrategy, RoyaltyFeeManager};
+use crate::order::{self, SignedOrder};
+use crate::types::Strategy;
+use crate::watch::unix_now;
+use crate::ClientError;
+use ethers::prelude::{Address, Middleware, U256};
+use futures::future::join_all;
+use futures::{pin_mut, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// What buying the cheapest listings of a collection would cost.
+///
+/// Fees whose lookup failed or timed out are left out of `protocol_fees` and
+/// `royalty_fees`, and their errors are listed in `errors`.
+#[derive(Debug)]
+pub struct SweepEstimate {
+    /// The listings to fill, cheapest first, at most one per token. Fewer than
+    /// asked for when the collection has fewer fillable listings.
+    pub listings: Vec<SignedOrder>,
+    /// What the buyer pays for the tokens, before gas.
+    pub total_price: U256,
+    /// The protocol's share of `total_price`, paid out of the sellers' proceeds.
+    pub protocol_fees: U256,
+    /// The royalty share of `total_price`, paid out of the sellers' proceeds.
+    pub royalty_fees: U256,
+    pub gas_price: U256,
+    /// One exchange transaction per listing, as `execution::ExchangeExecutor` sends them.
+    pub sequential: GasEstimate,
+    /// Every listing in one aggregator transaction, as `execution::AggregatorExecutor` sends it.
+    pub batched: GasEstimate,
+    pub errors: Vec<SubtaskError>,
+}
+
+impl SweepEstimate {
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Whether one aggregator transaction is cheaper in gas than filling each listing on its own.
+    pub fn prefers_batching(&self) -> bool {
+        self.batched.cost < self.sequential.cost
+    }
+}
+
+/// Gas for filling a sweep one way, from typical per-fill figures; actual use
+/// varies with the collection's transfer and royalty logic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasEstimate {
+    pub transactions: u64,
+    pub gas: U256,
+    /// `gas` at the estimate's gas price, in wei.
+    pub cost: U256,
+}
+
+impl GasEstimate {
+    pub fn sequential(fills: u64, gas_price: U256) -> Self {
+        Self::new(fills, U256::from(constants::FILL_GAS) * fills, gas_price)
+    }
+
+    pub fn batched(fills: u64, gas_price: U256) -> Self {
+        let gas = match fills {
+            0 => U256::zero(),
+            _ => U256::from(constants::AGGREGATOR_BASE_GAS) + U256::from(constants::AGGREGATOR_GAS_PER_ORDER) * fills,
+        };
+        Self::new(fills.min(1), gas, gas_price)
+    }
+
+    fn new(transactions: u64, gas: U256, gas_price: U256) -> Self {
+        Self {
+            transactions,
+            gas,
+            cost: gas * gas_price,
+        }
+    }
+}
+
+/// Estimates buying the `n` cheapest fillable listings of `collection`, with
+/// fees from the current on-chain configuration and the current gas price.
+///
+/// The fee lookups run concurrently, each bounded by `timeout`.
+pub async fn estimate_sweep<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    collection: Address,
+    n: usize,
+    timeout: Duration,
+) -> Result<SweepEstimate, ClientError> {
+    let req = OrdersRequest {
+        is_order_ask: Some(true),
+        collection: Some(collection),
+        strategy: Some(Strategy::StandardSale),
+        status: Some(vec![Status::Valid]),
+        pagination: Some(Pagination { first: Some(constants::DEFAULT_ORDERS_PER_PAGE), cursor: None }),
+        sort: Some(Sort::PriceAsc),
+        ..Default::default()
+    };
+
+    let now = unix_now();
+    let mut tokens = HashSet::new();
+    let mut listings = vec![];
+    let asks = api.get_orders_stream(req);
+    pin_mut!(asks);
+    while listings.len() < n {
+        let ask = match asks.next().await {
+            Some(ask) => ask?,
+            None => break,
+        };
+        if let Some(signed) = order::fillable(api.network(), &ask, now).filter(|signed| tokens.insert(signed.order.token_id)) {
+            listings.push(signed);
+        }
+    }
+
+    let fee_manager = RoyaltyFeeManager::new(api.network().royalty_fee_manager(), provider.clone());
+    let strategies: HashSet<Address> = listings.iter().map(|listing| listing.order.strategy).collect();
+    let (bps_lookups, royalty_lookups) = futures::join!(
+        join_all(strategies.into_iter().map(|strategy| {
+            let call = ExecutionStrategy::new(strategy, provider.clone()).view_protocol_fee();
+            async move { (strategy, subtask("protocol fee", timeout, async move { call.call().await }).await) }
+        })),
+        join_all(listings.iter().map(|listing| {
+            let order = &listing.order;
+            let call = fee_manager.calculate_royalty_fee_and_get_recipient(collection, order.token_id, order.price);
+            subtask("royalty fee", timeout, async move { call.call().await })
+        })),
+    );
+
+    let mut errors = vec![];
+    let mut protocol_fee_bps: HashMap<Address, U256> = HashMap::new();
+    for (strategy, bps) in bps_lookups {
+        if let Some(bps) = partial(bps, &mut errors) {
+            protocol_fee_bps.insert(strategy, bps);
+        }
+    }
+
+    let (mut total_price, mut protocol_fees, mut royalty_fees) = (U256::zero(), U256::zero(), U256::zero());
+    for (listing, royalty) in listings.iter().zip(royalty_lookups) {
+        let order = &listing.order;
+        total_price += order.price;
+        if let Some(bps) = protocol_fee_bps.get(&order.strategy) {
+            protocol_fees += order.price * *bps / U256::from(10_000);
+        }
+        if let Some((_, royalty_fee)) = partial(royalty, &mut errors) {
+            royalty_fees += royalty_fee;
+        }
+    }
+
+    let gas_price = provider
+        .get_gas_price()
+        .await
+        .map_err(|e| ClientError::ProviderError(e.to_string()))?;
+    let fills = listings.len() as u64;
+
+    Ok(SweepEstimate {
+        listings,
+        total_price,
+        protocol_fees,
+        royalty_fees,
+        gas_price,
+        sequential: GasEstimate::sequential(fills, gas_price),
+        batched: GasEstimate::batched(fills, gas_price),
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batching_pays_off_beyond_one_fill() {
+        let gas_price = U256::from(30_000_000_000u64);
+
+        let one = (GasEstimate::sequential(1, gas_price), GasEstimate::batched(1, gas_price));
+        assert!(one.0.cost < one.1.cost);
+
+        let five = (GasEstimate::sequential(5, gas_price), GasEstimate::batched(5, gas_price));
+        assert_eq!(five.0.transactions, 5);
+        assert_eq!(five.1.transactions, 1);
+        assert_eq!(five.1.gas, U256::from(60_000 + 5 * 150_000));
+        assert!(five.1.cost < five.0.cost);
+
+        assert_eq!(GasEstimate::batched(0, gas_price).gas, U256::zero());
+    }
+}
@@ -0,0 +1,407 @@
+use crate::annotations::Annotation;
+use crate::api::{EventType, EventsRequest, LooksRareApi, LooksRareApiError, Pagination};
+use crate::constants;
+use crate::contracts::{ExecutionStrategy, RoyaltyFeeManager};
+use crate::ownership;
+use crate::types::{Aggregator, Event, Network, Token};
+use crate::ClientError;
+use chrono::{DateTime, Utc};
+use ethers::prelude::{Address, Middleware, H256, U256};
+use futures::{stream, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+const CONCURRENT_CALLS: usize = 8;
+
+const CSV_HEADER: &str = "timestamp,token_id,price,currency,seller,buyer,rarity_rank,protocol_fee,royalty_fee,royalty_recipient,tx_hash,gas_used,effective_gas_price,gas_fee,via_aggregator,tags";
+
+/// One sale on the tape, with the fees that were taken out of the price.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trade {
+    pub timestamp: DateTime<Utc>,
+    pub token_id: String,
+    pub price: U256,
+    pub currency: Address,
+    pub seller: Address,
+    pub buyer: Option<Address>,
+    /// The token's rank by trait rarity in the collection, 1 the rarest; see `rarity_ranks`.
+    pub rarity_rank: Option<u64>,
+    pub protocol_fee: U256,
+    pub royalty_fee: U256,
+    pub royalty_recipient: Address,
+    pub tx_hash: Option<String>,
+    /// Set by `attach_receipts`.
+    pub gas: Option<GasCost>,
+    /// Set by `tag_aggregators` when the sale was routed through an aggregator.
+    pub via_aggregator: Option<Aggregator>,
+    /// The annotation on the filled order, if the API has an annotation store.
+    pub annotation: Option<Annotation>,
+}
+
+impl Trade {
+    /// What the seller received after protocol and royalty fees, in the trade currency.
+    pub fn seller_proceeds(&self) -> U256 {
+        self.price
+            .saturating_sub(self.protocol_fee)
+            .saturating_sub(self.royalty_fee)
+    }
+}
+
+/// Gas paid for the transaction a trade settled in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasCost {
+    /// Gas used by the whole transaction.
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    /// This trade's share of the transaction fee in wei, split evenly across
+    /// the trades of a transaction that settled several, e.g. a sweep.
+    pub fee: U256,
+}
+
+/// Builds the trade tape for a collection over a time range, oldest trade first.
+///
+/// Fees are read from the current on-chain strategy and royalty configuration,
+/// which may differ from what applied at the time of older trades. Rarity ranks
+/// take fetching every token of the collection from the API, one request each.
+pub async fn export<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    collection: Address,
+    range: Range<DateTime<Utc>>,
+) -> Result<Vec<Trade>, ClientError> {
+    let sales = sales_in_range(api, collection, &range).await?;
+    let ranks = rarity_ranks(&collection_tokens(api, collection).await?);
+    let fee_manager = RoyaltyFeeManager::new(api.network().royalty_fee_manager(), provider.clone());
+
+    let mut protocol_fees: HashMap<Address, U256> = HashMap::new();
+    let mut trades = vec![];
+
+    for sale in sales {
+        let (order, token) = match (&sale.order, &sale.token) {
+            (Some(order), Some(token)) => (order, token),
+            _ => continue,
+        };
+
+        let price = U256::from_dec_str(&order.price).unwrap_or_default();
+        let token_id = U256::from_dec_str(&token.token_id).unwrap_or_default();
+
+        let protocol_fee_bps = match protocol_fees.get(&order.strategy) {
+            Some(fee) => *fee,
+            None => {
+                let strategy = ExecutionStrategy::new(order.strategy, provider.clone());
+                let fee = strategy.view_protocol_fee().call().await?;
+                protocol_fees.insert(order.strategy, fee);
+                fee
+            }
+        };
+
+        let (royalty_recipient, royalty_fee) = fee_manager
+            .calculate_royalty_fee_and_get_recipient(collection, token_id, price)
+            .call()
+            .await?;
+
+        trades.push(Trade {
+            timestamp: sale.created_at,
+            token_id: token.token_id.clone(),
+            price,
+            currency: order.currency_address,
+            seller: sale.from,
+            buyer: sale.to,
+            rarity_rank: ranks.get(&token_id).copied(),
+            protocol_fee: price * protocol_fee_bps / U256::from(10_000),
+            royalty_fee,
+            royalty_recipient,
+            tx_hash: sale.hash.clone(),
+            gas: None,
+            via_aggregator: None,
+            annotation: order.hash.as_deref().and_then(|hash| api.annotation(hash)),
+        });
+    }
+
+    Ok(trades)
+}
+
+/// Ranks tokens by trait rarity, 1 the rarest.
+///
+/// A token scores the sum, over its traits, of how many tokens there are per
+/// token with that trait value; ties go to the lower token id.
+pub fn rarity_ranks(tokens: &[Token]) -> HashMap<U256, u64> {
+    let traits = |token: &Token| -> Vec<(String, String)> {
+        let attributes = token.attributes.iter();
+        attributes.map(|attribute| (attribute.trait_type.clone(), attribute.value.to_string())).collect()
+    };
+
+    let mut counts: HashMap<(String, String), usize> = HashMap::new();
+    for token in tokens {
+        for value in traits(token) {
+            *counts.entry(value).or_default() += 1;
+        }
+    }
+
+    let total = tokens.len() as f64;
+    let mut scored: Vec<(U256, f64)> = tokens
+        .iter()
+        .filter_map(|token| {
+            let token_id = U256::from_dec_str(&token.token_id).ok()?;
+            let score = traits(token).iter().map(|value| total / counts[value] as f64).sum();
+            Some((token_id, score))
+        })
+        .collect();
+    scored.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then(a_id.cmp(b_id)));
+
+    scored
+        .into_iter()
+        .enumerate()
+        .map(|(index, (token_id, _))| (token_id, index as u64 + 1))
+        .collect()
+}
+
+/// Every token of `collection` the API has seen minted, with its traits.
+async fn collection_tokens(api: &LooksRareApi, collection: Address) -> Result<Vec<Token>, ClientError> {
+    let token_ids = ownership::get_collection_tokens_from_api(api, collection).await?;
+
+    let tokens: Vec<Token> = stream::iter(token_ids)
+        .map(|token_id| api.get_token(collection, token_id))
+        .buffered(CONCURRENT_CALLS)
+        .try_collect()
+        .await?;
+
+    Ok(tokens)
+}
+
+/// Fetches the receipt of every trade's transaction and attaches its gas cost.
+///
+/// Trades without a transaction hash, or whose receipt is not available, are left without one.
+pub async fn attach_receipts<M: Middleware>(
+    provider: Arc<M>,
+    trades: &mut [Trade],
+) -> Result<(), ClientError> {
+    let mut trades_per_tx: HashMap<H256, u64> = HashMap::new();
+    for hash in trades.iter().filter_map(tx_hash) {
+        *trades_per_tx.entry(hash).or_default() += 1;
+    }
+
+    let mut costs: HashMap<H256, Option<(U256, U256)>> = HashMap::new();
+    for hash in trades_per_tx.keys() {
+        let receipt = provider
+            .get_transaction_receipt(*hash)
+            .await
+            .map_err(|e| ClientError::ProviderError(e.to_string()))?;
+        let cost = receipt.and_then(|receipt| Some((receipt.gas_used?, receipt.effective_gas_price?)));
+        costs.insert(*hash, cost);
+    }
+
+    for trade in trades.iter_mut() {
+        let hash = match tx_hash(trade) {
+            Some(hash) => hash,
+            None => continue,
+        };
+
+        if let Some((gas_used, effective_gas_price)) = costs[&hash] {
+            trade.gas = Some(GasCost {
+                gas_used,
+                effective_gas_price,
+                fee: gas_used * effective_gas_price / U256::from(trades_per_tx[&hash]),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Tags trades whose transaction was sent to a known aggregator router.
+///
+/// The taker on such trades is the router contract rather than the buyer,
+/// which matters when attributing taker behavior.
+pub async fn tag_aggregators<M: Middleware>(
+    network: &Network,
+    provider: Arc<M>,
+    trades: &mut [Trade],
+) -> Result<(), ClientError> {
+    let mut routed: HashMap<H256, Option<Aggregator>> = HashMap::new();
+
+    for trade in trades.iter_mut() {
+        let hash = match tx_hash(trade) {
+            Some(hash) => hash,
+            None => continue,
+        };
+
+        let aggregator = match routed.get(&hash) {
+            Some(aggregator) => *aggregator,
+            None => {
+                let tx = provider
+                    .get_transaction(hash)
+                    .await
+                    .map_err(|e| ClientError::ProviderError(e.to_string()))?;
+                let aggregator = tx.and_then(|tx| network.aggregator(tx.to?));
+                routed.insert(hash, aggregator);
+                aggregator
+            }
+        };
+
+        trade.via_aggregator = aggregator;
+    }
+
+    Ok(())
+}
+
+fn tx_hash(trade: &Trade) -> Option<H256> {
+    trade.tx_hash.as_deref()?.parse().ok()
+}
+
+/// Renders trades as CSV with a header row.
+pub fn to_csv(trades: &[Trade]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for trade in trades {
+        csv.push_str(&format!(
+            "{},{},{},{:?},{:?},{},{},{},{},{:?},{},{},{},{},{},{}\n",
+            trade.timestamp.to_rfc3339(),
+            trade.token_id,
+            trade.price,
+            trade.currency,
+            trade.seller,
+            trade.buyer.map(|buyer| format!("{:?}", buyer)).unwrap_or_default(),
+            trade.rarity_rank.map(|rank| rank.to_string()).unwrap_or_default(),
+            trade.protocol_fee,
+            trade.royalty_fee,
+            trade.royalty_recipient,
+            trade.tx_hash.clone().unwrap_or_default(),
+            trade.gas.as_ref().map(|gas| gas.gas_used.to_string()).unwrap_or_default(),
+            trade.gas.as_ref().map(|gas| gas.effective_gas_price.to_string()).unwrap_or_default(),
+            trade.gas.as_ref().map(|gas| gas.fee.to_string()).unwrap_or_default(),
+            trade.via_aggregator.map(|aggregator| aggregator.to_str().to_string()).unwrap_or_default(),
+            csv_field(&trade.annotation.as_ref().map(tags).unwrap_or_default()),
+        ));
+    }
+
+    csv
+}
+
+/// The annotation's tags, `;`-separated.
+fn tags(annotation: &Annotation) -> String {
+    annotation.tags.iter().cloned().collect::<Vec<_>>().join(";")
+}
+
+/// `value` quoted as one CSV field if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    match value.contains(|c: char| matches!(c, ',' | '"' | '\n' | '\r')) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+/// Every sale of `collection` in `range`, oldest first.
+pub(crate) async fn sales_in_range(
+    api: &LooksRareApi,
+    collection: Address,
+    range: &Range<DateTime<Utc>>,
+) -> Result<Vec<Event>, LooksRareApiError> {
+    events_in_range(api, collection, EventType::Sale, range).await
+}
+
+/// Every event of one type for `collection` in `range`, oldest first.
+pub(crate) async fn events_in_range(
+    api: &LooksRareApi,
+    collection: Address,
+    event_type: EventType,
+    range: &Range<DateTime<Utc>>,
+) -> Result<Vec<Event>, LooksRareApiError> {
+    let mut events = vec![];
+    let mut cursor: Option<String> = None;
+
+    // events come back newest first, so stop once a page reaches past the start of the range
+    loop {
+        let req = EventsRequest {
+            collection: Some(collection),
+            event_type: Some(event_type),
+            pagination: Some(Pagination {
+                first: Some(constants::MAX_ORDERS_PER_PAGE),
+                cursor: cursor.take(),
+            }),
+            ..Default::default()
+        };
+
+        let page = api.get_events(req).await?;
+        let page_len = page.len() as u64;
+        let reached_start = page.last().is_some_and(|event| event.created_at < range.start);
+        cursor = page.last().map(|event| event.id.to_string());
+
+        events.extend(page.into_iter().filter(|event| range.contains(&event.created_at)));
+
+        if reached_start || page_len < constants::MAX_ORDERS_PER_PAGE {
+            break;
+        }
+    }
+
+    events.reverse();
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TokenAttribute;
+
+    #[test]
+    fn renders_csv() {
+        let trade = Trade {
+            timestamp: "2022-11-06T16:10:34Z".parse().unwrap(),
+            token_id: String::from("62962"),
+            price: U256::from(1000),
+            currency: "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2".parse().unwrap(),
+            seller: Address::from_low_u64_be(1),
+            buyer: None,
+            rarity_rank: Some(12),
+            protocol_fee: U256::from(20),
+            royalty_fee: U256::from(50),
+            royalty_recipient: Address::from_low_u64_be(2),
+            tx_hash: Some(String::from("0xabc")),
+            gas: Some(GasCost {
+                gas_used: U256::from(150_000),
+                effective_gas_price: U256::from(20),
+                fee: U256::from(3_000_000),
+            }),
+            via_aggregator: Some(Aggregator::Gem),
+            annotation: Some(Annotation {
+                tags: ["strategy:sweep", "batch:7"].into_iter().map(String::from).collect(),
+                note: None,
+            }),
+        };
+        assert_eq!(trade.seller_proceeds(), U256::from(930));
+
+        let csv = to_csv(&[trade]);
+        let mut lines = csv.lines();
+
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        assert_eq!(
+            lines.next(),
+            Some("2022-11-06T16:10:34+00:00,62962,1000,0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2,0x0000000000000000000000000000000000000001,,12,20,50,0x0000000000000000000000000000000000000002,0xabc,150000,20,3000000,gem,batch:7;strategy:sweep")
+        );
+        assert_eq!(lines.next(), None);
+
+        assert_eq!(csv_field("batch:7"), "batch:7");
+        assert_eq!(csv_field(r#"note:"a, b""#), r#""note:""a, b""""#);
+    }
+
+    #[test]
+    fn ranks_rarest_traits_first() {
+        let token = |token_id: &str, background: &str| Token {
+            token_id: token_id.to_string(),
+            attributes: vec![TokenAttribute {
+                trait_type: String::from("Background"),
+                value: background.into(),
+                display_type: None,
+            }],
+            ..Default::default()
+        };
+        let ranks = rarity_ranks(&[token("1", "Blue"), token("2", "Blue"), token("3", "Gold")]);
+
+        assert_eq!(ranks[&U256::from(3)], 1);
+        assert_eq!(ranks[&U256::from(1)], 2);
+        assert_eq!(ranks[&U256::from(2)], 3);
+    }
+}
@@ -0,0 +1,77 @@
+use ethers::prelude::U256;
+
+/// 0.001 ETH, the precision prices are aligned to unless a profile sets its own tick.
+pub const DEFAULT_TICK: u64 = 1_000_000_000_000_000;
+
+/// Wei below one gwei, which no price written by hand ever carries.
+pub const DUST: u64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rounding {
+    #[default]
+    Down,
+    Up,
+    Nearest,
+}
+
+/// Aligns `price` to a multiple of `tick`. A zero tick leaves the price untouched.
+pub fn round(price: U256, tick: U256, rounding: Rounding) -> U256 {
+    if tick.is_zero() {
+        return price;
+    }
+
+    let remainder = price % tick;
+    if remainder.is_zero() {
+        return price;
+    }
+
+    let down = price - remainder;
+    match rounding {
+        Rounding::Down => down,
+        Rounding::Up => down.saturating_add(tick),
+        Rounding::Nearest if remainder * 2 >= tick => down.saturating_add(tick),
+        Rounding::Nearest => down,
+    }
+}
+
+/// Rounds an ask to `tick`. Asks round down so a listing never ends up above the intended price.
+pub fn round_ask(price: U256, tick: U256) -> U256 {
+    round(price, tick, Rounding::Down)
+}
+
+/// Rounds a bid to `tick`. Bids round down so an offer never pays more than intended.
+pub fn round_bid(price: U256, tick: U256) -> U256 {
+    round(price, tick, Rounding::Down)
+}
+
+/// Drops the sub-gwei remainder left behind by fee and conversion arithmetic.
+pub fn strip_dust(price: U256) -> U256 {
+    round(price, U256::from(DUST), Rounding::Down)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(milli: u64) -> U256 {
+        U256::from(milli) * U256::from(DEFAULT_TICK)
+    }
+
+    #[test]
+    fn rounds_to_tick() {
+        let tick = U256::from(DEFAULT_TICK);
+        let price = eth(1234) + U256::from(600_000_000_000_000u64);
+
+        assert_eq!(round(price, tick, Rounding::Down), eth(1234));
+        assert_eq!(round(price, tick, Rounding::Up), eth(1235));
+        assert_eq!(round(price, tick, Rounding::Nearest), eth(1235));
+        assert_eq!(round(eth(1234), tick, Rounding::Up), eth(1234));
+        assert_eq!(round(price, U256::zero(), Rounding::Up), price);
+    }
+
+    #[test]
+    fn strips_dust() {
+        let price = eth(1500) + U256::from(17);
+        assert_eq!(strip_dust(price), eth(1500));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::api::LooksRareApi;
+use crate::audit::AuditAction;
+use crate::contracts::{ERC1155, ERC721};
+use crate::types::Network;
+use crate::ClientError;
+use ethers::prelude::{Address, Bytes, Middleware, PendingTransaction, TransactionReceipt, H256, U256};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// ERC-165 interface id of ERC-721.
+const ERC721_INTERFACE: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+/// ERC-165 interface id of ERC-1155.
+const ERC1155_INTERFACE: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+
+/// The NFT standard a collection contract implements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenStandard {
+    Erc721,
+    Erc1155,
+}
+
+impl TokenStandard {
+    pub fn to_str(&self) -> &str {
+        match self {
+            TokenStandard::Erc721 => "ERC721",
+            TokenStandard::Erc1155 => "ERC1155",
+        }
+    }
+
+    /// The LooksRare transfer manager that moves tokens of this standard.
+    pub fn transfer_manager(&self, network: &Network) -> Address {
+        match self {
+            TokenStandard::Erc721 => network.transfer_manager_erc721(),
+            TokenStandard::Erc1155 => network.transfer_manager_erc1155(),
+        }
+    }
+}
+
+/// What became of a token bought for a vault; see `deliver_to_vault`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Delivery {
+    /// Sent on to the vault in this transaction, which was mined successfully.
+    Transferred(H256),
+    /// The purchase reverted, so there was nothing to send on.
+    PurchaseReverted,
+    /// The purchase wasn't confirmed, or the transfer couldn't be sent, reverted
+    /// or was dropped, so the token may be left with the buyer.
+    Stranded(String),
+}
+
+/// A purchase made on behalf of `vault`, with where the token ended up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaultPurchase {
+    pub vault: Address,
+    pub purchase_tx: H256,
+    pub delivery: Delivery,
+}
+
+impl VaultPurchase {
+    /// Whether the token was sent on to the vault.
+    pub fn is_delivered(&self) -> bool {
+        matches!(self.delivery, Delivery::Transferred(_))
+    }
+}
+
+/// The standard `collection` reports through ERC-165.
+pub async fn detect_standard<M: Middleware>(provider: Arc<M>, collection: Address) -> Result<TokenStandard, ClientError> {
+    // both interfaces go through the same `supportsInterface`
+    let contract = ERC721::new(collection, provider);
+    let erc1155 = contract.supports_interface(ERC1155_INTERFACE);
+    let erc721 = contract.supports_interface(ERC721_INTERFACE);
+    let (erc1155, erc721) = futures::try_join!(erc1155.call(), erc721.call())?;
+
+    match (erc721, erc1155) {
+        (_, true) => Ok(TokenStandard::Erc1155),
+        (true, false) => Ok(TokenStandard::Erc721),
+        (false, false) => Err(ClientError::UnsupportedToken(collection)),
+    }
+}
+
+/// Moves one `token_id` of `collection` from `signer` to `to` with `safeTransferFrom`,
+/// e.g. into a vault or multisig after buying it. `provider` must sign for `signer`.
+///
+/// A contract `to` must accept the token, as safe transfers revert otherwise.
+/// Returns the transaction hash once sent. The transfer is audited in `api`'s
+/// audit log, if it has one.
+pub async fn transfer_token<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    collection: Address,
+    token_id: U256,
+    to: Address,
+    signer: Address,
+) -> Result<H256, ClientError> {
+    let sent = async {
+        let tx_hash = match detect_standard(provider.clone(), collection).await? {
+            TokenStandard::Erc721 => {
+                let call = ERC721::new(collection, provider)
+                    .safe_transfer_from(signer, to, token_id)
+                    .from(signer);
+                let pending = call.send().await?;
+                *pending
+            }
+            TokenStandard::Erc1155 => {
+                let call = ERC1155::new(collection, provider)
+                    .safe_transfer_from(signer, to, token_id, U256::one(), Bytes::default())
+                    .from(signer);
+                let pending = call.send().await?;
+                *pending
+            }
+        };
+
+        Ok::<_, ClientError>(tx_hash)
+    }
+    .await;
+
+    let transfer = Transfer {
+        collection,
+        token_id,
+        from: signer,
+        to,
+    };
+    let outcome = sent.as_ref().map(|tx_hash| format!("{:?}", tx_hash)).map_err(|e| e.to_string());
+    api.audit(AuditAction::TokenTransferred, vec![], &transfer, outcome);
+
+    sent
+}
+
+/// The audit payload of a transfer.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Transfer {
+    collection: Address,
+    token_id: U256,
+    from: Address,
+    to: Address,
+}
+
+/// Waits for `purchase_tx` to be mined, then moves `token_id` of `collection`
+/// from `buyer` to `vault` with `transfer_token` and waits for that too.
+///
+/// The exchange always delivers to the taker, so a purchase for a vault or
+/// multisig takes this second transaction. Failures after the purchase was
+/// sent are reported in the returned `Delivery` rather than as an error.
+pub async fn deliver_to_vault<M: Middleware>(
+    api: &LooksRareApi,
+    provider: Arc<M>,
+    purchase_tx: H256,
+    collection: Address,
+    token_id: U256,
+    buyer: Address,
+    vault: Address,
+) -> VaultPurchase {
+    let receipt = PendingTransaction::new(purchase_tx, provider.provider())
+        .await
+        .map_err(|e| e.to_string());
+
+    let delivery = match after_purchase(receipt) {
+        Some(delivery) => delivery,
+        None => match transfer_token(api, provider.clone(), collection, token_id, vault, buyer).await {
+            Ok(tx_hash) => {
+                let receipt = PendingTransaction::new(tx_hash, provider.provider())
+                    .await
+                    .map_err(|e| e.to_string());
+                after_transfer(tx_hash, receipt)
+            }
+            Err(e) => Delivery::Stranded(e.to_string()),
+        },
+    };
+
+    VaultPurchase {
+        vault,
+        purchase_tx,
+        delivery,
+    }
+}
+
+/// How the purchase ended if the token can't be sent on, or `None` if it can.
+fn after_purchase(receipt: Result<Option<TransactionReceipt>, String>) -> Option<Delivery> {
+    match receipt {
+        Ok(Some(receipt)) if receipt.status.is_some_and(|status| status.is_zero()) => Some(Delivery::PurchaseReverted),
+        Ok(Some(_)) => None,
+        Ok(None) => Some(Delivery::Stranded(String::from("Purchase was dropped before being mined"))),
+        Err(e) => Some(Delivery::Stranded(e)),
+    }
+}
+
+/// How the transfer to the vault ended.
+fn after_transfer(tx_hash: H256, receipt: Result<Option<TransactionReceipt>, String>) -> Delivery {
+    match receipt {
+        Ok(Some(receipt)) if receipt.status.is_some_and(|status| status.is_zero()) => {
+            Delivery::Stranded(format!("Transfer {:?} reverted", tx_hash))
+        }
+        Ok(Some(_)) => Delivery::Transferred(tx_hash),
+        Ok(None) => Delivery::Stranded(format!("Transfer {:?} was dropped before being mined", tx_hash)),
+        Err(e) => Delivery::Stranded(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interface_ids_match_their_selectors() {
+        // ERC-721's id is the XOR of its function selectors
+        let erc721 = [
+            "balanceOf(address)",
+            "ownerOf(uint256)",
+            "approve(address,uint256)",
+            "getApproved(uint256)",
+            "setApprovalForAll(address,bool)",
+            "isApprovedForAll(address,address)",
+            "transferFrom(address,address,uint256)",
+            "safeTransferFrom(address,address,uint256)",
+            "safeTransferFrom(address,address,uint256,bytes)",
+        ];
+        let id = erc721.iter().fold([0u8; 4], |mut id, signature| {
+            let selector = ethers::utils::id(signature);
+            id.iter_mut().zip(selector).for_each(|(byte, other)| *byte ^= other);
+            id
+        });
+
+        assert_eq!(id, ERC721_INTERFACE);
+        assert_eq!(TokenStandard::Erc1155.to_str(), "ERC1155");
+    }
+
+    #[test]
+    fn forwards_only_mined_purchases() {
+        let mined = TransactionReceipt {
+            status: Some(1u64.into()),
+            ..Default::default()
+        };
+        assert_eq!(after_purchase(Ok(Some(mined))), None);
+
+        let reverted = TransactionReceipt {
+            status: Some(0u64.into()),
+            ..Default::default()
+        };
+        assert_eq!(after_purchase(Ok(Some(reverted))), Some(Delivery::PurchaseReverted));
+        assert!(matches!(after_purchase(Ok(None)), Some(Delivery::Stranded(_))));
+    }
+
+    #[test]
+    fn delivers_only_mined_transfers() {
+        let tx_hash = H256::repeat_byte(1);
+        let mined = TransactionReceipt {
+            status: Some(1u64.into()),
+            ..Default::default()
+        };
+        assert_eq!(after_transfer(tx_hash, Ok(Some(mined))), Delivery::Transferred(tx_hash));
+
+        let reverted = TransactionReceipt {
+            status: Some(0u64.into()),
+            ..Default::default()
+        };
+        assert!(matches!(after_transfer(tx_hash, Ok(Some(reverted))), Delivery::Stranded(_)));
+        assert!(matches!(after_transfer(tx_hash, Ok(None)), Delivery::Stranded(_)));
+        assert!(matches!(after_transfer(tx_hash, Err(String::from("timeout"))), Delivery::Stranded(_)));
+    }
+}
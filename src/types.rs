@@ -1,11 +1,19 @@
 use crate::constants;
+use crate::secret::Redacted;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ethers::{
     prelude::Address, 
-    types::H256,
+    types::{H256, U256},
 };
+use std::fmt;
 
-#[derive(Debug, Serialize, Deserialize)]
+// Orders in the shape the exchange takes them, convertible from `Order` and
+// ABI-encodable for contract calls.
+pub use crate::contracts::TakerOrder;
+pub use crate::order::{MakerOrder, SignedOrder};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Network {
     Mainnet,
@@ -22,9 +30,153 @@ impl Network {
         let url = self.url();
         format!("{}{}{}", url, constants::API_PATH, constants::VERSION)
     }
+
+    /// The LooksRare web app.
+    pub fn app_url(&self) -> &str {
+        match self {
+            Network::Mainnet => constants::APP_MAINNET,
+        }
+    }
+
+    /// The block explorer transactions and addresses are linked to.
+    pub fn explorer_url(&self) -> &str {
+        match self {
+            Network::Mainnet => constants::EXPLORER_MAINNET,
+        }
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            Network::Mainnet => constants::CHAIN_ID_MAINNET,
+        }
+    }
+
+    pub fn exchange(&self) -> Address {
+        match self {
+            Network::Mainnet => constants::EXCHANGE_MAINNET.parse().unwrap(),
+        }
+    }
+
+    pub fn transfer_manager_erc721(&self) -> Address {
+        match self {
+            Network::Mainnet => constants::TRANSFER_MANAGER_ERC721_MAINNET.parse().unwrap(),
+        }
+    }
+
+    pub fn transfer_manager_erc1155(&self) -> Address {
+        match self {
+            Network::Mainnet => constants::TRANSFER_MANAGER_ERC1155_MAINNET.parse().unwrap(),
+        }
+    }
+
+    pub fn weth(&self) -> Address {
+        match self {
+            Network::Mainnet => constants::WETH_MAINNET.parse().unwrap(),
+        }
+    }
+
+    pub fn looks(&self) -> Address {
+        match self {
+            Network::Mainnet => constants::LOOKS_MAINNET.parse().unwrap(),
+        }
+    }
+
+    pub fn royalty_fee_manager(&self) -> Address {
+        match self {
+            Network::Mainnet => constants::ROYALTY_FEE_MANAGER_MAINNET.parse().unwrap(),
+        }
+    }
+
+    pub fn currency(&self, currency: Currency) -> Address {
+        match currency {
+            Currency::Weth | Currency::Eth => self.weth(),
+            Currency::Looks => self.looks(),
+            Currency::Custom(address) => address,
+        }
+    }
+
+    pub fn strategy(&self, strategy: Strategy) -> Address {
+        let address = match (self, strategy) {
+            (Network::Mainnet, Strategy::StandardSale) => constants::STRATEGY_STANDARD_SALE_MAINNET,
+            (Network::Mainnet, Strategy::CollectionOffer) => constants::STRATEGY_COLLECTION_OFFER_MAINNET,
+            (Network::Mainnet, Strategy::PrivateSale) => constants::STRATEGY_PRIVATE_SALE_MAINNET,
+            (Network::Mainnet, Strategy::DutchAuction) => constants::STRATEGY_DUTCH_AUCTION_MAINNET,
+            (_, Strategy::Custom(address)) => return address,
+        };
+
+        address.parse().unwrap()
+    }
+
+    /// The aggregator whose router is at `address`, if any.
+    pub fn aggregator(&self, address: Address) -> Option<Aggregator> {
+        let routers: &[(Aggregator, &str)] = match self {
+            Network::Mainnet => &[
+                (Aggregator::Gem, constants::GEM_ROUTER_MAINNET),
+                (Aggregator::Gem, constants::GEM_ROUTER_LEGACY_MAINNET),
+                (Aggregator::Genie, constants::GENIE_ROUTER_MAINNET),
+                (Aggregator::Blur, constants::BLUR_ROUTER_MAINNET),
+                (Aggregator::UniversalRouter, constants::UNIVERSAL_ROUTER_MAINNET),
+            ],
+        };
+
+        routers
+            .iter()
+            .find(|(_, router)| router.parse::<Address>().unwrap() == address)
+            .map(|(aggregator, _)| *aggregator)
+    }
+}
+
+/// An execution strategy whitelisted on the exchange.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+    /// Fixed price sale of a specific token.
+    StandardSale,
+    /// Offer that any token in the collection can fill.
+    CollectionOffer,
+    /// Fixed price sale restricted to one buyer, given in `params`.
+    PrivateSale,
+    /// Price decays from the `params` start price to `price` over the order's lifetime.
+    DutchAuction,
+    /// A strategy contract at a fixed address on every network, e.g. one not listed here.
+    Custom(Address),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// A currency orders are priced in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Currency {
+    Weth,
+    /// Orders in ETH are WETH orders: the exchange only takes ETH from buyers,
+    /// wrapping it to fill WETH listings.
+    Eth,
+    Looks,
+    /// A token at a fixed address on every network, e.g. one not listed here.
+    Custom(Address),
+}
+
+/// A marketplace aggregator that routes purchases through its own contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregator {
+    Gem,
+    Genie,
+    Blur,
+    UniversalRouter,
+}
+
+impl Aggregator {
+    pub fn to_str(&self) -> &str {
+        match self {
+            Aggregator::Gem => "gem",
+            Aggregator::Genie => "genie",
+            Aggregator::Blur => "blur",
+            Aggregator::UniversalRouter => "universal_router",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
     pub address: Address,
@@ -36,7 +188,7 @@ pub struct Account {
     pub is_verified: bool,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Order {
     pub hash: String,
@@ -60,7 +212,33 @@ pub struct Order {
     pub s: Option<H256>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl fmt::Debug for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Order")
+            .field("hash", &self.hash)
+            .field("collection_address", &self.collection_address)
+            .field("token_id", &self.token_id)
+            .field("is_order_ask", &self.is_order_ask)
+            .field("signer", &self.signer)
+            .field("strategy", &self.strategy)
+            .field("currency_address", &self.currency_address)
+            .field("amount", &self.amount)
+            .field("price", &self.price)
+            .field("nonce", &self.nonce)
+            .field("start_time", &self.start_time)
+            .field("end_time", &self.end_time)
+            .field("min_percentage_to_ask", &self.min_percentage_to_ask)
+            .field("params", &self.params)
+            .field("status", &self.status)
+            .field("signature", &Redacted(&self.signature))
+            .field("v", &Redacted(&self.v))
+            .field("r", &Redacted(&self.r))
+            .field("s", &Redacted(&self.s))
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CollectionInformation {
     pub address: Address,
@@ -86,51 +264,231 @@ pub struct CollectionInformation {
     pub banner_uri: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// A collection as listed by `/collections`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub address: Address,
+    pub owner: Address,
+    pub name: String,
+    pub description: Option<String>,
+    pub symbol: Option<String>,
+    pub type_: String,
+    pub website_link: Option<String>,
+    pub twitter_link: Option<String>,
+    pub discord_link: Option<String>,
+    pub is_verified: bool,
+    pub is_explicit: bool,
+    #[serde(rename = "logoURI")]
+    pub logo_uri: Option<String>,
+    #[serde(rename = "bannerURI")]
+    pub banner_uri: Option<String>,
+}
+
+/// Collection stats with wei amounts as `U256` and counts and changes as numbers.
+///
+/// Fields the API sent in a form that doesn't parse are zero, except a missing floor, which is `None`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CollectionStats {
     pub address: Address,
-    pub count_owners: String,
-    pub total_supply: String,
-    pub floor_price: String,
-    pub floor_change_24h: String,
-    pub floor_change_7d: String,
-    pub floor_change_30d: String,
-    pub market_cap: String,
-    pub volume_24h: String,
-    pub average_24h: String,
-    pub count_24h: Option<String>,
-    pub change_24h: String,
-    pub volume_7d: String,
-    pub average_7d: String,
-    pub count_7d: String,
-    pub change_7d: String,
-    pub volume_1m: String,
-    pub average_1m: String,
-    pub count_1m: String,
-    pub change_1m: String,
-    pub volume_3m: String,
-    pub average_3m: String,
-    pub count_3m: String,
-    pub change_3m: String,
-    pub volume_6m: String,
-    pub average_6m: String,
-    pub count_6m: String,
-    pub change_6m: String,
-    pub volume_1y: String,
-    pub average_1y: String,
-    pub count_1y: String,
-    pub change_1y: String,
-    pub volume_all: String,
-    pub average_all: String,
-    pub count_all: String,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
+    pub count_owners: u64,
+    pub total_supply: u64,
+    pub floor_price: Option<U256>,
+    /// Percent change, e.g. `-12.5`.
+    pub floor_change_24h: f64,
+    pub floor_change_7d: f64,
+    pub floor_change_30d: f64,
+    pub market_cap: U256,
+    pub volume_24h: U256,
+    pub average_24h: U256,
+    pub count_24h: u64,
+    pub change_24h: f64,
+    pub volume_7d: U256,
+    pub average_7d: U256,
+    pub count_7d: u64,
+    pub change_7d: f64,
+    pub volume_1m: U256,
+    pub average_1m: U256,
+    pub count_1m: u64,
+    pub change_1m: f64,
+    pub volume_3m: U256,
+    pub average_3m: U256,
+    pub count_3m: u64,
+    pub change_3m: f64,
+    pub volume_6m: U256,
+    pub average_6m: U256,
+    pub count_6m: u64,
+    pub change_6m: f64,
+    pub volume_1y: U256,
+    pub average_1y: U256,
+    pub count_1y: u64,
+    pub change_1y: f64,
+    pub volume_all: U256,
+    pub average_all: U256,
+    pub count_all: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CollectionRewards {
     pub collection: CollectionInformation,
     pub volume_24h_global: String,
     pub points: u64,
     pub floor_global: String,
+}
+
+/// LOOKS rewards earned by an address, with the merkle proofs needed to claim them.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rewards {
+    pub trading: Option<Reward>,
+    pub listing: Option<Reward>,
+}
+
+/// One reward tree's entry for an address.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reward {
+    /// Cumulative amount earned in the tree, in wei, as claimed against the distributor.
+    pub amount: String,
+    /// Merkle proof of `amount` for the distributor's current root.
+    pub proof: Vec<H256>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Token {
+    pub collection_address: Address,
+    pub token_id: String,
+    #[serde(rename = "tokenURI")]
+    pub token_uri: Option<String>,
+    #[serde(rename = "imageURI")]
+    pub image_uri: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub is_explicit: bool,
+    pub flag: TokenFlag,
+    pub attributes: Vec<TokenAttribute>,
+}
+
+impl Token {
+    /// Whether LooksRare has banned the token from trading.
+    pub fn is_flagged(&self) -> bool {
+        self.flag == TokenFlag::Flagged
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenAttribute {
+    pub trait_type: String,
+    /// A string, number or boolean, as the collection's metadata has it.
+    pub value: serde_json::Value,
+    pub display_type: Option<String>,
+}
+
+/// A token's moderation status.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TokenFlag {
+    #[default]
+    #[serde(rename = "NONE")]
+    Unflagged,
+    /// Reported and waiting for review; still tradable.
+    Triage,
+    /// Banned from trading, e.g. as stolen.
+    Flagged,
+    /// Reviewed and cleared.
+    Validated,
+    /// A status this crate doesn't know yet.
+    #[serde(other)]
+    Unknown,
+}
+
+impl TokenFlag {
+    pub fn to_str(&self) -> &str {
+        match self {
+            TokenFlag::Unflagged => "NONE",
+            TokenFlag::Triage => "TRIAGE",
+            TokenFlag::Flagged => "FLAGGED",
+            TokenFlag::Validated => "VALIDATED",
+            TokenFlag::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+/// The kind of an event, as the `type` of `/events` and its filter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EventType {
+    List,
+    Sale,
+    CancelList,
+    Offer,
+    CancelOffer,
+    Mint,
+    Transfer,
+    /// A type this crate doesn't know yet.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl EventType {
+    pub fn to_str(&self) -> &str {
+        match &self {
+            EventType::List => "LIST",
+            EventType::Sale => "SALE",
+            EventType::CancelList => "CANCEL_LIST",
+            EventType::Offer => "OFFER",
+            EventType::CancelOffer => "CANCEL_OFFER",
+            EventType::Mint => "MINT",
+            EventType::Transfer => "TRANSFER",
+            EventType::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub id: u64,
+    pub from: Address,
+    pub to: Option<Address>,
+    pub type_: EventType,
+    pub hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub collection: Option<EventCollection>,
+    pub token: Option<EventToken>,
+    pub order: Option<EventOrder>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCollection {
+    pub address: Address,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventToken {
+    pub token_id: String,
+    pub name: Option<String>,
+    #[serde(rename = "imageURI")]
+    pub image_uri: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventOrder {
+    pub hash: Option<String>,
+    pub is_order_ask: bool,
+    pub signer: Address,
+    pub strategy: Address,
+    pub currency_address: Address,
+    pub price: String,
+    pub start_time: u64,
+    pub end_time: u64,
 }
\ No newline at end of file
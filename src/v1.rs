@@ -0,0 +1,361 @@
+//! Response payloads exactly as version 1 of the LooksRare API sends them.
+//!
+//! The types in `types` are the crate's stable interface. When the API
+//! renames or reshapes a field, only the wire type here and its `From`
+//! conversion change, so code built on `types` keeps compiling.
+use crate::secret::Redacted;
+use crate::types;
+use chrono::{DateTime, Utc};
+use ethers::prelude::{Address, H256, U256};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// An order as returned by `/api/v1/orders`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    pub hash: String,
+    pub collection_address: Address,
+    pub token_id: String,
+    pub is_order_ask: bool,
+    pub signer: Address,
+    pub strategy: Address,
+    pub currency_address: Address,
+    pub amount: String,
+    pub price: String,
+    pub nonce: String,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub min_percentage_to_ask: u64,
+    pub params: String,
+    pub status: String,
+    pub signature: Option<String>,
+    pub v: Option<u8>,
+    pub r: Option<H256>,
+    pub s: Option<H256>,
+}
+
+impl fmt::Debug for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Order")
+            .field("hash", &self.hash)
+            .field("collection_address", &self.collection_address)
+            .field("token_id", &self.token_id)
+            .field("is_order_ask", &self.is_order_ask)
+            .field("signer", &self.signer)
+            .field("strategy", &self.strategy)
+            .field("currency_address", &self.currency_address)
+            .field("amount", &self.amount)
+            .field("price", &self.price)
+            .field("nonce", &self.nonce)
+            .field("start_time", &self.start_time)
+            .field("end_time", &self.end_time)
+            .field("min_percentage_to_ask", &self.min_percentage_to_ask)
+            .field("params", &self.params)
+            .field("status", &self.status)
+            .field("signature", &Redacted(&self.signature))
+            .field("v", &Redacted(&self.v))
+            .field("r", &Redacted(&self.r))
+            .field("s", &Redacted(&self.s))
+            .finish()
+    }
+}
+
+impl From<Order> for types::Order {
+    fn from(order: Order) -> Self {
+        Self {
+            hash: order.hash,
+            collection_address: order.collection_address,
+            token_id: order.token_id,
+            is_order_ask: order.is_order_ask,
+            signer: order.signer,
+            strategy: order.strategy,
+            currency_address: order.currency_address,
+            amount: order.amount,
+            price: order.price,
+            nonce: order.nonce,
+            start_time: order.start_time,
+            end_time: order.end_time,
+            min_percentage_to_ask: order.min_percentage_to_ask,
+            params: order.params,
+            status: order.status,
+            signature: order.signature,
+            v: order.v,
+            r: order.r,
+            s: order.s,
+        }
+    }
+}
+
+/// Collection stats as returned by `/api/v1/collections/stats`, every number a string.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionStats {
+    pub address: Address,
+    pub count_owners: String,
+    pub total_supply: String,
+    pub floor_price: String,
+    pub floor_change_24h: String,
+    pub floor_change_7d: String,
+    pub floor_change_30d: String,
+    pub market_cap: String,
+    pub volume_24h: String,
+    pub average_24h: String,
+    pub count_24h: Option<String>,
+    pub change_24h: String,
+    pub volume_7d: String,
+    pub average_7d: String,
+    pub count_7d: String,
+    pub change_7d: String,
+    pub volume_1m: String,
+    pub average_1m: String,
+    pub count_1m: String,
+    pub change_1m: String,
+    pub volume_3m: String,
+    pub average_3m: String,
+    pub count_3m: String,
+    pub change_3m: String,
+    pub volume_6m: String,
+    pub average_6m: String,
+    pub count_6m: String,
+    pub change_6m: String,
+    pub volume_1y: String,
+    pub average_1y: String,
+    pub count_1y: String,
+    pub change_1y: String,
+    pub volume_all: String,
+    pub average_all: String,
+    pub count_all: String,
+}
+
+impl From<CollectionStats> for types::CollectionStats {
+    fn from(stats: CollectionStats) -> Self {
+        Self {
+            address: stats.address,
+            count_owners: stats.count_owners.parse().unwrap_or_default(),
+            total_supply: stats.total_supply.parse().unwrap_or_default(),
+            floor_price: U256::from_dec_str(&stats.floor_price).ok(),
+            floor_change_24h: stats.floor_change_24h.parse().unwrap_or_default(),
+            floor_change_7d: stats.floor_change_7d.parse().unwrap_or_default(),
+            floor_change_30d: stats.floor_change_30d.parse().unwrap_or_default(),
+            market_cap: wei(&stats.market_cap),
+            volume_24h: wei(&stats.volume_24h),
+            average_24h: wei(&stats.average_24h),
+            count_24h: stats.count_24h.and_then(|count| count.parse().ok()).unwrap_or_default(),
+            change_24h: stats.change_24h.parse().unwrap_or_default(),
+            volume_7d: wei(&stats.volume_7d),
+            average_7d: wei(&stats.average_7d),
+            count_7d: stats.count_7d.parse().unwrap_or_default(),
+            change_7d: stats.change_7d.parse().unwrap_or_default(),
+            volume_1m: wei(&stats.volume_1m),
+            average_1m: wei(&stats.average_1m),
+            count_1m: stats.count_1m.parse().unwrap_or_default(),
+            change_1m: stats.change_1m.parse().unwrap_or_default(),
+            volume_3m: wei(&stats.volume_3m),
+            average_3m: wei(&stats.average_3m),
+            count_3m: stats.count_3m.parse().unwrap_or_default(),
+            change_3m: stats.change_3m.parse().unwrap_or_default(),
+            volume_6m: wei(&stats.volume_6m),
+            average_6m: wei(&stats.average_6m),
+            count_6m: stats.count_6m.parse().unwrap_or_default(),
+            change_6m: stats.change_6m.parse().unwrap_or_default(),
+            volume_1y: wei(&stats.volume_1y),
+            average_1y: wei(&stats.average_1y),
+            count_1y: stats.count_1y.parse().unwrap_or_default(),
+            change_1y: stats.change_1y.parse().unwrap_or_default(),
+            volume_all: wei(&stats.volume_all),
+            average_all: wei(&stats.average_all),
+            count_all: stats.count_all.parse().unwrap_or_default(),
+        }
+    }
+}
+
+/// An account as returned by `/api/v1/accounts`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub address: Address,
+    pub name: Option<String>,
+    pub biography: Option<String>,
+    pub website_link: Option<String>,
+    pub instagram_link: Option<String>,
+    pub twitter_link: Option<String>,
+    pub is_verified: bool,
+}
+
+impl From<Account> for types::Account {
+    fn from(account: Account) -> Self {
+        Self {
+            address: account.address,
+            name: account.name,
+            biography: account.biography,
+            website_link: account.website_link,
+            instagram_link: account.instagram_link,
+            twitter_link: account.twitter_link,
+            is_verified: account.is_verified,
+        }
+    }
+}
+
+/// A collection as returned by `/api/v1/collections`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub address: Address,
+    pub owner: Address,
+    pub name: String,
+    pub description: Option<String>,
+    pub symbol: Option<String>,
+    pub type_: String,
+    pub website_link: Option<String>,
+    pub twitter_link: Option<String>,
+    pub discord_link: Option<String>,
+    pub is_verified: bool,
+    pub is_explicit: bool,
+    #[serde(rename = "logoURI")]
+    pub logo_uri: Option<String>,
+    #[serde(rename = "bannerURI")]
+    pub banner_uri: Option<String>,
+}
+
+impl From<Collection> for types::Collection {
+    fn from(collection: Collection) -> Self {
+        Self {
+            address: collection.address,
+            owner: collection.owner,
+            name: collection.name,
+            description: collection.description,
+            symbol: collection.symbol,
+            type_: collection.type_,
+            website_link: collection.website_link,
+            twitter_link: collection.twitter_link,
+            discord_link: collection.discord_link,
+            is_verified: collection.is_verified,
+            is_explicit: collection.is_explicit,
+            logo_uri: collection.logo_uri,
+            banner_uri: collection.banner_uri,
+        }
+    }
+}
+
+/// An event as returned by `/api/v1/events`, with its collection, token and order inlined.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub id: u64,
+    pub from: Address,
+    pub to: Option<Address>,
+    pub type_: types::EventType,
+    pub hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub collection: Option<EventCollection>,
+    pub token: Option<EventToken>,
+    pub order: Option<EventOrder>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCollection {
+    pub address: Address,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventToken {
+    pub token_id: String,
+    pub name: Option<String>,
+    #[serde(rename = "imageURI")]
+    pub image_uri: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventOrder {
+    pub hash: Option<String>,
+    pub is_order_ask: bool,
+    pub signer: Address,
+    pub strategy: Address,
+    pub currency_address: Address,
+    pub price: String,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl From<Event> for types::Event {
+    fn from(event: Event) -> Self {
+        Self {
+            id: event.id,
+            from: event.from,
+            to: event.to,
+            type_: event.type_,
+            hash: event.hash,
+            created_at: event.created_at,
+            collection: event.collection.map(|collection| types::EventCollection {
+                address: collection.address,
+                name: collection.name,
+            }),
+            token: event.token.map(|token| types::EventToken {
+                token_id: token.token_id,
+                name: token.name,
+                image_uri: token.image_uri,
+            }),
+            order: event.order.map(|order| types::EventOrder {
+                hash: order.hash,
+                is_order_ask: order.is_order_ask,
+                signer: order.signer,
+                strategy: order.strategy,
+                currency_address: order.currency_address,
+                price: order.price,
+                start_time: order.start_time,
+                end_time: order.end_time,
+            }),
+        }
+    }
+}
+
+fn wei(amount: &str) -> U256 {
+    U256::from_dec_str(amount).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_collection_stats() {
+        let stats = CollectionStats {
+            count_owners: String::from("5921"),
+            floor_price: String::from("1250000000000000000"),
+            volume_24h: String::from("90000000000000000000"),
+            count_24h: None,
+            change_24h: String::from("-12.5"),
+            ..Default::default()
+        };
+
+        let typed = types::CollectionStats::from(stats);
+        assert_eq!(typed.count_owners, 5921);
+        assert_eq!(typed.floor_price, Some(U256::from(1_250_000_000_000_000_000u64)));
+        assert_eq!(typed.volume_24h, U256::from(90) * U256::exp10(18));
+        assert_eq!(typed.count_24h, 0);
+        assert_eq!(typed.change_24h, -12.5);
+        assert_eq!(typed.market_cap, U256::zero());
+    }
+
+    #[test]
+    fn types_events() {
+        let event: Event = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "from": "0x0000000000000000000000000000000000000001",
+            "type": "SALE",
+            "createdAt": "2022-11-06T14:00:00Z",
+            "token": { "tokenId": "1", "imageURI": "ipfs://QmHash" },
+        }))
+        .unwrap();
+
+        let typed = types::Event::from(event);
+        assert_eq!(typed.type_, types::EventType::Sale);
+        assert_eq!(typed.token.unwrap().image_uri.as_deref(), Some("ipfs://QmHash"));
+        assert!(typed.order.is_none());
+    }
+}
@@ -0,0 +1,255 @@
+use crate::contracts::{LooksRareExchange, ERC1155, ERC20, ERC721};
+use crate::order::MakerOrder;
+use crate::transfer::{self, TokenStandard};
+use crate::types::Network;
+use ethers::prelude::{Address, Middleware, H256, U256};
+use futures::future;
+use std::sync::Arc;
+
+/// Whether an order could be filled given the chain state when it was checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidityReport {
+    pub order_hash: H256,
+    /// Empty if nothing prevents the order from being filled.
+    pub issues: Vec<ValidityIssue>,
+}
+
+impl ValidityReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Something on chain that makes an order fail if filled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidityIssue {
+    /// The nonce was already executed or cancelled.
+    NonceUsed,
+    /// The signer cancelled every order below `min_nonce`.
+    BelowMinNonce { min_nonce: U256 },
+    /// The listed token belongs to someone else.
+    NotOwner { owner: Address },
+    /// The signer holds fewer of the listed ERC-1155 token than the ask sells.
+    InsufficientTokens { balance: U256, amount: U256 },
+    /// The signer hasn't approved the transfer manager for the collection.
+    NotApproved { operator: Address },
+    /// The bid's currency balance doesn't cover its price.
+    InsufficientBalance { balance: U256, price: U256 },
+    /// The exchange's allowance on the bid's currency doesn't cover its price.
+    InsufficientAllowance { allowance: U256, price: U256 },
+    /// A call needed for a check failed, so the order's validity is unknown.
+    CheckFailed(String),
+}
+
+/// What the chain says about an order's signer, nonce and assets.
+#[derive(Clone, Debug, Default)]
+struct ChainState {
+    nonce_used: bool,
+    min_nonce: U256,
+    /// Ask only: the ERC-721 token owner or the signer's ERC-1155 balance,
+    /// and whether the collection's transfer manager is approved.
+    owner: Option<Address>,
+    held: Option<U256>,
+    operator: Address,
+    approved: bool,
+    /// Bid only: the signer's currency balance and the exchange's allowance.
+    balance: U256,
+    allowance: U256,
+}
+
+/// Checks orders against the exchange, collection and currency contracts.
+///
+/// Asks are checked against the transfer manager for the collection's
+/// standard: ownership for ERC-721, the signer's balance for ERC-1155.
+#[derive(Debug)]
+pub struct OrderValidator<M> {
+    network: Network,
+    provider: Arc<M>,
+}
+
+impl<M: Middleware> OrderValidator<M> {
+    pub fn new(network: Network, provider: Arc<M>) -> Self {
+        Self { network, provider }
+    }
+
+    pub async fn validate(&self, order: &MakerOrder) -> ValidityReport {
+        let issues = match self.chain_state(order).await {
+            Ok(state) => assess(order, &state),
+            Err(reason) => vec![ValidityIssue::CheckFailed(reason)],
+        };
+
+        ValidityReport {
+            order_hash: order.hash(),
+            issues,
+        }
+    }
+
+    /// Checks every order concurrently, returning the reports in the same order.
+    pub async fn validate_all(&self, orders: &[MakerOrder]) -> Vec<ValidityReport> {
+        future::join_all(orders.iter().map(|order| self.validate(order))).await
+    }
+
+    async fn chain_state(&self, order: &MakerOrder) -> Result<ChainState, String> {
+        let exchange = LooksRareExchange::new(self.network.exchange(), self.provider.clone());
+        let nonce_used = exchange.is_user_order_nonce_executed_or_cancelled(order.signer, order.nonce);
+        let min_nonce = exchange.user_min_order_nonce(order.signer);
+        let (nonce_used, min_nonce) =
+            futures::try_join!(nonce_used.call(), min_nonce.call()).map_err(|e| e.to_string())?;
+
+        let mut state = ChainState {
+            nonce_used,
+            min_nonce,
+            ..Default::default()
+        };
+
+        if order.is_order_ask {
+            let standard = transfer::detect_standard(self.provider.clone(), order.collection)
+                .await
+                .map_err(|e| e.to_string())?;
+            state.operator = standard.transfer_manager(&self.network);
+
+            match standard {
+                TokenStandard::Erc721 => {
+                    let collection = ERC721::new(order.collection, self.provider.clone());
+                    let owner = collection.owner_of(order.token_id);
+                    let approved = collection.is_approved_for_all(order.signer, state.operator);
+                    let (owner, approved) =
+                        futures::try_join!(owner.call(), approved.call()).map_err(|e| e.to_string())?;
+                    state.owner = Some(owner);
+                    state.approved = approved;
+                }
+                TokenStandard::Erc1155 => {
+                    let collection = ERC1155::new(order.collection, self.provider.clone());
+                    let held = collection.balance_of(order.signer, order.token_id);
+                    let approved = collection.is_approved_for_all(order.signer, state.operator);
+                    let (held, approved) =
+                        futures::try_join!(held.call(), approved.call()).map_err(|e| e.to_string())?;
+                    state.held = Some(held);
+                    state.approved = approved;
+                }
+            }
+        } else {
+            let currency = ERC20::new(order.currency, self.provider.clone());
+            let balance = currency.balance_of(order.signer);
+            let allowance = currency.allowance(order.signer, self.network.exchange());
+            let (balance, allowance) =
+                futures::try_join!(balance.call(), allowance.call()).map_err(|e| e.to_string())?;
+            state.balance = balance;
+            state.allowance = allowance;
+        }
+
+        Ok(state)
+    }
+}
+
+fn assess(order: &MakerOrder, state: &ChainState) -> Vec<ValidityIssue> {
+    let mut issues = vec![];
+
+    if state.nonce_used {
+        issues.push(ValidityIssue::NonceUsed);
+    }
+    if order.nonce < state.min_nonce {
+        issues.push(ValidityIssue::BelowMinNonce { min_nonce: state.min_nonce });
+    }
+
+    if order.is_order_ask {
+        if let Some(owner) = state.owner.filter(|owner| *owner != order.signer) {
+            issues.push(ValidityIssue::NotOwner { owner });
+        }
+        if let Some(balance) = state.held.filter(|held| *held < order.amount) {
+            issues.push(ValidityIssue::InsufficientTokens {
+                balance,
+                amount: order.amount,
+            });
+        }
+        if !state.approved {
+            issues.push(ValidityIssue::NotApproved {
+                operator: state.operator,
+            });
+        }
+    } else {
+        if state.balance < order.price {
+            issues.push(ValidityIssue::InsufficientBalance {
+                balance: state.balance,
+                price: order.price,
+            });
+        }
+        if state.allowance < order.price {
+            issues.push(ValidityIssue::InsufficientAllowance {
+                allowance: state.allowance,
+                price: order.price,
+            });
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderTemplate;
+
+    #[test]
+    fn reports_every_issue_found() {
+        let signer = Address::from_low_u64_be(1);
+        let network = Network::Mainnet;
+        let ask = OrderTemplate::ask(&network, signer, Address::from_low_u64_be(2))
+            .instantiate(U256::from(7), U256::exp10(18), U256::from(3), 1_000);
+
+        let fine = ChainState {
+            min_nonce: U256::from(3),
+            owner: Some(signer),
+            approved: true,
+            ..Default::default()
+        };
+        assert!(assess(&ask, &fine).is_empty());
+
+        let sold = ChainState {
+            nonce_used: true,
+            min_nonce: U256::from(4),
+            owner: Some(Address::from_low_u64_be(9)),
+            approved: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            assess(&ask, &sold),
+            vec![
+                ValidityIssue::NonceUsed,
+                ValidityIssue::BelowMinNonce { min_nonce: U256::from(4) },
+                ValidityIssue::NotOwner { owner: Address::from_low_u64_be(9) },
+            ]
+        );
+
+        let unapproved_1155 = ChainState {
+            min_nonce: U256::from(3),
+            held: Some(U256::zero()),
+            operator: network.transfer_manager_erc1155(),
+            ..Default::default()
+        };
+        assert_eq!(
+            assess(&ask, &unapproved_1155),
+            vec![
+                ValidityIssue::InsufficientTokens {
+                    balance: U256::zero(),
+                    amount: ask.amount,
+                },
+                ValidityIssue::NotApproved {
+                    operator: network.transfer_manager_erc1155(),
+                },
+            ]
+        );
+
+        let bid = OrderTemplate::bid(&network, signer, Address::from_low_u64_be(2))
+            .instantiate(U256::from(7), U256::exp10(18), U256::from(3), 1_000);
+        let unfunded = ChainState {
+            balance: U256::exp10(18),
+            allowance: U256::exp10(17),
+            ..Default::default()
+        };
+        assert!(matches!(
+            assess(&bid, &unfunded)[..],
+            [ValidityIssue::InsufficientAllowance { .. }]
+        ));
+    }
+}
@@ -0,0 +1,753 @@
+//! Polling streams over the API.
+//!
+//! Every stream here is cancellation safe: dropping a pending `next()`, e.g.
+//! when another branch of a `select!` wins, keeps the in-flight poll inside
+//! the stream, and the following `next()` resumes it. Watermarks and cursors
+//! only advance once a poll's items are queued, so nothing is skipped or
+//! emitted twice.
+use crate::annotations::Annotation;
+use crate::api::{EventType, EventsRequest, LooksRareApi, LooksRareApiError, OrdersRequest, Pagination, Sort, Status};
+use crate::book::{self, Quote, TokenBook};
+use crate::constants;
+use crate::limiter::Priority;
+use crate::types::{Event, Order};
+use ethers::prelude::{Address, U256};
+use futures::{stream, Stream, StreamExt};
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Debug)]
+pub struct WatchConfig {
+    /// Time between polls, or the starting point when `adaptive` is set.
+    pub interval: Duration,
+    /// Only emit items at or after this unix timestamp. Defaults to when the watcher starts.
+    pub since: Option<u64>,
+    /// Tune the interval to activity instead of polling at a fixed rate.
+    pub adaptive: Option<AdaptiveInterval>,
+    /// Priority of the watcher's polls under rate limiting. Defaults to `Priority::Low`.
+    pub priority: Priority,
+    /// For order watchers, hold orders this long and emit only the latest per token,
+    /// so a burst of reprices reaches the consumer as one update.
+    pub coalesce: Option<Duration>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            since: None,
+            adaptive: None,
+            priority: Priority::Low,
+            coalesce: None,
+        }
+    }
+}
+
+/// Bounds for an adaptive interval, which halves after a poll that found new
+/// items and doubles after an idle one. `min` must not exceed `max`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdaptiveInterval {
+    pub min: Duration,
+    pub max: Duration,
+}
+
+#[derive(Clone, Debug)]
+struct Pacer {
+    current: Duration,
+    adaptive: Option<AdaptiveInterval>,
+}
+
+impl Pacer {
+    fn new(interval: Duration, adaptive: Option<AdaptiveInterval>) -> Self {
+        let current = match adaptive {
+            Some(bounds) => interval.clamp(bounds.min, bounds.max),
+            None => interval,
+        };
+
+        Self { current, adaptive }
+    }
+
+    fn interval(&self) -> Duration {
+        self.current
+    }
+
+    fn record(&mut self, fresh: usize) {
+        if let Some(bounds) = self.adaptive {
+            self.current = if fresh > 0 {
+                (self.current / 2).max(bounds.min)
+            } else {
+                self.current.saturating_mul(2).min(bounds.max)
+            };
+        }
+    }
+}
+
+/// The point up to which a watcher has emitted items.
+///
+/// Polls ask for everything at or after `timestamp`, so items sharing the
+/// watermark's exact timestamp come back again on the next poll. Their keys
+/// are remembered so they are not emitted twice, while items that land on
+/// that same timestamp later still get through.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Watermark {
+    timestamp: u64,
+    boundary: HashSet<String>,
+}
+
+impl Watermark {
+    pub fn new(timestamp: u64) -> Self {
+        Self {
+            timestamp,
+            boundary: HashSet::new(),
+        }
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Keeps the items not yet emitted, oldest first, and moves the watermark past them.
+    pub fn advance<T>(
+        &mut self,
+        items: Vec<T>,
+        time_of: impl Fn(&T) -> u64,
+        key_of: impl Fn(&T) -> String,
+    ) -> Vec<T> {
+        let mut batch: HashSet<String> = HashSet::new();
+        let mut fresh: Vec<T> = items
+            .into_iter()
+            .filter(|item| {
+                let time = time_of(item);
+                let key = key_of(item);
+                let unseen = time > self.timestamp || (time == self.timestamp && !self.boundary.contains(&key));
+                unseen && batch.insert(key)
+            })
+            .collect();
+        fresh.sort_by_key(&time_of);
+
+        if let Some(latest) = fresh.last().map(&time_of) {
+            if latest > self.timestamp {
+                self.timestamp = latest;
+                self.boundary.clear();
+            }
+            self.boundary.extend(
+                fresh
+                    .iter()
+                    .filter(|item| time_of(item) == latest)
+                    .map(&key_of),
+            );
+        }
+
+        fresh
+    }
+}
+
+/// Streams orders matching `req` as they appear, querying only orders whose
+/// `start_time` is at or after the watermark on each poll.
+///
+/// Orders created with a `start_time` earlier than the watermark are not seen.
+pub fn watch_orders(
+    api: Arc<LooksRareApi>,
+    req: OrdersRequest,
+    config: WatchConfig,
+) -> impl Stream<Item = Result<Order, LooksRareApiError>> {
+    let api = Arc::new(api.with_priority(config.priority));
+    let fetch = move |watermark: u64| {
+        let api = api.clone();
+        let mut req = req.clone();
+        req.start_time = Some(watermark);
+        async move { api.get_all_orders(req).await }
+    };
+
+    let orders = poll(
+        fetch,
+        Pacer::new(config.interval, config.adaptive),
+        Watermark::new(config.since.unwrap_or_else(unix_now)),
+        |order: &Order| order.start_time,
+        |order: &Order| order.hash.clone(),
+    );
+
+    coalesce_by_token(orders, config.coalesce)
+}
+
+/// Streams events matching `req` as they appear, oldest first.
+///
+/// The events endpoint has no time filter, so each poll reads the newest page
+/// and the watermark drops what was already emitted.
+pub fn watch_events(
+    api: Arc<LooksRareApi>,
+    req: EventsRequest,
+    config: WatchConfig,
+) -> impl Stream<Item = Result<Event, LooksRareApiError>> {
+    let api = Arc::new(api.with_priority(config.priority));
+    let fetch = move |_watermark: u64| {
+        let api = api.clone();
+        let req = req.clone();
+        async move { api.get_events(req).await }
+    };
+
+    poll(
+        fetch,
+        Pacer::new(config.interval, config.adaptive),
+        Watermark::new(config.since.unwrap_or_else(unix_now)),
+        |event: &Event| u64::try_from(event.created_at.timestamp()).unwrap_or_default(),
+        |event: &Event| event.id.to_string(),
+    )
+}
+
+/// Streams the mints of a collection as they appear.
+pub fn watch_mints(
+    api: Arc<LooksRareApi>,
+    collection: Address,
+    config: WatchConfig,
+) -> impl Stream<Item = Result<Event, LooksRareApiError>> {
+    let req = EventsRequest {
+        collection: Some(collection),
+        event_type: Some(EventType::Mint),
+        ..Default::default()
+    };
+
+    watch_events(api, req, config)
+}
+
+/// Streams the best ask, best bid and mid price for a token, emitting only when the quote changes.
+///
+/// Each poll snapshots the token's valid orders into an internal book, so
+/// fills and cancellations are reflected as well as new orders. Only offers
+/// placed on this specific token are counted as bids.
+pub fn midprice_stream(
+    api: Arc<LooksRareApi>,
+    collection: Address,
+    token_id: u64,
+    config: WatchConfig,
+) -> impl Stream<Item = Result<Quote, LooksRareApiError>> {
+    let api = Arc::new(api.with_priority(config.priority));
+    let state = QuoteState {
+        api,
+        collection,
+        token_id,
+        pacer: Pacer::new(config.interval, config.adaptive),
+        book: TokenBook::new(),
+        last: None,
+        polled: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if state.polled {
+                tokio::time::sleep(state.pacer.interval()).await;
+            }
+            state.polled = true;
+
+            let asks = token_orders(state.collection, state.token_id, true);
+            let bids = token_orders(state.collection, state.token_id, false);
+            let (asks, bids) = match futures::try_join!(state.api.get_orders(asks), state.api.get_orders(bids)) {
+                Ok(orders) => orders,
+                Err(e) => return Some((Err(e), state)),
+            };
+
+            state.book.replace(asks, bids);
+            let quote = state.book.quote(unix_now());
+            let changed = state.last.as_ref() != Some(&quote);
+            state.pacer.record(usize::from(changed));
+
+            if changed {
+                state.last = Some(quote.clone());
+                return Some((Ok(quote), state));
+            }
+        }
+    })
+}
+
+struct QuoteState {
+    api: Arc<LooksRareApi>,
+    collection: Address,
+    token_id: u64,
+    pacer: Pacer,
+    book: TokenBook,
+    last: Option<Quote>,
+    polled: bool,
+}
+
+fn token_orders(collection: Address, token_id: u64, is_order_ask: bool) -> OrdersRequest {
+    OrdersRequest {
+        is_order_ask: Some(is_order_ask),
+        collection: Some(collection),
+        token_id: Some(token_id),
+        status: Some(vec![Status::Valid]),
+        pagination: Some(Pagination {
+            first: Some(constants::MAX_ORDERS_PER_PAGE),
+            cursor: None,
+        }),
+        sort: Some(if is_order_ask { Sort::PriceAsc } else { Sort::PriceDesc }),
+        ..Default::default()
+    }
+}
+
+/// Another bidder's offer above the watched signer's best bid on a token.
+#[derive(Clone, Debug)]
+pub struct Outbid {
+    pub order: Order,
+    pub price: U256,
+    pub own_price: U256,
+    /// The hash of the signer's best offer.
+    pub own_hash: String,
+    /// The annotation on the signer's best offer, if the API has an annotation store.
+    pub own_annotation: Option<Annotation>,
+    /// How far the competing offer is above the signer's.
+    pub delta: U256,
+}
+
+/// Streams an `Outbid` whenever the best offer from another bidder on a token
+/// rises above `signer`'s best offer on it.
+///
+/// Nothing is emitted while the signer has no live offer on the token, and the
+/// same competing offer is only reported once.
+pub fn watch_competing_bids(
+    api: Arc<LooksRareApi>,
+    signer: Address,
+    collection: Address,
+    token_id: u64,
+    config: WatchConfig,
+) -> impl Stream<Item = Result<Outbid, LooksRareApiError>> {
+    let api = Arc::new(api.with_priority(config.priority));
+    let state = BidState {
+        api,
+        signer,
+        collection,
+        token_id,
+        pacer: Pacer::new(config.interval, config.adaptive),
+        reported: None,
+        polled: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if state.polled {
+                tokio::time::sleep(state.pacer.interval()).await;
+            }
+            state.polled = true;
+
+            let bids = match state.api.get_orders(token_orders(state.collection, state.token_id, false)).await {
+                Ok(bids) => bids,
+                Err(e) => return Some((Err(e), state)),
+            };
+
+            let found = outbid(&bids, state.signer, unix_now())
+                .filter(|found| state.reported.as_ref() != Some(&found.order.hash));
+            state.pacer.record(usize::from(found.is_some()));
+
+            if let Some(mut found) = found {
+                found.own_annotation = state.api.annotation(&found.own_hash);
+                state.reported = Some(found.order.hash.clone());
+                return Some((Ok(found), state));
+            }
+        }
+    })
+}
+
+struct BidState {
+    api: Arc<LooksRareApi>,
+    signer: Address,
+    collection: Address,
+    token_id: u64,
+    pacer: Pacer,
+    reported: Option<String>,
+    polled: bool,
+}
+
+fn outbid(bids: &[Order], signer: Address, now: u64) -> Option<Outbid> {
+    let live = bids
+        .iter()
+        .filter(|bid| !bid.is_order_ask && book::is_live(bid, now))
+        .filter_map(|bid| Some((bid, U256::from_dec_str(&bid.price).ok()?)));
+
+    let (own, competing): (Vec<_>, Vec<_>) = live.partition(|(bid, _)| bid.signer == signer);
+    let (own, own_price) = own.into_iter().max_by_key(|(_, price)| *price)?;
+    let (order, price) = competing.into_iter().max_by_key(|(_, price)| *price)?;
+
+    (price > own_price).then(|| Outbid {
+        order: order.clone(),
+        price,
+        own_price,
+        own_hash: own.hash.clone(),
+        own_annotation: None,
+        delta: price - own_price,
+    })
+}
+
+struct PollState<T, F> {
+    fetch: F,
+    pacer: Pacer,
+    watermark: Watermark,
+    pending: VecDeque<T>,
+    polled: bool,
+}
+
+fn poll<T, F, Fut>(
+    fetch: F,
+    pacer: Pacer,
+    watermark: Watermark,
+    time_of: fn(&T) -> u64,
+    key_of: fn(&T) -> String,
+) -> impl Stream<Item = Result<T, LooksRareApiError>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, LooksRareApiError>>,
+{
+    let state = PollState {
+        fetch,
+        pacer,
+        watermark,
+        pending: VecDeque::new(),
+        polled: false,
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(item) = state.pending.pop_front() {
+                return Some((Ok(item), state));
+            }
+
+            if state.polled {
+                tokio::time::sleep(state.pacer.interval()).await;
+            }
+            state.polled = true;
+
+            match (state.fetch)(state.watermark.timestamp()).await {
+                Ok(items) => {
+                    let fresh = state.watermark.advance(items, time_of, key_of);
+                    state.pacer.record(fresh.len());
+                    state.pending.extend(fresh);
+                }
+                Err(e) => return Some((Err(e), state)),
+            }
+        }
+    })
+}
+
+struct CoalesceState<S> {
+    inner: Pin<Box<S>>,
+    /// Latest order per token, in the order each token first appeared in the window.
+    window: Vec<Order>,
+    ready: VecDeque<Order>,
+    deadline: Option<Instant>,
+    done: bool,
+}
+
+/// Collapses orders for the same token arriving within `window` of the first into the latest one.
+///
+/// With no window, orders pass straight through. Errors are never held back.
+fn coalesce_by_token<S, E>(inner: S, window: Option<Duration>) -> impl Stream<Item = Result<Order, E>>
+where
+    S: Stream<Item = Result<Order, E>>,
+{
+    let state = CoalesceState {
+        inner: Box::pin(inner),
+        window: vec![],
+        ready: VecDeque::new(),
+        deadline: None,
+        done: false,
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(order) = state.ready.pop_front() {
+                return Some((Ok(order), state));
+            }
+            if state.done {
+                return None;
+            }
+
+            let next = match (window, state.deadline) {
+                (None, _) => return state.inner.next().await.map(|item| (item, state)),
+                (Some(_), None) => Some(state.inner.next().await),
+                // dropping `next()` on timeout is safe, as every watcher stream resumes where it stopped
+                (Some(_), Some(deadline)) => tokio::time::timeout_at(deadline.into(), state.inner.next()).await.ok(),
+            };
+
+            match next {
+                Some(Some(Ok(order))) => {
+                    let token = |held: &Order| {
+                        held.collection_address == order.collection_address && held.token_id == order.token_id
+                    };
+                    match state.window.iter_mut().find(|held| token(held)) {
+                        Some(held) => *held = order,
+                        None => state.window.push(order),
+                    }
+                    if state.deadline.is_none() {
+                        state.deadline = window.map(|window| Instant::now() + window);
+                    }
+                }
+                Some(Some(Err(e))) => return Some((Err(e), state)),
+                Some(None) => {
+                    state.done = true;
+                    state.ready.extend(state.window.drain(..));
+                }
+                None => {
+                    state.deadline = None;
+                    state.ready.extend(state.window.drain(..));
+                }
+            }
+        }
+    })
+}
+
+/// Hands out sequence numbers shared by every stream it is given to.
+///
+/// Numbers are contiguous across all of those streams, so a consumer merging
+/// them can put items back in emission order and spot any it never received.
+#[derive(Clone, Debug, Default)]
+pub struct Sequencer {
+    next: Arc<AtomicU64>,
+}
+
+impl Sequencer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn next(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+/// A watcher item with its sequence number and timestamps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sequenced<T> {
+    pub seq: u64,
+    /// When the source says the item happened, if it carries a time.
+    pub source_time: Option<u64>,
+    /// When the watcher emitted it.
+    pub observed_at: u64,
+    pub item: T,
+}
+
+/// Watcher items that carry the time they happened at.
+pub trait SourceTime {
+    fn source_time(&self) -> Option<u64>;
+}
+
+impl SourceTime for Order {
+    fn source_time(&self) -> Option<u64> {
+        Some(self.start_time)
+    }
+}
+
+impl SourceTime for Event {
+    fn source_time(&self) -> Option<u64> {
+        u64::try_from(self.created_at.timestamp()).ok()
+    }
+}
+
+impl SourceTime for Outbid {
+    fn source_time(&self) -> Option<u64> {
+        self.order.source_time()
+    }
+}
+
+impl SourceTime for Quote {
+    fn source_time(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Numbers the items of any watcher stream from `sequencer`. Errors pass through unnumbered.
+pub fn sequenced<S, T, E>(stream: S, sequencer: Sequencer) -> impl Stream<Item = Result<Sequenced<T>, E>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: SourceTime,
+{
+    stream.map(move |item| {
+        item.map(|item| Sequenced {
+            seq: sequencer.next(),
+            source_time: item.source_time(),
+            observed_at: unix_now(),
+            item,
+        })
+    })
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequences_are_shared_across_streams() {
+        let sequencer = Sequencer::new();
+        let events = |ids: Vec<u64>| {
+            stream::iter(ids.into_iter().map(|id| {
+                Ok::<_, LooksRareApiError>(Event {
+                    id,
+                    ..crate::fixtures::event("SALE", "2022-11-06T14:00:00Z")
+                })
+            }))
+        };
+
+        let first: Vec<_> = futures::executor::block_on(sequenced(events(vec![1, 2]), sequencer.clone()).collect());
+        let second: Vec<_> = futures::executor::block_on(sequenced(events(vec![3]), sequencer).collect());
+
+        let seqs: Vec<u64> = first.iter().chain(&second).map(|item| item.as_ref().unwrap().seq).collect();
+        assert_eq!(seqs, vec![0, 1, 2]);
+        assert_eq!(second[0].as_ref().unwrap().source_time, Some(1_667_743_200));
+    }
+
+    #[test]
+    fn dropped_next_resumes_the_same_poll() {
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        let mut rx = Some(rx);
+        let mut fetches = 0;
+        let fetch = move |_watermark: u64| {
+            fetches += 1;
+            assert_eq!(fetches, 1, "the first poll must not be restarted");
+            let rx = rx.take();
+            async move {
+                if let Some(rx) = rx {
+                    rx.await.ok();
+                }
+                Ok::<_, LooksRareApiError>(vec![item(100, "a")])
+            }
+        };
+        let mut stream = Box::pin(poll(
+            fetch,
+            Pacer::new(Duration::from_secs(60), None),
+            Watermark::new(0),
+            |item: &(u64, String)| item.0,
+            |item: &(u64, String)| item.1.clone(),
+        ));
+
+        futures::executor::block_on(async {
+            assert!(futures::poll!(stream.next()).is_pending());
+            tx.send(()).unwrap();
+            assert_eq!(stream.next().await.unwrap().unwrap(), item(100, "a"));
+        });
+    }
+
+    #[tokio::test]
+    async fn coalesces_reprices_per_token() {
+        let listing = |token_id: &str, price: &str| Ok::<_, LooksRareApiError>(crate::fixtures::listing(token_id, price));
+        let orders = || stream::iter(vec![listing("1", "100"), listing("2", "50"), listing("1", "90"), listing("1", "80")]);
+        let prices = |orders: Vec<Result<Order, LooksRareApiError>>| -> Vec<(String, String)> {
+            orders.into_iter().map(|order| order.unwrap()).map(|order| (order.token_id, order.price)).collect()
+        };
+
+        let coalesced = coalesce_by_token(orders(), Some(Duration::from_millis(10))).collect().await;
+        assert_eq!(prices(coalesced), vec![("1".into(), "80".into()), ("2".into(), "50".into())]);
+
+        let passed = coalesce_by_token(orders(), None).collect().await;
+        assert_eq!(prices(passed).len(), 4);
+    }
+
+    fn item(time: u64, key: &str) -> (u64, String) {
+        (time, key.to_string())
+    }
+
+    fn advance(watermark: &mut Watermark, items: Vec<(u64, String)>) -> Vec<(u64, String)> {
+        watermark.advance(items, |item| item.0, |item| item.1.clone())
+    }
+
+    #[test]
+    fn emits_new_items_oldest_first() {
+        let mut watermark = Watermark::new(100);
+
+        let fresh = advance(&mut watermark, vec![item(102, "b"), item(99, "old"), item(101, "a")]);
+
+        assert_eq!(fresh, vec![item(101, "a"), item(102, "b")]);
+        assert_eq!(watermark.timestamp(), 102);
+    }
+
+    #[test]
+    fn does_not_repeat_items_at_the_boundary() {
+        let mut watermark = Watermark::new(100);
+        advance(&mut watermark, vec![item(105, "a"), item(105, "b")]);
+
+        // the next poll asks for >= 105 and gets the same two back, plus a late arrival at 105
+        let fresh = advance(&mut watermark, vec![item(105, "a"), item(105, "b"), item(105, "c")]);
+
+        assert_eq!(fresh, vec![item(105, "c")]);
+        assert_eq!(watermark.timestamp(), 105);
+
+        let fresh = advance(&mut watermark, vec![item(105, "a"), item(105, "c"), item(106, "d")]);
+        assert_eq!(fresh, vec![item(106, "d")]);
+    }
+
+    #[test]
+    fn items_at_the_initial_watermark_are_emitted_once() {
+        let mut watermark = Watermark::new(100);
+
+        assert_eq!(advance(&mut watermark, vec![item(100, "a")]), vec![item(100, "a")]);
+        assert!(advance(&mut watermark, vec![item(100, "a")]).is_empty());
+    }
+
+    #[test]
+    fn drops_duplicates_within_a_poll() {
+        let mut watermark = Watermark::new(0);
+
+        let fresh = advance(&mut watermark, vec![item(1, "a"), item(1, "a")]);
+
+        assert_eq!(fresh, vec![item(1, "a")]);
+    }
+
+    #[test]
+    fn detects_competing_bid_above_own() {
+        let signer = Address::from_low_u64_be(1);
+        let bid = |from: u64, price: &str| {
+            let mut bid = crate::fixtures::order();
+            bid.is_order_ask = false;
+            bid.hash = format!("{}-{}", from, price);
+            bid.signer = Address::from_low_u64_be(from);
+            bid.price = price.to_string();
+            bid
+        };
+        let now = bid(1, "0").start_time;
+
+        let event = outbid(&[bid(1, "100"), bid(2, "90"), bid(3, "130")], signer, now).unwrap();
+        assert_eq!(event.order.signer, Address::from_low_u64_be(3));
+        assert_eq!(event.delta, U256::from(30));
+        assert_eq!(event.own_hash, "1-100");
+
+        assert!(outbid(&[bid(1, "100"), bid(2, "90")], signer, now).is_none());
+        assert!(outbid(&[bid(2, "90")], signer, now).is_none());
+    }
+
+    #[test]
+    fn fixed_interval_never_changes() {
+        let mut pacer = Pacer::new(Duration::from_secs(10), None);
+        pacer.record(5);
+        pacer.record(0);
+        assert_eq!(pacer.interval(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn adaptive_interval_tracks_activity_within_bounds() {
+        let bounds = AdaptiveInterval {
+            min: Duration::from_secs(2),
+            max: Duration::from_secs(30),
+        };
+        let mut pacer = Pacer::new(Duration::from_secs(10), Some(bounds));
+
+        pacer.record(3);
+        assert_eq!(pacer.interval(), Duration::from_secs(5));
+        pacer.record(1);
+        pacer.record(1);
+        assert_eq!(pacer.interval(), Duration::from_secs(2));
+
+        pacer.record(0);
+        assert_eq!(pacer.interval(), Duration::from_secs(4));
+        for _ in 0..10 {
+            pacer.record(0);
+        }
+        assert_eq!(pacer.interval(), Duration::from_secs(30));
+    }
+}